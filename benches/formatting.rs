@@ -0,0 +1,76 @@
+//! Compares formatting a wide object type with the sequential `fmt::Write` path against
+//! formatting its properties in parallel with rayon, as `format_type_to_ts_string` used to.
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use infer_json_stream::{
+    formatting::format_type_to_ts_string, inference::infer_type_from_value, types::InferredType,
+};
+use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
+use serde_json::{Map, Value, json};
+use std::hint::black_box;
+
+/// An object type with `width` properties of primitive, array and nested object types.
+fn wide_object(width: usize) -> InferredType {
+    let properties = (0..width)
+        .map(|i| {
+            let value = match i % 4 {
+                0 => json!(i),
+                1 => json!(format!("value{i}")),
+                2 => json!([true, false]),
+                _ => json!({ "id": i, "name": "a", "tags": ["x"] }),
+            };
+            (format!("property{i}"), value)
+        })
+        .collect::<Map<_, _>>();
+    infer_type_from_value(Value::Object(properties))
+}
+
+/// Formats each property on the rayon thread pool and joins them, as before the `fmt::Write`
+/// rewrite. The keys are valid identifiers, so they are not quoted.
+fn format_object_in_parallel(inferred_type: InferredType) -> String {
+    let InferredType::Object(properties) = inferred_type else {
+        unreachable!("the benchmark input is an object");
+    };
+    let mut sorted = properties.into_iter().collect::<Vec<_>>();
+    sorted.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    let props = sorted
+        .into_par_iter()
+        .map(|(key, prop_def)| {
+            let optional_marker = if prop_def.optional { "?" } else { "" };
+            format!(
+                "  {key}{optional_marker}: {}",
+                format_type_to_ts_string(prop_def.r#type)
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("{{\n{}\n}}", props.join(";\n"))
+}
+
+fn object_properties(c: &mut Criterion) {
+    let mut group = c.benchmark_group("object_properties");
+    for width in [100, 20_000] {
+        let inferred_type = wide_object(width);
+        assert_eq!(
+            format_type_to_ts_string(inferred_type.clone()),
+            format_object_in_parallel(inferred_type.clone())
+        );
+        group.bench_function(format!("sequential/{width}"), |b| {
+            b.iter_batched(
+                || inferred_type.clone(),
+                |inferred_type| black_box(format_type_to_ts_string(inferred_type)),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(format!("parallel/{width}"), |b| {
+            b.iter_batched(
+                || inferred_type.clone(),
+                |inferred_type| black_box(format_object_in_parallel(inferred_type)),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, object_properties);
+criterion_main!(benches);
@@ -1,12 +1,21 @@
-use crate::types::{InferredType, PrimitiveType};
-use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
-use std::borrow::Cow;
+use crate::{
+    generation::json_schema::to_json_schema,
+    inference::merge_types,
+    types::{InferredType, PrimitiveType, PropertyDefinition},
+};
+use serde_json::Value;
+use std::{borrow::Cow, fmt};
+use unicode_ident::{is_xid_continue, is_xid_start};
 
-fn format_property_key(key: &str) -> Cow<'_, str> {
+pub(crate) fn format_property_key(key: &str) -> Cow<'_, str> {
+    /// Follows the ECMAScript `IdentifierName` grammar: `ID_Start`, `_` or `$`, followed by
+    /// `ID_Continue` or `$`.
     fn is_valid_ts_identifier(s: &str) -> bool {
-        s.chars().next().is_some_and(|c| !c.is_numeric())
-            && s.chars()
-                .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        let mut chars = s.chars();
+        chars
+            .next()
+            .is_some_and(|c| is_xid_start(c) || c == '_' || c == '$')
+            && chars.all(|c| is_xid_continue(c) || c == '$')
     }
 
     if is_valid_ts_identifier(key) {
@@ -16,53 +25,387 @@ fn format_property_key(key: &str) -> Cow<'_, str> {
     }
 }
 
+/// Options controlling how TypeScript type expressions are written.
+#[derive(Debug, Default, Clone)]
+pub struct FormatOptions {
+    pub property_order: PropertyOrder,
+    /// Precede each object property with a comment holding its JSON Schema equivalent.
+    pub json_schema_comments: bool,
+    /// Precede each object property with a TSDoc comment derived from its key.
+    pub property_docs_from_key: bool,
+    /// Precede each property and content type whose type is `any` with a line comment holding
+    /// this text.
+    pub any_comment: Option<String>,
+    /// With `any_comment`, also disable the `@typescript-eslint/no-explicit-any` lint for the
+    /// following line.
+    pub suppress_any_lint: bool,
+    /// Open each object type with a `[key: string]` index signature typed as the union of its
+    /// property types.
+    pub index_signatures: bool,
+    /// Precede each object property with a line comment listing its collected examples.
+    pub example_comments: bool,
+}
+
+/// The order in which object properties are emitted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PropertyOrder {
+    #[default]
+    Alphabetical,
+    /// The order in which properties were first observed in the input.
+    #[cfg(feature = "preserve-order")]
+    Insertion,
+}
+
+/// Formats an inferred type as a TypeScript type expression.
+///
+/// This is a thin wrapper over [`write_type_to_ts`] that avoids allocating for primitive types.
 pub fn format_type_to_ts_string(inferred_type: InferredType) -> Cow<'static, str> {
+    format_type_to_ts_string_with_options(inferred_type, &FormatOptions::default())
+}
+
+pub fn format_type_to_ts_string_with_options(
+    inferred_type: InferredType,
+    options: &FormatOptions,
+) -> Cow<'static, str> {
     match inferred_type {
         InferredType::Primitive(prim_type) => Cow::Borrowed(prim_type.as_str()),
         InferredType::Any => Cow::Borrowed("any"),
-        InferredType::PrimitiveUnion(types) => {
-            let type_strings: Vec<&str> = types.iter().map(PrimitiveType::as_str).collect();
-            Cow::Owned(type_strings.join(" | "))
+        inferred_type => {
+            let mut output = String::new();
+            write_type_to_ts_with_options(&mut output, inferred_type, options)
+                .expect("writing to a String cannot fail");
+            Cow::Owned(output)
         }
+    }
+}
+
+/// Writes an inferred type as a TypeScript type expression to any [`fmt::Write`] implementor.
+pub fn write_type_to_ts<W: fmt::Write>(w: &mut W, inferred_type: InferredType) -> fmt::Result {
+    write_type_to_ts_with_options(w, inferred_type, &FormatOptions::default())
+}
+
+pub fn write_type_to_ts_with_options<W: fmt::Write>(
+    w: &mut W,
+    inferred_type: InferredType,
+    options: &FormatOptions,
+) -> fmt::Result {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => w.write_str(prim_type.as_str()),
+        InferredType::Any => w.write_str("any"),
+        InferredType::PrimitiveUnion(types) => write_primitives(w, &types, " | "),
         InferredType::PrimitiveTuple(types) => {
-            if types.is_empty() {
-                return Cow::Borrowed("[]");
+            w.write_char('[')?;
+            write_primitives(w, &types, ", ")?;
+            w.write_char(']')
+        }
+        InferredType::TupleUnion(tuples) => {
+            for (i, types) in tuples.iter().enumerate() {
+                if i > 0 {
+                    w.write_str(" | ")?;
+                }
+                w.write_char('[')?;
+                write_primitives(w, types, ", ")?;
+                w.write_char(']')?;
             }
-            let type_strings: Vec<&str> = types.iter().map(PrimitiveType::as_str).collect();
-            Cow::Owned(format!("[{}]", type_strings.join(", ")))
+            Ok(())
         }
         InferredType::Array(item_type) => {
-            Cow::Owned(format!("Array<{}>", format_type_to_ts_string(*item_type)))
+            w.write_str("Array<")?;
+            write_type_to_ts_with_options(w, *item_type, options)?;
+            w.write_char('>')
         }
         InferredType::Object(properties) => {
             if properties.is_empty() {
-                return Cow::Borrowed("object");
+                return w.write_str("object");
             }
 
-            let mut sorted = properties.into_iter().collect::<Vec<_>>();
-            sorted.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
-            let props = sorted
-                .into_par_iter()
-                .map(|(key, prop_def)| {
-                    let optional_marker = if prop_def.optional { "?" } else { "" };
-                    format!(
-                        "  {}{}: {}",
-                        format_property_key(&key),
-                        optional_marker,
-                        format_type_to_ts_string(prop_def.r#type)
-                    )
-                })
-                .collect::<Vec<_>>();
-            Cow::Owned(format!("{{\n{}\n}}", props.join(";\n")))
+            let mut properties = properties.into_iter().collect::<Vec<_>>();
+            if options.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+            }
+            w.write_str("{\n")?;
+            if options.index_signatures {
+                w.write_str("  [key: string]: ")?;
+                write_type_to_ts_with_options(w, index_signature_type(&properties), options)?;
+                w.write_str(";\n")?;
+            }
+            for (i, (key, prop_def)) in properties.into_iter().enumerate() {
+                if i > 0 {
+                    w.write_str(";\n")?;
+                }
+                if options.json_schema_comments {
+                    write_json_schema_comment(w, &key, &prop_def.r#type)?;
+                }
+                if options.property_docs_from_key {
+                    writeln!(
+                        w,
+                        "  /** {} */",
+                        key_to_sentence(&key).replace("*/", "*\\/")
+                    )?;
+                }
+                if options.example_comments && !prop_def.examples.is_empty() {
+                    let examples = prop_def
+                        .examples
+                        .iter()
+                        .map(Value::to_string)
+                        .collect::<Vec<_>>();
+                    writeln!(w, "  // e.g.: {}", examples.join(", "))?;
+                }
+                w.write_str(&format_any_comment(&prop_def.r#type, options, "  "))?;
+                let optional_marker = if prop_def.optional { "?" } else { "" };
+                write!(w, "  {}{}: ", format_property_key(&key), optional_marker)?;
+                write_type_to_ts_with_options(w, prop_def.r#type, options)?;
+            }
+            w.write_str("\n}")
+        }
+        InferredType::ComplexUnion(members) => {
+            for (i, member) in members.into_iter().enumerate() {
+                if i > 0 {
+                    w.write_str(" | ")?;
+                }
+                write_type_to_ts_with_options(w, member, options)?;
+            }
+            Ok(())
+        }
+        InferredType::Optional(inner) => {
+            write_type_to_ts_with_options(w, *inner, options)?;
+            w.write_str(" | undefined")
         }
         InferredType::NullableObj(obj) => {
-            let inner_type = format_type_to_ts_string(*obj);
-            Cow::Owned(format!("{inner_type} | null"))
+            write_type_to_ts_with_options(w, *obj, options)?;
+            w.write_str(" | null")
         }
+        InferredType::Branded { name, .. } | InferredType::Reference(name) => w.write_str(&name),
         InferredType::Never => unreachable!(),
     }
 }
 
+/// The type of an index signature that every property in `properties` conforms to: the union of
+/// their types, including `undefined` if any of them is optional.
+fn index_signature_type(properties: &[(String, PropertyDefinition)]) -> InferredType {
+    let union = properties
+        .iter()
+        .map(|(_, prop_def)| prop_def.r#type.clone())
+        .reduce(merge_types)
+        .expect("empty objects are formatted as `object`");
+    if properties.iter().any(|(_, prop_def)| prop_def.optional) && union != InferredType::Any {
+        InferredType::Optional(Box::new(union))
+    } else {
+        union
+    }
+}
+
+/// Formats the `any` comment lines, each indented by `indent`, to precede a declaration of
+/// `inferred_type`. Empty unless [`FormatOptions::any_comment`] is set and the type is `any`
+/// outside of any nested object, whose properties get their own comments.
+pub(crate) fn format_any_comment(
+    inferred_type: &InferredType,
+    options: &FormatOptions,
+    indent: &str,
+) -> String {
+    fn has_direct_any(inferred_type: &InferredType) -> bool {
+        match inferred_type {
+            InferredType::Any => true,
+            InferredType::Array(inner)
+            | InferredType::NullableObj(inner)
+            | InferredType::Optional(inner)
+            | InferredType::Branded { inner, .. } => has_direct_any(inner),
+            InferredType::ComplexUnion(members) => members.iter().any(has_direct_any),
+            _ => false,
+        }
+    }
+
+    let Some(comment) = options
+        .any_comment
+        .as_ref()
+        .filter(|_| has_direct_any(inferred_type))
+    else {
+        return String::new();
+    };
+    let mut lines = format!("{indent}// {comment}\n");
+    if options.suppress_any_lint {
+        lines.push_str(&format!(
+            "{indent}// eslint-disable-next-line @typescript-eslint/no-explicit-any\n"
+        ));
+    }
+    lines
+}
+
+fn write_json_schema_comment<W: fmt::Write>(
+    w: &mut W,
+    key: &str,
+    inferred_type: &InferredType,
+) -> fmt::Result {
+    let mut schema = to_json_schema(inferred_type);
+    if let Some(schema) = schema.as_object_mut() {
+        schema.insert("description".to_string(), key.into());
+    }
+    // Keep the keys sorted whether or not `preserve-order` is enabled.
+    schema.sort_all_objects();
+    // `\/` is a valid JSON escape, so the comment cannot be terminated early.
+    writeln!(w, "  /* {} */", schema.to_string().replace("*/", "*\\/"))
+}
+
+/// Minifies generated TypeScript onto a single line, without a trailing newline. Comments are dropped, and whitespace is only
+/// kept where it separates two identifier characters, as in `export type`. String literals are
+/// copied verbatim.
+pub fn compress_typescript(source: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let mut output = String::with_capacity(source.len());
+    let mut pending_space = false;
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' | '`' => {
+                output.push(c);
+                while let Some(next) = chars.next() {
+                    output.push(next);
+                    if next == '\\' {
+                        output.extend(chars.next());
+                    } else if next == c {
+                        break;
+                    }
+                }
+                pending_space = false;
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|&next| next == '\n');
+                pending_space = true;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                while let Some(next) = chars.next() {
+                    if next == '*' && chars.next_if_eq(&'/').is_some() {
+                        break;
+                    }
+                }
+                pending_space = true;
+            }
+            c if c.is_whitespace() => pending_space = true,
+            c => {
+                if pending_space && is_word(c) && output.ends_with(is_word) {
+                    output.push(' ');
+                }
+                pending_space = false;
+                output.push(c);
+            }
+        }
+    }
+    output
+}
+
+/// Renders an inferred type as an indented tree of its variants, for debugging inference:
+///
+/// ```text
+/// Object {
+///   name?: Primitive(String)
+///   tags: Array(
+///     Primitive(String)
+///   )
+/// }
+/// ```
+///
+/// `indent` is the nesting level of the line the type starts on. The first line is not indented,
+/// so that it can follow a property key.
+pub fn display_type_tree(ty: &InferredType, indent: usize) -> String {
+    let inner_indent = "  ".repeat(indent + 1);
+    let closing_indent = "  ".repeat(indent);
+    let wrap = |label: &str, children: &[&InferredType]| {
+        let children = children
+            .iter()
+            .map(|child| format!("{inner_indent}{}\n", display_type_tree(child, indent + 1)))
+            .collect::<String>();
+        format!("{label}(\n{children}{closing_indent})")
+    };
+    match ty {
+        InferredType::Primitive(prim_type) => format!("Primitive({prim_type:?})"),
+        InferredType::Any => "Any".to_string(),
+        InferredType::Never => "Never".to_string(),
+        InferredType::PrimitiveUnion(types) => format!("PrimitiveUnion({types:?})"),
+        InferredType::PrimitiveTuple(types) => format!("PrimitiveTuple({types:?})"),
+        InferredType::TupleUnion(tuples) => format!("TupleUnion({tuples:?})"),
+        InferredType::Reference(name) => format!("Reference({name})"),
+        InferredType::Array(item_type) => wrap("Array", &[item_type]),
+        InferredType::NullableObj(inner) => wrap("NullableObj", &[inner]),
+        InferredType::Optional(inner) => wrap("Optional", &[inner]),
+        InferredType::Branded { name, inner } => wrap(&format!("Branded {name}"), &[inner]),
+        InferredType::ComplexUnion(members) => {
+            wrap("ComplexUnion", &members.iter().collect::<Vec<_>>())
+        }
+        InferredType::Object(properties) => {
+            if properties.is_empty() {
+                return "Object {}".to_string();
+            }
+            let mut sorted = properties.iter().collect::<Vec<_>>();
+            sorted.sort_by_key(|(key, _)| *key);
+            let mut output = "Object {\n".to_string();
+            for (key, prop_def) in sorted {
+                let optional_marker = if prop_def.optional { "?" } else { "" };
+                output.push_str(&format!(
+                    "{inner_indent}{key}{optional_marker}: {}\n",
+                    display_type_tree(&prop_def.r#type, indent + 1)
+                ));
+            }
+            output.push_str(&closing_indent);
+            output.push('}');
+            output
+        }
+    }
+}
+
+/// Converts a property key into a sentence, e.g. `createdAt` and `created_at` into `Created at.`.
+fn key_to_sentence(key: &str) -> String {
+    let chars = key.chars().collect::<Vec<_>>();
+    let mut words = Vec::<String>::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+            continue;
+        }
+        // A new word starts at `aB` and at the last capital of an acronym, as in `IDToken`.
+        let starts_word = c.is_uppercase()
+            && i > 0
+            && (chars[i - 1].is_lowercase()
+                || chars[i - 1].is_ascii_digit()
+                || (chars[i - 1].is_uppercase()
+                    && chars.get(i + 1).is_some_and(|next| next.is_lowercase())));
+        if starts_word && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    words.extend((!word.is_empty()).then_some(word));
+
+    let mut sentence = words
+        .iter()
+        .map(|word| {
+            // Acronyms such as `ID` keep their casing.
+            if word.chars().count() > 1 && word.chars().all(char::is_uppercase) {
+                word.clone()
+            } else {
+                word.to_lowercase()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(first) = sentence.chars().next() {
+        sentence.replace_range(..first.len_utf8(), &first.to_uppercase().to_string());
+    }
+    sentence.push('.');
+    sentence
+}
+
+fn write_primitives<W: fmt::Write>(w: &mut W, types: &[PrimitiveType], sep: &str) -> fmt::Result {
+    for (i, prim_type) in types.iter().enumerate() {
+        if i > 0 {
+            w.write_str(sep)?;
+        }
+        w.write_str(prim_type.as_str())?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,5 +418,56 @@ mod tests {
         assert_eq!(format_property_key("with\"quote"), "\"with\\\"quote\"");
         assert_eq!(format_property_key("$special"), "$special");
         assert_eq!(format_property_key("_underscore"), "_underscore");
+        assert_eq!(format_property_key("ユーザー名"), "ユーザー名");
+        assert_eq!(format_property_key("用户"), "用户");
+        assert_eq!(format_property_key("اسم"), "اسم");
+        assert_eq!(format_property_key("名前_2"), "名前_2");
+        assert_eq!(format_property_key("²squared"), "\"²squared\"");
+        assert_eq!(format_property_key("emoji🙂"), "\"emoji🙂\"");
+        assert_eq!(format_property_key(""), "\"\"");
+    }
+
+    #[test]
+    fn test_compress_typescript() {
+        assert_eq!(
+            compress_typescript(
+                "export type LoginContent = {\n  userId: number;\n  timestamp: number\n};\n"
+            ),
+            "export type LoginContent={userId:number;timestamp:number};"
+        );
+        assert_eq!(
+            compress_typescript(
+                "// invalid JSON: \"a  b\"\nexport type A = { type: \"a  // b\", content: B };\n"
+            ),
+            "export type A={type:\"a  // b\",content:B};"
+        );
+        assert_eq!(
+            compress_typescript("  /** Doc. */\n  readonly __brand: \"Id\" | undefined"),
+            "readonly __brand:\"Id\"|undefined"
+        );
+    }
+
+    #[test]
+    fn test_key_to_sentence() {
+        assert_eq!(key_to_sentence("userId"), "User id.");
+        assert_eq!(key_to_sentence("isActive"), "Is active.");
+        assert_eq!(key_to_sentence("createdAt"), "Created at.");
+        assert_eq!(key_to_sentence("created_at"), "Created at.");
+        assert_eq!(key_to_sentence("IDToken"), "ID token.");
+        assert_eq!(key_to_sentence("name"), "Name.");
+    }
+
+    #[test]
+    fn test_write_type_to_ts() {
+        let mut output = String::from("type T = ");
+        write_type_to_ts(
+            &mut output,
+            InferredType::Array(Box::new(InferredType::PrimitiveTuple(vec![
+                PrimitiveType::String,
+                PrimitiveType::Number,
+            ]))),
+        )
+        .unwrap();
+        assert_eq!(output, "type T = Array<[string, number]>");
     }
 }
@@ -1,18 +1,961 @@
 use crate::{
-    formatting::format_type_to_ts_string,
-    inference::{infer_type_from_value, merge_types},
-    types::{InferredType, InputData, PrimitiveType},
+    formatting::{
+        FormatOptions, compress_typescript, format_any_comment, format_property_key,
+        format_type_to_ts_string_with_options,
+    },
+    inference::{InferenceOptions, infer_type_from_value_with_options, merge_types_with_options},
+    transform::{brand_id_fields, filter_properties, fold_recursive_types, prune_rare_properties},
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
 };
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail, ensure};
+use clap::ValueEnum as _;
 use rayon::iter::{IntoParallelIterator as _, ParallelIterator as _};
-use serde_json::Value;
-use std::collections::{BTreeMap, HashMap};
-use stringcase::pascal_case;
+use regex::Regex;
+use serde_json::{Value, json};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+use stringcase::{camel_case, pascal_case, snake_case};
+
+pub mod cerberus;
+mod class_validator;
+mod classes;
+pub mod clojure;
+pub mod csharp;
+pub mod django;
+pub mod effect_schema;
+pub mod elm;
+mod fixtures;
+pub mod haskell;
+pub mod io_ts;
+pub mod java;
+pub mod json_schema;
+pub mod jtd;
+pub mod kotlin;
+pub mod mongoose;
+pub mod openrpc;
+pub mod pydantic;
+pub mod runtypes;
+pub mod split;
+pub mod superstruct;
+pub mod swift;
+pub mod typebox;
+pub mod typeorm;
+pub mod valibot;
+
+/// Options controlling how TypeScript definitions are generated.
+#[derive(Debug, Default, Clone)]
+pub struct GenerationOptions {
+    /// Prepended to every generated content type name.
+    pub type_prefix: String,
+    /// Appended to every generated content type name.
+    pub type_suffix: String,
+    /// Emit `getEventType` and per-event `isXxx` narrowing helpers for the root union.
+    pub emit_union_helpers: bool,
+    /// Emit a `matchEvent` function calling the handler for the event's type, with one required
+    /// handler per event type.
+    pub emit_match_helper: bool,
+    /// Emit a `sendXxx` function for each event type and a `dispatchEvent` function for the root
+    /// union, which send events with `fetch`.
+    pub fetch_helpers: Option<FetchHelpers>,
+    /// Emit Fastify route options for each event type, with the content's JSON Schema as the
+    /// request body schema.
+    pub emit_fastify_schemas: bool,
+    /// The JSON type of the tag values, which determines the discriminant literal type.
+    pub tag_type: TagType,
+    /// Emit `Id`/`_id` suffixed `number`/`string` properties as branded types.
+    pub emit_branded_ids: bool,
+    /// Pair up `<base><request>`/`<base><response>` event types into operation types.
+    pub request_response_pairs: Option<RequestResponseSuffixes>,
+    /// Wrap the output in an ambient `declare module "<name>" { … }` block.
+    pub module_declaration: Option<String>,
+    pub content_merge_strategy: ContentMergeStrategy,
+    /// Properties whose keys match any of these patterns are removed from the output.
+    pub property_filters: Vec<Regex>,
+    /// Top-level content properties present in fewer than this fraction of an event type's
+    /// records are removed from the output.
+    pub rare_property_threshold: Option<f64>,
+    /// How event type keys are converted into generated type names.
+    pub event_name_case: EventNameCase,
+    /// How content type names shared by several event types are disambiguated.
+    pub type_name_collision_strategy: TypeNameCollisionStrategy,
+    pub inference: InferenceOptions,
+    pub format: FormatOptions,
+    /// Emit a `class-validator` decorated `{TypeName}Dto` class for each object content type.
+    pub emit_class_validators: bool,
+    /// Declare object content types as classes with constructor parameter properties instead of
+    /// type aliases.
+    pub emit_class_declarations: bool,
+    /// Add a `toJSON()` method to the classes emitted by
+    /// [`GenerationOptions::emit_class_declarations`].
+    pub class_to_json: bool,
+    /// Emit a `{TypeName}Update` mapped type making every property of each object content type
+    /// optional.
+    pub emit_mapped_types: bool,
+    /// Emit a test fixtures module importing the generated types from this module specifier.
+    pub test_fixtures_import: Option<String>,
+    /// Emit a module checking a representative value of each object content type with
+    /// `satisfies`, importing the generated types from this module specifier.
+    pub satisfies_check_import: Option<String>,
+    /// Emit a JSON Schema document for the root union with this `$id`.
+    pub json_schema_id: Option<String>,
+    /// Check test fixtures with `satisfies` instead of annotating their type.
+    pub fixtures_satisfies: bool,
+    /// Follow every object and array literal in test fixtures with `as const`.
+    pub fixtures_const_assertions: bool,
+    /// Check that the test fixtures cover the root union's event types with a `_typeCheck`
+    /// object.
+    pub fixtures_type_check: bool,
+    /// Fold nested objects with the same property names as an enclosing object into recursive
+    /// types.
+    pub recursive_types: bool,
+    /// Fail with an [`AnyTypeError`] if any inferred type contains `any`.
+    pub error_on_any: bool,
+    /// `Pick<…>` aliases to emit for subsets of content type fields.
+    pub pick_types: Vec<PickSpec>,
+    /// Minify the TypeScript definitions onto a single line.
+    pub compress_output: bool,
+    /// Emit a `typia.createValidate` validator for each content type.
+    pub emit_runtime_types: bool,
+    /// Start the output with a `/** @module <name> */` TypeDoc comment.
+    pub typedoc_module: Option<String>,
+    /// Modifier tags added in a TSDoc comment before every exported type.
+    pub tsdoc_tags: Vec<TsdocTag>,
+}
+
+/// The request the `fetch` helpers send each event as, with a `{ type, content }` JSON body.
+#[derive(Debug, Clone)]
+pub struct FetchHelpers {
+    pub endpoint: String,
+    pub method: HttpMethod,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HttpMethod {
+    #[default]
+    Post,
+    Put,
+}
+
+impl HttpMethod {
+    fn as_str(self) -> &'static str {
+        match self {
+            HttpMethod::Post => "POST",
+            HttpMethod::Put => "PUT",
+        }
+    }
+}
+
+/// A TSDoc modifier tag for generated types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TsdocTag {
+    Public,
+    Sealed,
+    Readonly,
+}
+
+impl TsdocTag {
+    fn as_str(self) -> &'static str {
+        match self {
+            TsdocTag::Public => "@public",
+            TsdocTag::Sealed => "@sealed",
+            TsdocTag::Readonly => "@readonly",
+        }
+    }
+}
+
+/// A `Pick<…>` alias over some fields of a content type, written as `Login:userId,timestamp`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickSpec {
+    /// The event type, matched against either its key or its converted name.
+    pub type_name: String,
+    pub fields: Vec<String>,
+    /// Overrides the default `{TypeName}Without{RemainingFields}` alias name.
+    pub alias: Option<String>,
+}
+
+impl FromStr for PickSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (type_name, fields) = s
+            .split_once(':')
+            .context("Expected `<Type>:<field>,<field>,…`")?;
+        let fields = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        ensure!(!type_name.is_empty(), "Missing type name");
+        ensure!(!fields.is_empty(), "Missing field names");
+        Ok(PickSpec {
+            type_name: type_name.to_string(),
+            fields,
+            alias: None,
+        })
+    }
+}
+
+/// The files produced by a single generation run.
+#[derive(Debug, Default)]
+pub struct GeneratedFiles {
+    pub definitions: String,
+    /// The companion `{root_name}.fixtures.ts` module, when requested and there are object
+    /// content types to build fixtures for.
+    pub test_fixtures: Option<String>,
+    /// The companion `{root_name}.validate.ts` module, when requested and there are object
+    /// content types to check.
+    pub satisfies_check: Option<String>,
+    /// Modules imported by the definitions, keyed by module name, to be written next to them as
+    /// `{name}.ts`.
+    pub modules: BTreeMap<String, String>,
+    /// The companion `{root_name}.schema.json` document, when requested.
+    pub json_schema: Option<String>,
+}
+
+/// Casing applied to event type keys when deriving type names.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EventNameCase {
+    #[default]
+    Pascal,
+    Camel,
+    Snake,
+    /// Use the key as-is, only replacing characters that are invalid in identifiers.
+    Preserve,
+}
+
+/// How event types whose content types would get the same name, such as `user-created` and
+/// `user_created`, are handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TypeNameCollisionStrategy {
+    /// Number the colliding names, e.g. `UserCreatedContent1` and `UserCreatedContent2`.
+    Prefix,
+    /// Append the event type key to the colliding names, with characters that are invalid in
+    /// identifiers replaced by `$`, e.g. `UserCreatedContent_user$created`.
+    Suffix,
+    /// Fail, listing every collision.
+    #[default]
+    Error,
+}
+
+/// How content types are inferred across event types.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ContentMergeStrategy {
+    /// Infer a separate content type for each event type.
+    #[default]
+    PerType,
+    /// Merge every event type's content into a single `UnifiedContent` type.
+    CrossType,
+}
+
+/// A supported output format, selected with `--format`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    #[value(name = "typescript")]
+    TypeScript,
+    IoTs,
+    Openrpc,
+    Superstruct,
+    Runtypes,
+    Valibot,
+    Typebox,
+    #[value(name = "effect")]
+    EffectSchema,
+    Pydantic,
+    Kotlin,
+    Swift,
+    Csharp,
+    Java,
+    Elm,
+    Haskell,
+    ClojureSpec,
+    Jtd,
+    Cerberus,
+    Mongoose,
+    Django,
+    Typeorm,
+}
+
+/// Describes an output format for `--help-formats`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatInfo {
+    pub name: String,
+    pub description: &'static str,
+    /// The output file extension, without the leading dot.
+    pub extension: &'static str,
+    /// The cargo feature required to enable the format, if any.
+    pub feature: Option<&'static str>,
+}
+
+impl OutputFormat {
+    pub fn info(self) -> FormatInfo {
+        let (description, extension, feature) = match self {
+            OutputFormat::TypeScript => ("TypeScript type aliases for each event type", "ts", None),
+            OutputFormat::IoTs => ("io-ts codecs and the types derived from them", "ts", None),
+            OutputFormat::Openrpc => (
+                "OpenRPC document with a method per event type",
+                "json",
+                None,
+            ),
+            OutputFormat::Superstruct => (
+                "superstruct structs and the types inferred from them",
+                "ts",
+                None,
+            ),
+            OutputFormat::Runtypes => (
+                "runtypes definitions and the static types derived from them",
+                "ts",
+                None,
+            ),
+            OutputFormat::Valibot => (
+                "valibot schemas and the types inferred from them",
+                "ts",
+                None,
+            ),
+            OutputFormat::Typebox => (
+                "TypeBox schemas and the static types derived from them",
+                "ts",
+                None,
+            ),
+            OutputFormat::EffectSchema => (
+                "Effect Schema definitions and the types decoded by them",
+                "ts",
+                None,
+            ),
+            OutputFormat::Pydantic => (
+                "Pydantic v2 models and a discriminated union of the event models",
+                "py",
+                None,
+            ),
+            OutputFormat::Kotlin => (
+                "Kotlin data classes and a sealed class with a subclass per event type",
+                "kt",
+                None,
+            ),
+            OutputFormat::Swift => (
+                "Codable Swift structs and an enum with a case per event type",
+                "swift",
+                None,
+            ),
+            OutputFormat::Csharp => (
+                "C# records with System.Text.Json polymorphic attributes on the root record",
+                "cs",
+                None,
+            ),
+            OutputFormat::Java => (
+                "Java classes with Jackson annotations, nested in the root class",
+                "java",
+                None,
+            ),
+            OutputFormat::Elm => (
+                "An Elm module with type aliases and Json.Decode decoders",
+                "elm",
+                None,
+            ),
+            OutputFormat::Haskell => (
+                "A Haskell module with records and Generic-based aeson instances",
+                "hs",
+                None,
+            ),
+            OutputFormat::ClojureSpec => (
+                "A Clojure namespace with clojure.spec definitions",
+                "clj",
+                None,
+            ),
+            OutputFormat::Jtd => (
+                "A JSON Type Definition (RFC 8927) schema discriminated by the tag",
+                "jtd.json",
+                None,
+            ),
+            OutputFormat::Cerberus => ("A Python module with Cerberus schemas", "py", None),
+            OutputFormat::Mongoose => (
+                "TypeScript with Mongoose schemas and document types",
+                "ts",
+                None,
+            ),
+            OutputFormat::Django => ("A Python module with Django models", "py", None),
+            OutputFormat::Typeorm => ("TypeScript with TypeORM entity classes", "ts", None),
+        };
+        FormatInfo {
+            name: self
+                .to_possible_value()
+                .expect("output formats are never skipped")
+                .get_name()
+                .to_string(),
+            description,
+            extension,
+            feature,
+        }
+    }
+
+    /// Describes every output format enabled in this build.
+    pub fn all_info() -> Vec<FormatInfo> {
+        Self::value_variants()
+            .iter()
+            .map(|format| format.info())
+            .collect()
+    }
+}
+
+/// Suffixes identifying the two halves of a request/response event pair.
+#[derive(Debug, Clone)]
+pub struct RequestResponseSuffixes {
+    pub request: String,
+    pub response: String,
+}
 
 pub fn generate_typescript_definitions(
     json_array: Vec<InputData>,
     root_name: &str,
 ) -> Result<String> {
+    generate_typescript_definitions_with_options(
+        json_array,
+        root_name,
+        &GenerationOptions::default(),
+    )
+}
+
+pub fn generate_typescript_definitions_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    Ok(generate_typescript_files(json_array, root_name, options)?.definitions)
+}
+
+/// Generates the TypeScript definitions together with any requested companion files.
+pub fn generate_typescript_files(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<GeneratedFiles> {
+    let InferredEvents {
+        types: mut overall_inferred_types,
+        invalid_json: invalid_json_types,
+        pruned_properties,
+    } = infer_event_types(json_array, options)?;
+
+    let mut brands = BTreeMap::new();
+    if options.emit_branded_ids {
+        for inferred_type in overall_inferred_types.values_mut() {
+            brand_id_fields(inferred_type, &mut brands);
+        }
+    }
+
+    if options.content_merge_strategy == ContentMergeStrategy::CrossType {
+        // These generate per-event-type declarations, which a single content type has none of.
+        let unsupported = [
+            (options.emit_union_helpers, "--emit-union-helpers"),
+            (
+                options.emit_match_helper,
+                "--emit-discriminated-union-helpers",
+            ),
+            (options.fetch_helpers.is_some(), "--emit-fetch-types"),
+            (options.emit_fastify_schemas, "--emit-fastify-schema"),
+            (options.emit_class_validators, "--emit-class-validators"),
+            (options.emit_class_declarations, "--emit-class-interfaces"),
+            (
+                options.request_response_pairs.is_some(),
+                "--emit-request-response-pairs",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, flag)| enabled.then_some(flag))
+        .collect::<Vec<_>>();
+        ensure!(
+            unsupported.is_empty(),
+            "{} cannot be used with --content-merge-strategy cross-type",
+            unsupported.join(", ")
+        );
+
+        // The comments name the event type they are about, since they share one content type.
+        let mut comments = String::new();
+        let pruned_properties = pruned_properties.into_iter().collect::<BTreeMap<_, _>>();
+        for (event_type_key, pruned) in pruned_properties {
+            let pruned = pruned
+                .iter()
+                .map(|(key, ratio)| format!("{key} ({:.1}%)", ratio * 100.0))
+                .collect::<Vec<_>>();
+            comments.push_str(&format!(
+                "// Pruned rare properties of '{event_type_key}': {}\n",
+                pruned.join(", ")
+            ));
+        }
+        let invalid_json_types = invalid_json_types.into_iter().collect::<BTreeMap<_, _>>();
+        for (event_type_key, invalid_json) in invalid_json_types {
+            comments.push_str(&format!(
+                "// The 'content' field of '{event_type_key}' contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+
+        let mut unified_type = overall_inferred_types
+            .into_values()
+            .reduce(|type1, type2| merge_types_with_options(type1, type2, &options.inference))
+            .unwrap_or_else(|| InferredType::Object(PropertyMap::new()));
+        let type_name = format!(
+            "{}UnifiedContent{}",
+            options.type_prefix, options.type_suffix
+        );
+        let mut recursive_types = BTreeMap::new();
+        if options.recursive_types {
+            fold_recursive_types(
+                &mut unified_type,
+                &type_name,
+                &mut recursive_types,
+                &options.inference,
+            );
+        }
+        let fixture_sources = [fixtures::FixtureSource {
+            type_name: type_name.clone(),
+            fixture_name: "exampleUnifiedContent".to_string(),
+            event_type_key: None,
+            inferred_type: &unified_type,
+        }];
+        let test_fixtures = options
+            .test_fixtures_import
+            .as_ref()
+            .and_then(|import_path| {
+                fixtures::format_fixtures(
+                    import_path,
+                    &fixture_sources,
+                    options.fixtures_satisfies,
+                    options.fixtures_const_assertions,
+                    None,
+                )
+            });
+        let satisfies_check = format_satisfies_check(
+            &fixture_sources,
+            test_fixtures.is_some(),
+            root_name,
+            options,
+        );
+        let pick_types = format_pick_types(&options.pick_types, |name| {
+            (name == "UnifiedContent").then_some((type_name.clone(), &unified_type))
+        })?;
+        let json_schema = options.json_schema_id.as_ref().map(|schema_id| {
+            let tag = match options.tag_type {
+                TagType::String => json!({ "type": "string" }),
+                TagType::Number => json!({ "type": "number" }),
+                TagType::Bool => json!({ "type": "boolean" }),
+                TagType::Any => json!({}),
+            };
+            let definitions = recursive_types
+                .iter()
+                .map(|(name, inferred_type)| (name.clone(), inferred_type))
+                .chain([(type_name.clone(), &unified_type)])
+                .collect();
+            format_json_schema_document(
+                schema_id,
+                root_name,
+                json_schema::event_schema(tag, &type_name),
+                definitions,
+            )
+        });
+        let tag_type = match options.tag_type {
+            TagType::String => "string",
+            TagType::Number => "number",
+            TagType::Bool => "boolean",
+            TagType::Any => "unknown",
+        };
+        let update_type =
+            if options.emit_mapped_types && matches!(unified_type, InferredType::Object(_)) {
+                format_update_type(&type_name)
+            } else {
+                String::new()
+            };
+        let ambient = options.module_declaration.is_some();
+        let mut output = String::new();
+        if options.emit_runtime_types && !ambient {
+            output.push_str(TYPIA_IMPORT);
+        }
+        output.push_str(&format_brands(&brands));
+        output.push_str(&format_named_types(recursive_types, &options.format));
+        output.push_str(&comments);
+        output.push_str(&format_any_comment(&unified_type, &options.format, ""));
+        output.push_str(&format!(
+            "export type {type_name} = {};\n\n{update_type}{pick_types}export type {root_name} = {{ type: {tag_type}, content: {type_name} }};\n",
+            format_type_to_ts_string_with_options(unified_type, &options.format)
+        ));
+        if options.emit_runtime_types {
+            output.push_str(&format_typia_validators(
+                &[("UnifiedContent".to_string(), type_name)],
+                ambient,
+            ));
+        }
+        return Ok(GeneratedFiles {
+            definitions: finish_output(output, options),
+            test_fixtures,
+            satisfies_check,
+            modules: BTreeMap::new(),
+            json_schema,
+        });
+    }
+
+    // Maps each operation base name to its (request, response) event type keys.
+    let operations: BTreeMap<String, (String, String)> = match &options.request_response_pairs {
+        Some(suffixes) => overall_inferred_types
+            .keys()
+            .filter_map(|event_type_key| {
+                let base = event_type_key.strip_suffix(&suffixes.request)?;
+                let response_key = format!("{base}{}", suffixes.response);
+                overall_inferred_types
+                    .contains_key(&response_key)
+                    .then(|| (base.to_string(), (event_type_key.clone(), response_key)))
+            })
+            .collect(),
+        None => BTreeMap::new(),
+    };
+    let paired_keys: HashSet<&str> = operations
+        .values()
+        .flat_map(|(request_key, response_key)| [request_key.as_str(), response_key.as_str()])
+        .collect();
+    // Paired event types are named after the event itself, e.g. `UserFetchRequest`.
+    let stem_for = |event_type_key: &str| {
+        if paired_keys.contains(event_type_key) {
+            ""
+        } else {
+            "Content"
+        }
+    };
+    let type_names = TypeNames::resolve(overall_inferred_types.keys(), stem_for, options)?;
+    let type_name_for =
+        |event_type_key: &str| type_names.get(event_type_key, stem_for(event_type_key), options);
+    // Helper and fixture names share the disambiguation of their type name, e.g. `isUserCreated1`
+    // for `UserCreatedContent1`.
+    let helper_name_for = |event_type_key: &str| {
+        format!(
+            "{}{}",
+            event_type_name(event_type_key, EventNameCase::Pascal),
+            type_names.disambiguation(event_type_key)
+        )
+    };
+
+    let helper_events = if options.emit_union_helpers
+        || options.emit_match_helper
+        || options.emit_runtime_types
+        || options.fetch_helpers.is_some()
+    {
+        overall_inferred_types
+            .keys()
+            .map(|event_type_key| HelperEvent {
+                key: event_type_key.clone(),
+                type_name: type_name_for(event_type_key),
+                name: helper_name_for(event_type_key),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let ambient = options.module_declaration.is_some();
+    let mut recursive_types = BTreeMap::new();
+    if options.recursive_types {
+        for (event_type_key, inferred_type) in overall_inferred_types.iter_mut() {
+            fold_recursive_types(
+                inferred_type,
+                &type_name_for(event_type_key),
+                &mut recursive_types,
+                &options.inference,
+            );
+        }
+    }
+
+    let json_schema = options.json_schema_id.as_ref().map(|schema_id| {
+        let events = overall_inferred_types
+            .keys()
+            .map(|event_type_key| {
+                let tag = json!({ "const": options.tag_type.json_value(event_type_key) });
+                json_schema::event_schema(tag, &type_name_for(event_type_key))
+            })
+            .collect::<Vec<_>>();
+        let definitions = recursive_types
+            .iter()
+            .map(|(name, inferred_type)| (name.clone(), inferred_type))
+            .chain(
+                overall_inferred_types
+                    .iter()
+                    .map(|(event_type_key, inferred_type)| {
+                        (type_name_for(event_type_key), inferred_type)
+                    }),
+            )
+            .collect();
+        format_json_schema_document(
+            schema_id,
+            root_name,
+            json!({ "oneOf": events }),
+            definitions,
+        )
+    });
+    let fastify_schemas = if options.emit_fastify_schemas {
+        let routes = overall_inferred_types
+            .iter()
+            .map(|(event_type_key, inferred_type)| {
+                let mut name = helper_name_for(event_type_key);
+                if let Some(first) = name.get(..1) {
+                    name.replace_range(..1, &first.to_lowercase());
+                }
+                (name, json_schema::to_json_schema(inferred_type))
+            })
+            .collect::<Vec<_>>();
+        format_fastify_schemas(routes, ambient)
+    } else {
+        String::new()
+    };
+
+    let pick_types = format_pick_types(&options.pick_types, |name| {
+        overall_inferred_types
+            .iter()
+            .find(|(event_type_key, _)| {
+                *event_type_key == name
+                    || event_type_name(event_type_key, options.event_name_case) == name
+            })
+            .map(|(event_type_key, inferred_type)| (type_name_for(event_type_key), inferred_type))
+    })?;
+
+    let fixture_sources = overall_inferred_types
+        .iter()
+        .map(|(event_type_key, inferred_type)| fixtures::FixtureSource {
+            type_name: type_name_for(event_type_key),
+            fixture_name: format!("example{}", helper_name_for(event_type_key)),
+            event_type_key: Some(event_type_key),
+            inferred_type,
+        })
+        .collect::<Vec<_>>();
+    let test_fixtures = options
+        .test_fixtures_import
+        .as_ref()
+        .and_then(|import_path| {
+            fixtures::format_fixtures(
+                import_path,
+                &fixture_sources,
+                options.fixtures_satisfies,
+                options.fixtures_const_assertions,
+                options.fixtures_type_check.then_some(root_name),
+            )
+        });
+    let satisfies_check = format_satisfies_check(
+        &fixture_sources,
+        test_fixtures.is_some(),
+        root_name,
+        options,
+    );
+
+    let mut validator_decorators = BTreeSet::new();
+    let mut validator_classes = HashMap::new();
+    if options.emit_class_validators {
+        for (event_type_key, inferred_type) in &overall_inferred_types {
+            let class_name = format!("{}Dto", type_name_for(event_type_key));
+            if let Some(class) = class_validator::format_class(
+                &class_name,
+                inferred_type,
+                &options.format,
+                &mut validator_decorators,
+            ) {
+                validator_classes.insert(event_type_key.clone(), class);
+            }
+        }
+    }
+
+    let mut formatted_types = overall_inferred_types
+        .into_par_iter()
+        .map(|(event_type_key, inferred_type)| {
+            let type_name = type_name_for(&event_type_key);
+
+            let mut ts_output = String::new();
+            if let Some(pruned) = pruned_properties.get(&event_type_key) {
+                let pruned = pruned
+                    .iter()
+                    .map(|(key, ratio)| format!("{key} ({:.1}%)", ratio * 100.0))
+                    .collect::<Vec<_>>();
+                ts_output.push_str(&format!(
+                    "// Pruned rare properties: {}\n",
+                    pruned.join(", ")
+                ));
+            }
+            if let Some(invalid_json) = invalid_json_types.get(&event_type_key) {
+                ts_output.push_str(&format!(
+                    "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+                ));
+            }
+            ts_output.push_str(&format_any_comment(&inferred_type, &options.format, ""));
+            let is_object = matches!(inferred_type, InferredType::Object(_));
+            let class = options
+                .emit_class_declarations
+                .then(|| {
+                    classes::format_class_declaration(
+                        &type_name,
+                        &inferred_type,
+                        &options.format,
+                        options.class_to_json,
+                    )
+                })
+                .flatten();
+            ts_output.push_str(&class.unwrap_or_else(|| {
+                format!(
+                    "export type {type_name} = {};\n\n",
+                    format_type_to_ts_string_with_options(inferred_type, &options.format)
+                )
+            }));
+            if options.emit_mapped_types && is_object {
+                ts_output.push_str(&format_update_type(&type_name));
+            }
+            if let Some(class) = validator_classes.get(&event_type_key) {
+                ts_output.push_str(class);
+            }
+
+            let event_type_string = format!(
+                "{{ type: {}, content: {type_name} }}",
+                options.tag_type.format_literal(&event_type_key)
+            );
+            (event_type_key, ts_output, event_type_string)
+        })
+        .collect::<Vec<_>>();
+    // Sort explicitly so the output does not depend on how the parallel results are collected.
+    formatted_types.sort_unstable_by(|(key1, ..), (key2, ..)| key1.cmp(key2));
+    let (ts_output, event_type_strings): (String, Vec<String>) = formatted_types
+        .into_iter()
+        .map(|(_, ts_output, event_type_string)| (ts_output, event_type_string))
+        .unzip();
+
+    let mut output = String::new();
+    if options.emit_runtime_types && !ambient {
+        output.push_str(TYPIA_IMPORT);
+    }
+    if options.emit_fastify_schemas && !ambient {
+        output.push_str(FASTIFY_IMPORT);
+    }
+    if !validator_decorators.is_empty() {
+        output.push_str(&format!(
+            "import {{ {} }} from \"class-validator\";\n\n",
+            validator_decorators
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    output.push_str(&format_brands(&brands));
+    output.push_str(&format_named_types(recursive_types, &options.format));
+    output.push_str(&ts_output);
+    for (base, (request_key, response_key)) in &operations {
+        output.push_str(&format!(
+            "export type {}{}Operation{} = {{ request: {}; response: {} }};\n\n",
+            options.type_prefix,
+            event_type_name(base, options.event_name_case),
+            options.type_suffix,
+            type_name_for(request_key),
+            type_name_for(response_key)
+        ));
+    }
+    output.push_str(&pick_types);
+    output.push_str(&format!(
+        "export type {root_name} = {};\n",
+        event_type_strings.join(" | ")
+    ));
+
+    if options.emit_runtime_types {
+        let validators = helper_events
+            .iter()
+            .map(|event| (event.name.clone(), event.type_name.clone()))
+            .collect::<Vec<_>>();
+        output.push_str(&format_typia_validators(&validators, ambient));
+    }
+    if options.emit_union_helpers {
+        output.push_str(&format_union_helpers(
+            &helper_events,
+            root_name,
+            options.tag_type,
+            ambient,
+        ));
+    }
+    if options.emit_match_helper {
+        output.push_str(&format_match_helper(
+            &helper_events,
+            root_name,
+            options.tag_type,
+            ambient,
+        ));
+    }
+    if let Some(fetch_helpers) = &options.fetch_helpers {
+        output.push_str(&format_fetch_helpers(
+            &helper_events,
+            root_name,
+            options.tag_type,
+            fetch_helpers,
+            ambient,
+        ));
+    }
+    output.push_str(&fastify_schemas);
+
+    Ok(GeneratedFiles {
+        definitions: finish_output(output, options),
+        test_fixtures,
+        satisfies_check,
+        modules: BTreeMap::new(),
+        json_schema,
+    })
+}
+
+/// Formats the satisfies check module when requested, reusing the test fixtures module written
+/// next to it when there is one.
+fn format_satisfies_check(
+    sources: &[fixtures::FixtureSource],
+    has_test_fixtures: bool,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Option<String> {
+    let import_path = options.satisfies_check_import.as_ref()?;
+    let fixtures_import = has_test_fixtures.then(|| format!("./{root_name}.fixtures"));
+    fixtures::format_satisfies_check(import_path, sources, fixtures_import.as_deref())
+}
+
+/// Returned when [`GenerationOptions::error_on_any`] is set and `any` was inferred.
+#[derive(Debug)]
+pub struct AnyTypeError {
+    /// The event type and property path of each `any`, e.g. `("login", "$.meta")`.
+    pub locations: Vec<(String, String)>,
+}
+
+impl fmt::Display for AnyTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Inferred `any` at {} location(s):", self.locations.len())?;
+        for (event_type, path) in &self.locations {
+            write!(f, "\n  {event_type}: {path}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for AnyTypeError {}
+
+/// The content types inferred for each event type, before any output-specific transformations.
+#[derive(Debug)]
+pub struct InferredEvents {
+    pub types: BTreeMap<String, InferredType>,
+    /// The raw `content` of event types whose content was not valid JSON, keyed by event type.
+    /// These are inferred as `string`.
+    pub invalid_json: HashMap<String, String>,
+    /// The properties removed by [`GenerationOptions::rare_property_threshold`], with the fraction
+    /// of records they were present in, keyed by event type.
+    pub pruned_properties: HashMap<String, Vec<(String, f64)>>,
+}
+
+/// Parses each record's content and infers a merged content type per event type, applying the
+/// inference options and property filters shared by every output format.
+pub fn infer_event_types(
+    json_array: Vec<InputData>,
+    options: &GenerationOptions,
+) -> Result<InferredEvents> {
+    let inference = InferenceOptions {
+        max_examples: options.inference.max_examples.max(usize::from(
+            options.test_fixtures_import.is_some() || options.satisfies_check_import.is_some(),
+        )),
+        ..options.inference
+    };
+
     let items = json_array
         .into_par_iter()
         .map(|item| {
@@ -56,17 +999,45 @@ pub fn generate_typescript_definitions(
         },
     );
 
-    let mut overall_inferred_types: BTreeMap<String, InferredType> = type_contents
+    let inferred = type_contents
         .into_par_iter()
         .map(|(event_type, contents)| {
-            let final_type = contents
+            let total = contents.len();
+            let counts = options.rare_property_threshold.map(|threshold| {
+                let mut counts = HashMap::<String, usize>::new();
+                for key in contents
+                    .iter()
+                    .filter_map(Value::as_object)
+                    .flat_map(|obj| obj.keys())
+                {
+                    *counts.entry(key.clone()).or_default() += 1;
+                }
+                (counts, threshold)
+            });
+            let mut final_type = contents
                 .into_par_iter()
-                .map(infer_type_from_value)
-                .reduce(|| InferredType::Never, merge_types);
+                .map(|value| infer_type_from_value_with_options(value, &inference))
+                .reduce(
+                    || InferredType::Never,
+                    |type1, type2| merge_types_with_options(type1, type2, &inference),
+                );
             // `contents` is never empty, so `final_type` will not be `Never`.
-            (event_type, final_type)
+            let pruned = counts
+                .map(|(counts, threshold)| {
+                    prune_rare_properties(&mut final_type, &counts, total, threshold)
+                })
+                .unwrap_or_default();
+            (event_type, final_type, pruned)
         })
-        .collect();
+        .collect::<Vec<_>>();
+    let mut overall_inferred_types = BTreeMap::new();
+    let mut pruned_properties = HashMap::new();
+    for (event_type, final_type, pruned) in inferred {
+        if !pruned.is_empty() {
+            pruned_properties.insert(event_type.clone(), pruned);
+        }
+        overall_inferred_types.insert(event_type, final_type);
+    }
     overall_inferred_types.extend(invalid_json_types.keys().map(|event_type| {
         (
             event_type.clone(),
@@ -74,33 +1045,540 @@ pub fn generate_typescript_definitions(
         )
     }));
 
-    let (ts_output, event_type_strings): (String, Vec<String>) = overall_inferred_types
-        .into_par_iter()
-        .map(|(event_type_key, inferred_type)| {
-            let type_name = format!("{}Content", pascal_case(&event_type_key));
+    if !options.property_filters.is_empty() {
+        for inferred_type in overall_inferred_types.values_mut() {
+            filter_properties(inferred_type, &options.property_filters);
+        }
+    }
 
-            let ts_output = if let Some(invalid_json) = invalid_json_types.get(&event_type_key) {
-                format!(
-                    "// The 'content' field contained invalid JSON: \"{invalid_json}\"\nexport type {type_name} = {};\n\n",
-                    format_type_to_ts_string(inferred_type)
-                )
+    if options.error_on_any {
+        let locations = overall_inferred_types
+            .iter()
+            .flat_map(|(event_type, inferred_type)| {
+                inferred_type
+                    .any_paths()
+                    .into_iter()
+                    .map(|path| (event_type.clone(), path))
+            })
+            .collect::<Vec<_>>();
+        if !locations.is_empty() {
+            return Err(AnyTypeError { locations }.into());
+        }
+    }
+
+    Ok(InferredEvents {
+        types: overall_inferred_types,
+        invalid_json: invalid_json_types,
+        pruned_properties,
+    })
+}
+
+fn format_json_schema_document(
+    schema_id: &str,
+    root_name: &str,
+    root: Value,
+    definitions: Vec<(String, &InferredType)>,
+) -> String {
+    let document = json_schema::schema_document(schema_id, root_name, root, definitions);
+    serde_json::to_string_pretty(&document).expect("serializing a JSON value cannot fail") + "\n"
+}
+
+/// Formats the requested `Pick<…>` aliases. `resolve` maps a spec's type name to the generated
+/// content type name and its inferred type.
+fn format_pick_types<'a>(
+    specs: &[PickSpec],
+    resolve: impl Fn(&str) -> Option<(String, &'a InferredType)>,
+) -> Result<String> {
+    let mut output = String::new();
+    for spec in specs {
+        let Some((type_name, inferred_type)) = resolve(&spec.type_name) else {
+            bail!("Unknown type in pick types: {}", spec.type_name);
+        };
+        let InferredType::Object(properties) = inferred_type else {
+            bail!("Cannot pick fields from non-object type {type_name}");
+        };
+        if let Some(field) = spec
+            .fields
+            .iter()
+            .find(|field| !properties.contains_key(*field))
+        {
+            bail!("{type_name} has no field {field}");
+        }
+
+        let alias = spec.alias.clone().unwrap_or_else(|| {
+            let mut remaining = properties
+                .keys()
+                .filter(|key| !spec.fields.contains(key))
+                .collect::<Vec<_>>();
+            remaining.sort();
+            let stem = pascal_case(&spec.type_name);
+            if remaining.is_empty() {
+                format!("{stem}Pick")
             } else {
-                format!(
-                    "export type {type_name} = {};\n\n",
-                    format_type_to_ts_string(inferred_type)
-                )
-            };
+                let remaining = remaining
+                    .into_iter()
+                    .map(|key| pascal_case(key))
+                    .collect::<String>();
+                format!("{stem}Without{remaining}")
+            }
+        });
+        let fields = spec
+            .fields
+            .iter()
+            .map(|field| Value::from(field.as_str()).to_string())
+            .collect::<Vec<_>>();
+        output.push_str(&format!(
+            "export type {alias} = Pick<{type_name}, {}>;\n\n",
+            fields.join(" | ")
+        ));
+    }
+    Ok(output)
+}
 
-            let event_type_string =
-                format!("{{ type: \"{event_type_key}\", content: {type_name} }}");
-            (ts_output, event_type_string)
+/// Applies the whole-file transformations shared by every generation strategy.
+fn finish_output(output: String, options: &GenerationOptions) -> String {
+    let output = if options.tsdoc_tags.is_empty() {
+        output
+    } else {
+        add_tsdoc_tags(&output, &options.tsdoc_tags)
+    };
+    let output = match &options.module_declaration {
+        Some(module_name) => wrap_in_module_declaration(&output, module_name),
+        None => output,
+    };
+    let output = match &options.typedoc_module {
+        Some(module_name) => format!("/** @module {module_name} */\n\n{output}"),
+        None => output,
+    };
+    if options.compress_output {
+        compress_typescript(&output)
+    } else {
+        output
+    }
+}
+
+/// Precedes every exported type or class with a TSDoc comment holding `tags`.
+fn add_tsdoc_tags(output: &str, tags: &[TsdocTag]) -> String {
+    let comment = format!(
+        "/** {} */\n",
+        tags.iter()
+            .map(|tag| tag.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    let mut tagged = String::with_capacity(output.len());
+    for line in output.split_inclusive('\n') {
+        if ["export type ", "export interface ", "export class "]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            tagged.push_str(&comment);
+        }
+        tagged.push_str(line);
+    }
+    tagged
+}
+
+fn wrap_in_module_declaration(output: &str, module_name: &str) -> String {
+    let body = output
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("  {line}")
+            }
         })
-        .unzip();
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("declare module \"{module_name}\" {{\n{body}\n}}\n")
+}
 
-    let output = format!(
-        "{ts_output}export type {root_name} = {};\n",
-        event_type_strings.join(" | ")
+fn format_named_types(named: BTreeMap<String, InferredType>, options: &FormatOptions) -> String {
+    named
+        .into_iter()
+        .map(|(name, inferred_type)| {
+            format!(
+                "export type {name} = {};\n\n",
+                format_type_to_ts_string_with_options(inferred_type, options)
+            )
+        })
+        .collect()
+}
+
+fn format_brands(brands: &BTreeMap<String, BTreeSet<PrimitiveType>>) -> String {
+    brands
+        .iter()
+        .map(|(name, inner_types)| {
+            let inner = inner_types
+                .iter()
+                .map(PrimitiveType::as_str)
+                .collect::<Vec<_>>();
+            let inner = if let [single] = inner.as_slice() {
+                single.to_string()
+            } else {
+                format!("({})", inner.join(" | "))
+            };
+            format!("export type {name} = {inner} & {{ readonly __brand: \"{name}\" }};\n\n")
+        })
+        .collect()
+}
+
+/// The name of a type generated for an event type, e.g. `LoginContent` for the `Content` stem.
+fn generated_type_name(event_type_key: &str, stem: &str, options: &GenerationOptions) -> String {
+    format!(
+        "{}{}{stem}{}",
+        options.type_prefix,
+        event_type_name(event_type_key, options.event_name_case),
+        options.type_suffix
+    )
+}
+
+/// The generated type names of each event type, disambiguated with
+/// [`GenerationOptions::type_name_collision_strategy`].
+struct TypeNames {
+    /// What is appended to the names of each event type whose names collide, e.g. `1` for
+    /// `UserCreatedContent1`.
+    disambiguations: HashMap<String, String>,
+}
+
+impl TypeNames {
+    /// Resolves the names of `event_type_keys`. `stem_for` picks the stem of each event type's
+    /// main type name, which is the one checked for collisions.
+    fn resolve<'a>(
+        event_type_keys: impl Iterator<Item = &'a String>,
+        stem_for: impl Fn(&str) -> &'static str,
+        options: &GenerationOptions,
+    ) -> Result<Self> {
+        let undisambiguated = |event_type_key: &str| {
+            generated_type_name(event_type_key, stem_for(event_type_key), options)
+        };
+        let type_names = resolve_type_name_collisions(
+            event_type_keys
+                .map(|event_type_key| (event_type_key.clone(), undisambiguated(event_type_key))),
+            options.type_name_collision_strategy,
+        )?;
+        let disambiguations = type_names
+            .into_iter()
+            .filter_map(|(event_type_key, type_name)| {
+                let disambiguation = type_name
+                    .strip_prefix(&undisambiguated(&event_type_key))?
+                    .to_string();
+                (!disambiguation.is_empty()).then_some((event_type_key, disambiguation))
+            })
+            .collect();
+        Ok(Self { disambiguations })
+    }
+
+    /// The disambiguation appended to the names of `event_type_key`, if any.
+    fn disambiguation(&self, event_type_key: &str) -> &str {
+        self.disambiguations
+            .get(event_type_key)
+            .map_or("", String::as_str)
+    }
+
+    /// The name of a type generated for `event_type_key`, e.g. `UserCreatedEvent1` for the
+    /// `Event` stem.
+    fn get(&self, event_type_key: &str, stem: &str, options: &GenerationOptions) -> String {
+        format!(
+            "{}{}",
+            generated_type_name(event_type_key, stem, options),
+            self.disambiguation(event_type_key)
+        )
+    }
+}
+
+/// Maps each event type key to its type name, disambiguating names shared by several keys with
+/// `strategy`.
+fn resolve_type_name_collisions(
+    names: impl Iterator<Item = (String, String)>,
+    strategy: TypeNameCollisionStrategy,
+) -> Result<HashMap<String, String>> {
+    let mut keys_by_name = BTreeMap::<String, Vec<String>>::new();
+    for (event_type_key, type_name) in names {
+        keys_by_name
+            .entry(type_name)
+            .or_default()
+            .push(event_type_key);
+    }
+    let mut collisions = Vec::new();
+    let mut type_names = HashMap::new();
+    for (type_name, event_type_keys) in keys_by_name {
+        if event_type_keys.len() == 1 {
+            type_names.extend(
+                event_type_keys
+                    .into_iter()
+                    .map(|key| (key, type_name.clone())),
+            );
+            continue;
+        }
+        match strategy {
+            TypeNameCollisionStrategy::Prefix => {
+                for (i, event_type_key) in event_type_keys.into_iter().enumerate() {
+                    type_names.insert(event_type_key, format!("{type_name}{}", i + 1));
+                }
+            }
+            TypeNameCollisionStrategy::Suffix => {
+                for event_type_key in event_type_keys {
+                    let suffix = event_type_key
+                        .chars()
+                        .map(|c| {
+                            if c.is_alphanumeric() || c == '_' || c == '$' {
+                                c
+                            } else {
+                                '$'
+                            }
+                        })
+                        .collect::<String>();
+                    type_names.insert(event_type_key.clone(), format!("{type_name}_{suffix}"));
+                }
+            }
+            TypeNameCollisionStrategy::Error => collisions.push((type_name, event_type_keys)),
+        }
+    }
+
+    // Disambiguated names can still clash, e.g. with another event type's name.
+    if collisions.is_empty() {
+        let mut keys_by_name = BTreeMap::<&str, Vec<&str>>::new();
+        for (event_type_key, type_name) in &type_names {
+            keys_by_name
+                .entry(type_name)
+                .or_default()
+                .push(event_type_key);
+        }
+        collisions.extend(
+            keys_by_name
+                .into_iter()
+                .filter(|(_, event_type_keys)| event_type_keys.len() > 1)
+                .map(|(type_name, mut event_type_keys)| {
+                    event_type_keys.sort_unstable();
+                    let event_type_keys = event_type_keys.into_iter().map(str::to_string).collect();
+                    (type_name.to_string(), event_type_keys)
+                }),
+        );
+    }
+    if !collisions.is_empty() {
+        let collisions = collisions
+            .iter()
+            .map(|(type_name, event_type_keys)| {
+                format!("  {type_name}: {}", event_type_keys.join(", "))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!(
+            "Several event types would get the same type name; use --field-name-collision-strategy to disambiguate them:\n{collisions}"
+        );
+    }
+    Ok(type_names)
+}
+
+/// Converts an event type key into the stem of its generated type names.
+fn event_type_name(event_type_key: &str, case: EventNameCase) -> String {
+    let name = match case {
+        EventNameCase::Pascal => pascal_case(event_type_key),
+        EventNameCase::Camel => camel_case(event_type_key),
+        EventNameCase::Snake => snake_case(event_type_key),
+        EventNameCase::Preserve => event_type_key
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '_' || c == '$' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect(),
+    };
+    // Numeric tags would otherwise produce identifiers starting with a digit.
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Type{name}")
+    } else {
+        name
+    }
+}
+
+/// Formats a `{type_name}Update` type with every property of `type_name` optional, spelled out as
+/// a mapped type rather than `Partial<…>`.
+fn format_update_type(type_name: &str) -> String {
+    format!(
+        "export type {type_name}Update = {{\n  [K in keyof {type_name}]?: {type_name}[K]\n}};\n\n"
+    )
+}
+
+const TYPIA_IMPORT: &str = "import typia from \"typia\";\n\n";
+
+/// An event type to generate helper functions for.
+struct HelperEvent {
+    key: String,
+    type_name: String,
+    /// The stem of the helper names, e.g. `Login` for `isLogin`.
+    name: String,
+}
+
+/// Formats a `validate{Name}` validator for each `(Name, TypeName)` pair. typia's transformer
+/// generates the validator bodies at compile time, so ambient declarations only declare their
+/// signatures.
+fn format_typia_validators(validators: &[(String, String)], ambient: bool) -> String {
+    let mut output = "\n".to_string();
+    for (name, type_name) in validators {
+        output.push_str(&if ambient {
+            format!(
+                "export const validate{name}: (input: unknown) => import(\"typia\").IValidation<{type_name}>;\n"
+            )
+        } else {
+            format!("export const validate{name} = typia.createValidate<{type_name}>();\n")
+        });
+    }
+    output
+}
+
+/// Formats a `matchEvent` function taking a handler for each event type. Ambient contexts only
+/// allow declarations, so `ambient` emits the signature without a body.
+fn format_match_helper(
+    events: &[HelperEvent],
+    root_name: &str,
+    tag_type: TagType,
+    ambient: bool,
+) -> String {
+    let mut handlers = String::new();
+    let mut cases = String::new();
+    for HelperEvent {
+        key: event_type_key,
+        type_name,
+        ..
+    } in events
+    {
+        let key = format_property_key(event_type_key);
+        handlers.push_str(&format!("  {key}: (content: {type_name}) => T;\n"));
+        let handler = if key.starts_with('"') {
+            format!("handlers[{key}]")
+        } else {
+            format!("handlers.{key}")
+        };
+        cases.push_str(&format!(
+            "    case {}:\n      return {handler}(event.content);\n",
+            tag_type.format_literal(event_type_key)
+        ));
+    }
+
+    let signature = format!(
+        "\nexport function matchEvent<T>(event: {root_name}, handlers: {{\n{handlers}}}): T"
     );
+    if ambient {
+        format!("{signature};\n")
+    } else {
+        format!("{signature} {{\n  switch (event.type) {{\n{cases}  }}\n}}\n")
+    }
+}
 
-    Ok(output)
+/// Formats a `send{Name}` function for each event type and a `dispatchEvent` function switching
+/// over the root union. Ambient contexts only allow declarations, so `ambient` emits signatures
+/// without bodies.
+fn format_fetch_helpers(
+    events: &[HelperEvent],
+    root_name: &str,
+    tag_type: TagType,
+    fetch_helpers: &FetchHelpers,
+    ambient: bool,
+) -> String {
+    let signature = |name: &str, params: &str| {
+        if ambient {
+            format!("\nexport function {name}({params}): Promise<Response>;\n")
+        } else {
+            format!("\nexport async function {name}({params}): Promise<Response> {{\n")
+        }
+    };
+    let endpoint = Value::from(fetch_helpers.endpoint.as_str());
+    let method = fetch_helpers.method.as_str();
+
+    let mut helpers = String::new();
+    let mut cases = String::new();
+    for HelperEvent {
+        key: event_type_key,
+        type_name,
+        name,
+    } in events
+    {
+        let function_name = format!("send{name}");
+        let literal = tag_type.format_literal(event_type_key);
+        helpers.push_str(&signature(&function_name, &format!("content: {type_name}")));
+        if !ambient {
+            helpers.push_str(&format!(
+                "  return fetch({endpoint}, {{\n    method: \"{method}\",\n    headers: {{ \"Content-Type\": \"application/json\" }},\n    body: JSON.stringify({{ type: {literal}, content }}),\n  }});\n}}\n"
+            ));
+        }
+        cases.push_str(&format!(
+            "    case {literal}:\n      return {function_name}(event.content);\n"
+        ));
+    }
+    helpers.push_str(&signature("dispatchEvent", &format!("event: {root_name}")));
+    if !ambient {
+        helpers.push_str(&format!("  switch (event.type) {{\n{cases}  }}\n}}\n"));
+    }
+    helpers
+}
+
+const FASTIFY_IMPORT: &str = "import type { RouteShorthandOptions } from \"fastify\";\n\n";
+
+/// Formats a `{name}RouteOptions` constant for each `(name, content schema)` pair, validating the
+/// request body against the content schema and responding with `{ status: "ok" }`. Ambient
+/// contexts cannot import, so `ambient` declares the constants with an inline import type.
+fn format_fastify_schemas(routes: Vec<(String, Value)>, ambient: bool) -> String {
+    let mut output = String::new();
+    for (name, body) in routes {
+        if ambient {
+            output.push_str(&format!(
+                "\nexport const {name}RouteOptions: import(\"fastify\").RouteShorthandOptions;\n"
+            ));
+            continue;
+        }
+        let route_options = json!({
+            "schema": {
+                "body": body,
+                "response": {
+                    "200": {
+                        "type": "object",
+                        "properties": { "status": { "const": "ok" } },
+                        "required": ["status"],
+                    },
+                },
+            },
+        });
+        output.push_str(&format!(
+            "\nexport const {name}RouteOptions: RouteShorthandOptions = {};\n",
+            serde_json::to_string_pretty(&route_options)
+                .expect("serializing a JSON value cannot fail")
+        ));
+    }
+    output
+}
+
+/// Formats the union narrowing helpers. Ambient contexts only allow declarations, so `ambient`
+/// emits signatures without bodies.
+fn format_union_helpers(
+    events: &[HelperEvent],
+    root_name: &str,
+    tag_type: TagType,
+    ambient: bool,
+) -> String {
+    let body = |statement: &str| {
+        if ambient {
+            ";\n".to_string()
+        } else {
+            format!(" {{\n  {statement};\n}}\n")
+        }
+    };
+
+    let mut helpers = format!(
+        "\nexport function getEventType<T extends {root_name}>(event: T): T[\"type\"]{}",
+        body("return event.type")
+    );
+    for HelperEvent { key, name, .. } in events {
+        let literal = tag_type.format_literal(key);
+        helpers.push_str(&format!(
+            "\nexport function is{name}(event: {root_name}): event is Extract<{root_name}, {{ type: {literal} }}>{}",
+            body(&format!("return event.type === {literal}"))
+        ));
+    }
+    helpers
 }
@@ -0,0 +1,307 @@
+use super::{
+    GenerationOptions, InferredEvents, TypeNames, infer_event_types, pydantic::python_literal,
+};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::Result;
+use serde_json::Value;
+use stringcase::snake_case;
+
+/// The line length within which dicts and lists are kept on one line.
+const LINE_LENGTH: usize = 88;
+
+pub fn generate_cerberus(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_cerberus_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a Python module with a Cerberus schema for each object content type, e.g.
+/// `LOGIN_CONTENT_SCHEMA`, and a dict mapping each event type to the schema of its events, e.g.
+/// `EVENTS_SCHEMAS`, to validate a document with `Validator(EVENTS_SCHEMAS[document["type"]])`.
+pub fn generate_cerberus_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut definitions = Vec::new();
+    let mut events = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let tag = options.tag_type.json_value(event_type_key);
+        let mut content_rules = if let InferredType::Object(properties) = inferred_type {
+            let schema_name = format!(
+                "{}_SCHEMA",
+                constant_name(&type_names.get(event_type_key, "Content", options))
+            );
+            let comment = invalid_json
+                .get(event_type_key)
+                .map(|invalid_json| {
+                    format!(
+                        "# The 'content' field contained invalid JSON: \"{}\"\n",
+                        invalid_json.replace('\n', " ")
+                    )
+                })
+                .unwrap_or_default();
+            definitions.push(format!(
+                "{comment}{schema_name} = {}\n",
+                schema(properties, options).format(0)
+            ));
+            vec![
+                rule("type", Python::string("dict")),
+                rule("schema", Python::Name(schema_name)),
+            ]
+        } else {
+            rules(inferred_type, options)
+        };
+
+        let mut tag_rules = Vec::new();
+        if let Some(tag_type) = tag_type_name(options.tag_type) {
+            tag_rules.push(rule("type", Python::string(tag_type)));
+        }
+        tag_rules.push(rule(
+            "allowed",
+            Python::List(vec![Python::Literal(python_literal(&tag))]),
+        ));
+        tag_rules.push(rule("required", Python::Bool(true)));
+        content_rules.push(rule("required", Python::Bool(true)));
+        events.push((
+            python_literal(&tag),
+            Python::Dict(vec![
+                rule("type", Python::Dict(tag_rules)),
+                rule("content", Python::Dict(content_rules)),
+            ]),
+        ));
+    }
+
+    definitions.push(format!(
+        "# Maps each event type to the schema of its events.\n{}_SCHEMAS = {}\n",
+        constant_name(root_name),
+        Python::Dict(events).format(0)
+    ));
+    Ok(definitions.join("\n\n"))
+}
+
+/// A Python literal in a schema.
+enum Python {
+    /// A literal formatted by [`python_literal`].
+    Literal(String),
+    Bool(bool),
+    /// A reference to a module-level constant.
+    Name(String),
+    List(Vec<Python>),
+    /// A dict whose keys are Python literals.
+    Dict(Vec<(String, Python)>),
+}
+
+impl Python {
+    fn string(s: &str) -> Self {
+        Python::Literal(python_literal(&Value::from(s)))
+    }
+
+    /// Formats the literal at `depth` levels of indentation, keeping dicts and lists on one line
+    /// when they fit like Black does.
+    fn format(&self, depth: usize) -> String {
+        let inline = self.format_inline();
+        if depth * 4 + inline.len() <= LINE_LENGTH {
+            return inline;
+        }
+        let indent = "    ".repeat(depth + 1);
+        let (open, items, close) = match self {
+            Python::List(items) => (
+                '[',
+                items
+                    .iter()
+                    .map(|item| format!("{indent}{},\n", item.format(depth + 1)))
+                    .collect::<String>(),
+                ']',
+            ),
+            Python::Dict(entries) => (
+                '{',
+                entries
+                    .iter()
+                    .map(|(key, value)| format!("{indent}{key}: {},\n", value.format(depth + 1)))
+                    .collect::<String>(),
+                '}',
+            ),
+            _ => return inline,
+        };
+        format!("{open}\n{items}{}{close}", "    ".repeat(depth))
+    }
+
+    fn format_inline(&self) -> String {
+        match self {
+            Python::Literal(literal) | Python::Name(literal) => literal.clone(),
+            Python::Bool(true) => "True".to_string(),
+            Python::Bool(false) => "False".to_string(),
+            Python::List(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(Python::format_inline)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Python::Dict(entries) => format!(
+                "{{{}}}",
+                entries
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {}", value.format_inline()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// The schema of an object type, with the rules of each property. Optional properties are not
+/// `required`.
+fn schema(properties: &PropertyMap, options: &GenerationOptions) -> Python {
+    let mut properties = properties.iter().collect::<Vec<_>>();
+    if options.format.property_order == PropertyOrder::Alphabetical {
+        properties.sort_by_key(|(key, _)| *key);
+    }
+    Python::Dict(
+        properties
+            .into_iter()
+            .map(|(key, prop_def)| {
+                let mut rules = rules(&prop_def.r#type, options);
+                rules.push(rule("required", Python::Bool(!prop_def.optional)));
+                (
+                    python_literal(&Value::from(key.as_str())),
+                    Python::Dict(rules),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// The rules validating a value of `inferred_type`.
+fn rules(inferred_type: &InferredType, options: &GenerationOptions) -> Vec<(String, Python)> {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_union_rules(&[*prim_type]),
+        InferredType::Any | InferredType::Never => Vec::new(),
+        InferredType::PrimitiveUnion(types) => primitive_union_rules(types),
+        InferredType::PrimitiveTuple(types) => tuple_rules(types),
+        InferredType::TupleUnion(tuples) => vec![rule(
+            "anyof",
+            Python::List(
+                tuples
+                    .iter()
+                    .map(|types| Python::Dict(tuple_rules(types)))
+                    .collect(),
+            ),
+        )],
+        InferredType::Array(item_type) => vec![
+            rule("type", Python::string("list")),
+            rule("schema", Python::Dict(rules(item_type, options))),
+        ],
+        InferredType::Object(properties) => vec![
+            rule("type", Python::string("dict")),
+            rule("schema", schema(properties, options)),
+        ],
+        InferredType::ComplexUnion(members) => vec![rule(
+            "anyof",
+            Python::List(
+                members
+                    .iter()
+                    .map(|member| Python::Dict(rules(member, options)))
+                    .collect(),
+            ),
+        )],
+        InferredType::NullableObj(inner) => nullable(rules(inner, options)),
+        // Optional properties are not `required`, and constraints need custom rules, so both are
+        // validated as their inner type.
+        InferredType::Optional(inner) | InferredType::Branded { inner, .. } => {
+            rules(inner, options)
+        }
+        // Named types are not emitted separately.
+        InferredType::Reference(_) => Vec::new(),
+    }
+}
+
+fn tuple_rules(types: &[PrimitiveType]) -> Vec<(String, Python)> {
+    vec![
+        rule("type", Python::string("list")),
+        rule(
+            "items",
+            Python::List(
+                types
+                    .iter()
+                    .map(|t| Python::Dict(primitive_union_rules(&[*t])))
+                    .collect(),
+            ),
+        ),
+    ]
+}
+
+/// The rules of a union of primitive types, possibly repeated: the `type` of its single non-null
+/// member, or an `anyof` of the members' types. Unions including `null` are `nullable`.
+fn primitive_union_rules(types: &[PrimitiveType]) -> Vec<(String, Python)> {
+    let mut members = types
+        .iter()
+        .filter(|t| **t != PrimitiveType::Null)
+        .copied()
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    members.dedup();
+    let rules = match members.as_slice() {
+        [] => Vec::new(),
+        [member] => vec![rule("type", Python::string(cerberus_type(*member)))],
+        members => vec![rule(
+            "anyof",
+            Python::List(
+                members
+                    .iter()
+                    .map(|member| {
+                        Python::Dict(vec![rule("type", Python::string(cerberus_type(*member)))])
+                    })
+                    .collect(),
+            ),
+        )],
+    };
+    if types.contains(&PrimitiveType::Null) {
+        nullable(rules)
+    } else {
+        rules
+    }
+}
+
+fn rule(name: &str, value: Python) -> (String, Python) {
+    (python_literal(&Value::from(name)), value)
+}
+
+fn nullable(mut rules: Vec<(String, Python)>) -> Vec<(String, Python)> {
+    rules.push(rule("nullable", Python::Bool(true)));
+    rules
+}
+
+fn cerberus_type(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "string",
+        // JSON does not distinguish integers, and `number` accepts both.
+        PrimitiveType::Number => "number",
+        PrimitiveType::Boolean => "boolean",
+        PrimitiveType::Null => unreachable!("null is expressed with `nullable`"),
+    }
+}
+
+fn tag_type_name(tag_type: TagType) -> Option<&'static str> {
+    match tag_type {
+        TagType::String => Some("string"),
+        TagType::Number => Some("number"),
+        TagType::Bool => Some("boolean"),
+        TagType::Any => None,
+    }
+}
+
+/// The SCREAMING_SNAKE_CASE name of a module-level constant for `name`.
+fn constant_name(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
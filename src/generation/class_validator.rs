@@ -0,0 +1,89 @@
+use crate::{
+    formatting::{
+        FormatOptions, PropertyOrder, format_property_key, format_type_to_ts_string_with_options,
+    },
+    types::{InferredType, PrimitiveType},
+};
+use std::collections::BTreeSet;
+
+/// Formats a class mirroring an object content type, with `class-validator` decorators on each
+/// property. Returns `None` for non-object content types.
+///
+/// The names of the decorators used are added to `decorators` so a single import can be emitted.
+pub(super) fn format_class(
+    class_name: &str,
+    inferred_type: &InferredType,
+    options: &FormatOptions,
+    decorators: &mut BTreeSet<&'static str>,
+) -> Option<String> {
+    let InferredType::Object(properties) = inferred_type else {
+        return None;
+    };
+
+    let mut properties = properties.iter().collect::<Vec<_>>();
+    if options.property_order == PropertyOrder::Alphabetical {
+        properties.sort_by_key(|(key, _)| *key);
+    }
+
+    let mut class = format!("export class {class_name} {{\n");
+    for (key, prop_def) in properties {
+        // `@IsOptional()` skips validation for both `undefined` and `null`.
+        if prop_def.optional || allows_empty(&prop_def.r#type) {
+            decorators.insert("IsOptional");
+            class.push_str("  @IsOptional()\n");
+        }
+        if let Some(decorator) = type_decorator(&prop_def.r#type) {
+            decorators.insert(decorator);
+            class.push_str(&format!("  @{decorator}()\n"));
+        }
+        let marker = if prop_def.optional { "?" } else { "!" };
+        class.push_str(&format!(
+            "  {}{marker}: {};\n",
+            format_property_key(key),
+            format_type_to_ts_string_with_options(prop_def.r#type.clone(), options)
+        ));
+    }
+    class.push_str("}\n\n");
+    Some(class)
+}
+
+fn type_decorator(inferred_type: &InferredType) -> Option<&'static str> {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_decorator(*prim_type),
+        InferredType::PrimitiveUnion(types) => {
+            let mut non_null = types.iter().filter(|t| **t != PrimitiveType::Null);
+            match (non_null.next(), non_null.next()) {
+                (Some(prim_type), None) => primitive_decorator(*prim_type),
+                _ => None,
+            }
+        }
+        InferredType::Array(_) | InferredType::PrimitiveTuple(_) | InferredType::TupleUnion(_) => {
+            Some("IsArray")
+        }
+        InferredType::Object(_) | InferredType::Reference(_) => Some("IsObject"),
+        InferredType::NullableObj(inner)
+        | InferredType::Optional(inner)
+        | InferredType::Branded { inner, .. } => type_decorator(inner),
+        InferredType::Any | InferredType::Never | InferredType::ComplexUnion(_) => None,
+    }
+}
+
+fn primitive_decorator(prim_type: PrimitiveType) -> Option<&'static str> {
+    match prim_type {
+        PrimitiveType::String => Some("IsString"),
+        PrimitiveType::Number => Some("IsNumber"),
+        PrimitiveType::Boolean => Some("IsBoolean"),
+        PrimitiveType::Null => None,
+    }
+}
+
+/// Whether the type admits `null` or `undefined`.
+fn allows_empty(inferred_type: &InferredType) -> bool {
+    match inferred_type {
+        InferredType::Primitive(PrimitiveType::Null)
+        | InferredType::NullableObj(_)
+        | InferredType::Optional(_) => true,
+        InferredType::PrimitiveUnion(types) => types.contains(&PrimitiveType::Null),
+        _ => false,
+    }
+}
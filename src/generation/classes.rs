@@ -0,0 +1,113 @@
+use crate::{
+    formatting::{
+        FormatOptions, PropertyOrder, format_property_key, format_type_to_ts_string_with_options,
+    },
+    types::InferredType,
+};
+use std::borrow::Cow;
+
+/// Words that cannot name a constructor parameter in strict mode, which classes always use.
+const RESERVED_WORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Formats a class for an object content type, declaring each property as a constructor parameter
+/// property so that values can be built with `new`. Returns `None` for non-object content types
+/// and for keys that cannot name a parameter.
+///
+/// Required properties come first, since optional parameters must follow them. With `to_json`, a
+/// `toJSON()` method returning the properties as a plain object is added.
+pub(super) fn format_class_declaration(
+    class_name: &str,
+    inferred_type: &InferredType,
+    options: &FormatOptions,
+    to_json: bool,
+) -> Option<String> {
+    let InferredType::Object(properties) = inferred_type else {
+        return None;
+    };
+    if properties.keys().any(|key| {
+        matches!(format_property_key(key), Cow::Owned(_)) || RESERVED_WORDS.contains(&key.as_str())
+    }) {
+        return None;
+    }
+
+    let mut properties = properties.iter().collect::<Vec<_>>();
+    if options.property_order == PropertyOrder::Alphabetical {
+        properties.sort_by_key(|(key, _)| *key);
+    }
+    properties.sort_by_key(|(_, prop_def)| prop_def.optional);
+
+    let mut class = format!("export class {class_name} {{\n");
+    if properties.is_empty() {
+        class.push_str("  constructor() {}\n");
+    } else {
+        class.push_str("  constructor(\n");
+        for (key, prop_def) in &properties {
+            let marker = if prop_def.optional { "?" } else { "" };
+            class.push_str(&format!(
+                "    public {key}{marker}: {},\n",
+                format_type_to_ts_string_with_options(prop_def.r#type.clone(), options)
+            ));
+        }
+        class.push_str("  ) {}\n");
+    }
+    if to_json {
+        let fields = properties
+            .iter()
+            .map(|(key, _)| format!("{key}: this.{key}"))
+            .collect::<Vec<_>>();
+        let object = if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", fields.join(", "))
+        };
+        class.push_str(&format!("\n  toJSON() {{\n    return {object};\n  }}\n"));
+    }
+    class.push_str("}\n\n");
+    Some(class)
+}
@@ -0,0 +1,265 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap},
+};
+use anyhow::Result;
+use serde_json::Value;
+use stringcase::{pascal_case, snake_case};
+
+/// Characters that may appear in a keyword's name besides alphanumerics.
+const KEYWORD_CHARS: &[char] = &['*', '+', '!', '-', '_', '?', '<', '>', '='];
+
+pub fn generate_clojure_spec(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_clojure_spec_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a `<root-name>.spec` namespace with a spec for each event type's content and a
+/// multi-spec for the root union dispatching on `:type`. The specs expect JSON parsed with
+/// keywordized keys. Each object's keys are specced under a namespace named after the object,
+/// e.g. `:events.spec.login-content/user_id`, so that keys shared by several objects can have
+/// different specs.
+pub fn generate_clojure_spec_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let root = kebab_case(root_name);
+    let mut module = Module {
+        ns: format!("{root}.spec"),
+        definitions: Vec::new(),
+    };
+    let mut methods = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!(
+                ";; The 'content' field contained invalid JSON: \"{}\"\n",
+                invalid_json.replace('\n', " ")
+            )
+        });
+        if let InferredType::Object(properties) = inferred_type {
+            module.push_keys(&content_name, properties, options, comment);
+        } else {
+            let spec = module.format_spec(inferred_type, &content_name, options);
+            module.definitions.push(format!(
+                "{}(s/def ::{} {spec})\n",
+                comment.unwrap_or_default(),
+                kebab_case(&content_name)
+            ));
+        }
+
+        let content_key = format!(
+            ":{}.{}/content",
+            module.ns,
+            kebab_case(&type_names.get(event_type_key, "Event", options))
+        );
+        methods.push(format!(
+            "(s/def {content_key} ::{})\n(defmethod {root}-type {} [_]\n  (s/keys :req-un [{content_key}]))\n",
+            kebab_case(&content_name),
+            clojure_literal(&options.tag_type.json_value(event_type_key))
+        ));
+    }
+
+    module
+        .definitions
+        .push(format!("(defmulti {root}-type :type)\n"));
+    module.definitions.extend(methods);
+    module.definitions.push(format!(
+        "(s/def ::{root} (s/multi-spec {root}-type :type))\n"
+    ));
+    Ok(format!(
+        "(ns {}\n  (:require [clojure.spec.alpha :as s]))\n\n{}",
+        module.ns,
+        module.definitions.join("\n")
+    ))
+}
+
+/// The definitions of a namespace, in dependency order.
+struct Module {
+    ns: String,
+    definitions: Vec<String>,
+}
+
+impl Module {
+    /// Adds an `s/keys` spec for an object type, preceded by the specs of its keys. Keys that
+    /// cannot be written as keywords are left out, with a comment.
+    fn push_keys(
+        &mut self,
+        spec_name: &str,
+        properties: &PropertyMap,
+        options: &GenerationOptions,
+        comment: Option<String>,
+    ) {
+        let mut properties = properties.iter().collect::<Vec<_>>();
+        if options.format.property_order == PropertyOrder::Alphabetical {
+            properties.sort_by_key(|(key, _)| *key);
+        }
+
+        let key_ns = format!("{}.{}", self.ns, kebab_case(spec_name));
+        let mut definition = comment.unwrap_or_default();
+        let mut required = Vec::new();
+        let mut optional = Vec::new();
+        for (key, prop_def) in properties {
+            if !is_keyword_name(key) {
+                definition.push_str(&format!(
+                    ";; The key {} cannot be written as a keyword.\n",
+                    Value::from(key.as_str())
+                ));
+                continue;
+            }
+            let nested_name = format!("{spec_name}{}", pascal_case(key));
+            let spec = self.format_spec(&prop_def.r#type, &nested_name, options);
+            let key_spec = format!(":{key_ns}/{key}");
+            definition.push_str(&format!("(s/def {key_spec} {spec})\n"));
+            if prop_def.optional {
+                optional.push(key_spec);
+            } else {
+                required.push(key_spec);
+            }
+        }
+
+        let mut keys = "(s/keys".to_string();
+        for (kind, key_specs) in [("req-un", required), ("opt-un", optional)] {
+            if !key_specs.is_empty() {
+                keys.push_str(&format!(" :{kind} [{}]", key_specs.join(" ")));
+            }
+        }
+        definition.push_str(&format!("(s/def ::{} {keys}))\n", kebab_case(spec_name)));
+        self.definitions.push(definition);
+    }
+
+    /// Formats a spec, adding the definitions it needs under names derived from `spec_name`.
+    fn format_spec(
+        &mut self,
+        inferred_type: &InferredType,
+        spec_name: &str,
+        options: &GenerationOptions,
+    ) -> String {
+        match inferred_type {
+            InferredType::Primitive(prim_type) => primitive_spec(*prim_type).to_string(),
+            InferredType::Any
+            | InferredType::Never
+            | InferredType::Branded { .. }
+            | InferredType::Reference(_) => "any?".to_string(),
+            InferredType::PrimitiveUnion(types) => {
+                let mut members = types
+                    .iter()
+                    .filter(|t| **t != PrimitiveType::Null)
+                    .collect::<Vec<_>>();
+                members.sort_unstable();
+                members.dedup();
+                let union = match members.as_slice() {
+                    [] => return primitive_spec(PrimitiveType::Null).to_string(),
+                    [member] => primitive_spec(**member).to_string(),
+                    members => format!(
+                        "(s/or {})",
+                        members
+                            .iter()
+                            .map(|t| format!(":{} {}", t.as_str(), primitive_spec(**t)))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    ),
+                };
+                if types.contains(&PrimitiveType::Null) {
+                    format!("(s/nilable {union})")
+                } else {
+                    union
+                }
+            }
+            InferredType::PrimitiveTuple(types) => tuple_spec(types),
+            InferredType::TupleUnion(tuples) => format!(
+                "(s/or {})",
+                tuples
+                    .iter()
+                    .enumerate()
+                    .map(|(index, types)| format!(":tuple-{index} {}", tuple_spec(types)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            InferredType::Array(item_type) => format!(
+                "(s/coll-of {})",
+                self.format_spec(item_type, &format!("{spec_name}Item"), options)
+            ),
+            InferredType::Object(properties) => {
+                self.push_keys(spec_name, properties, options, None);
+                format!("::{}", kebab_case(spec_name))
+            }
+            InferredType::ComplexUnion(members) => {
+                let mut tags = Vec::<String>::with_capacity(members.len());
+                let mut branches = Vec::with_capacity(members.len());
+                for member in members {
+                    let (kind, nested_name) = match member {
+                        InferredType::Object(_) => ("object", format!("{spec_name}Object")),
+                        InferredType::Array(_) => ("array", spec_name.to_string()),
+                        _ => ("value", spec_name.to_string()),
+                    };
+                    let mut tag = kind.to_string();
+                    if tags.contains(&tag) {
+                        tag = format!("{kind}-{}", tags.len());
+                    }
+                    let spec = self.format_spec(member, &nested_name, options);
+                    branches.push(format!(":{tag} {spec}"));
+                    tags.push(tag);
+                }
+                format!("(s/or {})", branches.join(" "))
+            }
+            InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+                format!(
+                    "(s/nilable {})",
+                    self.format_spec(inner, spec_name, options)
+                )
+            }
+        }
+    }
+}
+
+fn tuple_spec(types: &[PrimitiveType]) -> String {
+    let mut spec = "(s/tuple".to_string();
+    for t in types {
+        spec.push(' ');
+        spec.push_str(primitive_spec(*t));
+    }
+    spec.push(')');
+    spec
+}
+
+fn primitive_spec(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "string?",
+        PrimitiveType::Number => "number?",
+        PrimitiveType::Boolean => "boolean?",
+        PrimitiveType::Null => "nil?",
+    }
+}
+
+fn kebab_case(name: &str) -> String {
+    snake_case(name).replace('_', "-")
+}
+
+/// Whether `key` can be written as the name of a namespaced keyword.
+fn is_keyword_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || KEYWORD_CHARS.contains(&c))
+        && chars.all(|c| c.is_alphanumeric() || KEYWORD_CHARS.contains(&c) || c == '\'')
+}
+
+/// Formats a tag value as a Clojure literal. Values that are neither strings, numbers nor
+/// booleans, such as objects, are keyed by their JSON serialization.
+fn clojure_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "nil".to_string(),
+        // JSON string escapes are also valid in Clojure.
+        Value::Bool(_) | Value::Number(_) | Value::String(_) => value.to_string(),
+        Value::Array(_) | Value::Object(_) => Value::String(value.to_string()).to_string(),
+    }
+}
@@ -0,0 +1,360 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::{Result, bail};
+use std::collections::HashSet;
+use stringcase::pascal_case;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// Words that can only name a parameter when prefixed with `@`.
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+pub fn generate_csharp(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_csharp_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates C# records for each event type's content and an abstract root record with a sealed
+/// record per event type, annotated for System.Text.Json polymorphic (de)serialization. Nested
+/// objects become records named after their parent and key, e.g. `LoginContentProfile`.
+pub fn generate_csharp_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut definitions = Vec::new();
+    let mut derived_types = String::new();
+    let mut events = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!("// The 'content' field contained invalid JSON: \"{invalid_json}\"\n")
+        });
+        let content_type = if let InferredType::Object(properties) = inferred_type {
+            push_record(
+                &mut definitions,
+                &content_name,
+                properties,
+                options,
+                comment,
+            );
+            content_name
+        } else {
+            // Type aliases are file-scoped, so non-object content is inlined into the event.
+            let content_type = format_type(&mut definitions, inferred_type, &content_name, options);
+            if let Some(comment) = comment {
+                events.push(comment);
+            }
+            content_type
+        };
+
+        let event_name = type_names.get(event_type_key, "Event", options);
+        derived_types.push_str(&format!(
+            "[JsonDerivedType(typeof({event_name}), {})]\n",
+            discriminator(options.tag_type, event_type_key)?
+        ));
+        events.push(format!(
+            "public sealed record {event_name}([property: JsonPropertyName(\"content\")] {content_type} Content) : {root_name};\n"
+        ));
+    }
+    definitions.push(format!(
+        "[JsonPolymorphic(TypeDiscriminatorPropertyName = \"type\")]\n{derived_types}public abstract record {root_name};\n"
+    ));
+    definitions.extend(events);
+
+    Ok(format!(
+        "#nullable enable\n\nusing System.Collections.Generic;\nusing System.Text.Json.Serialization;\n\n{}",
+        definitions.join("\n")
+    ))
+}
+
+/// Adds a positional record for an object type, after the records of its nested objects.
+/// Optional parameters default to `null`, so they follow the required ones.
+fn push_record(
+    definitions: &mut Vec<String>,
+    record_name: &str,
+    properties: &PropertyMap,
+    options: &GenerationOptions,
+    comment: Option<String>,
+) {
+    let comment = comment.unwrap_or_default();
+    if properties.is_empty() {
+        definitions.push(format!("{comment}public record {record_name}();\n"));
+        return;
+    }
+    let mut properties = properties.iter().collect::<Vec<_>>();
+    if options.format.property_order == PropertyOrder::Alphabetical {
+        properties.sort_by_key(|(key, _)| *key);
+    }
+    properties.sort_by_key(|(_, prop_def)| prop_def.optional);
+
+    let mut names = HashSet::new();
+    let mut parameters = Vec::with_capacity(properties.len());
+    for (key, prop_def) in properties {
+        let mut name = parameter_name(key);
+        while !names.insert(name.clone()) {
+            name.push('_');
+        }
+        let mut param_type = format_type(
+            definitions,
+            &prop_def.r#type,
+            &name_for(record_name, &name),
+            options,
+        );
+        let default = if prop_def.optional {
+            param_type = nullable(param_type);
+            " = null"
+        } else {
+            ""
+        };
+        parameters.push(format!(
+            "    [property: JsonPropertyName({})] {param_type} {name}{default}",
+            string_literal(key)
+        ));
+    }
+    definitions.push(format!(
+        "{comment}public record {record_name}(\n{});\n",
+        parameters.join(",\n")
+    ));
+}
+
+/// The name of a record nested in `record_name` for the parameter `name`.
+fn name_for(record_name: &str, name: &str) -> String {
+    format!("{record_name}{}", name.trim_start_matches('@'))
+}
+
+/// Formats a C# type, adding the records it needs under names derived from `record_name`.
+fn format_type(
+    definitions: &mut Vec<String>,
+    inferred_type: &InferredType,
+    record_name: &str,
+    options: &GenerationOptions,
+) -> String {
+    match inferred_type {
+        InferredType::Primitive(PrimitiveType::Null) => "object? /* null */".to_string(),
+        InferredType::Primitive(prim_type) => primitive_type(*prim_type).to_string(),
+        InferredType::Any
+        | InferredType::Never
+        | InferredType::Branded { .. }
+        | InferredType::Reference(_) => "object?".to_string(),
+        InferredType::PrimitiveUnion(types) => primitive_union(types),
+        // Tuples are (de)serialized as lists of their element types.
+        InferredType::PrimitiveTuple(types) => {
+            format!("IReadOnlyList<{}>", primitive_union(types))
+        }
+        InferredType::TupleUnion(tuples) => {
+            let types = tuples.iter().flatten().copied().collect::<Vec<_>>();
+            format!("IReadOnlyList<{}>", primitive_union(&types))
+        }
+        InferredType::Array(item_type) => format!(
+            "IReadOnlyList<{}>",
+            format_type(
+                definitions,
+                item_type,
+                &format!("{record_name}Item"),
+                options
+            )
+        ),
+        InferredType::Object(properties) => {
+            push_record(definitions, record_name, properties, options, None);
+            record_name.to_string()
+        }
+        InferredType::ComplexUnion(members) => {
+            let members = members
+                .iter()
+                .map(|member| match member {
+                    InferredType::Object(_) => "object",
+                    InferredType::Array(_) => "array",
+                    _ => "value",
+                })
+                .collect::<Vec<_>>();
+            format!("object /* {} */", members.join(" | "))
+        }
+        InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+            nullable(format_type(definitions, inner, record_name, options))
+        }
+    }
+}
+
+/// Formats a union of primitive types, possibly repeated, as the single non-null member, or as `object` with a
+/// comment listing the members. The type is nullable if the union includes `null`.
+fn primitive_union(types: &[PrimitiveType]) -> String {
+    let mut members = types
+        .iter()
+        .filter(|t| **t != PrimitiveType::Null)
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    members.dedup();
+    let union = match members.as_slice() {
+        [] => return "object?".to_string(),
+        [member] => primitive_type(**member).to_string(),
+        members => format!(
+            "object /* {} */",
+            members
+                .iter()
+                .map(|t| t.as_str())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ),
+    };
+    if types.contains(&PrimitiveType::Null) {
+        nullable(union)
+    } else {
+        union
+    }
+}
+
+/// Marks a type as nullable, before any trailing comment.
+fn nullable(csharp_type: String) -> String {
+    let (base, comment) = match csharp_type.split_once(" /*") {
+        Some((base, comment)) => (base, format!(" /*{comment}")),
+        None => (csharp_type.as_str(), String::new()),
+    };
+    if base.ends_with('?') {
+        csharp_type
+    } else {
+        format!("{base}?{comment}")
+    }
+}
+
+fn primitive_type(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "string",
+        // JSON does not distinguish integers.
+        PrimitiveType::Number => "double",
+        PrimitiveType::Boolean => "bool",
+        PrimitiveType::Null => "object?",
+    }
+}
+
+/// The PascalCase parameter name for `key`, prefixed with `@` if it is a keyword.
+fn parameter_name(key: &str) -> String {
+    let mut name = pascal_case(key)
+        .chars()
+        .map(|c| if is_xid_continue(c) { c } else { '_' })
+        .collect::<String>();
+    if !name.starts_with(|c| is_xid_start(c) || c == '_') {
+        name.insert(0, '_');
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.insert(0, '@');
+    }
+    name
+}
+
+/// Formats a tag key as a `JsonDerivedType` discriminator, which must be a string or an integer.
+fn discriminator(tag_type: TagType, tag_key: &str) -> Result<String> {
+    match tag_type {
+        TagType::String => Ok(string_literal(tag_key)),
+        TagType::Number => match tag_key.parse::<i32>() {
+            Ok(discriminator) => Ok(discriminator.to_string()),
+            Err(_) => bail!("C# type discriminators must be integers, but found {tag_key}"),
+        },
+        TagType::Bool | TagType::Any => {
+            bail!("C# type discriminators must be strings or integers")
+        }
+    }
+}
+
+fn string_literal(s: &str) -> String {
+    let mut literal = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,197 @@
+use super::{
+    GenerationOptions, InferredEvents, TypeNames, infer_event_types,
+    pydantic::{KEYWORDS, python_literal},
+};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType},
+};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::HashSet;
+use stringcase::snake_case;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+pub fn generate_django(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_django_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a Django model for each event type's content, with a field per property of object
+/// content types and a single `content` field otherwise, and a dict mapping each event type to
+/// its model, e.g. `EVENTS_MODELS`. Nested objects, arrays and unions are stored in a
+/// `JSONField`.
+pub fn generate_django_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut definitions = Vec::with_capacity(types.len() + 1);
+    let mut models = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let model_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json
+            .get(event_type_key)
+            .map(|invalid_json| {
+                format!(
+                    "# The 'content' field contained invalid JSON: \"{}\"\n",
+                    invalid_json.replace('\n', " ")
+                )
+            })
+            .unwrap_or_default();
+
+        let mut fields = Vec::new();
+        if let InferredType::Object(properties) = inferred_type {
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            let mut field_names = HashSet::new();
+            for (key, prop_def) in properties {
+                let mut name = field_name(key);
+                let base_len = name.len();
+                let mut suffix = 1;
+                while !field_names.insert(name.clone()) {
+                    suffix += 1;
+                    name.truncate(base_len);
+                    name.push_str(&suffix.to_string());
+                }
+                // The `id` column belongs to the automatic primary key.
+                let db_column = (name != *key && key != "id").then_some(key.as_str());
+                fields.push(format_field(
+                    &name,
+                    &prop_def.r#type,
+                    prop_def.optional,
+                    db_column,
+                ));
+            }
+        } else {
+            fields.push(format_field("content", inferred_type, false, None));
+        }
+        if fields.is_empty() {
+            fields.push("pass".to_string());
+        }
+
+        definitions.push(format!(
+            "{comment}class {model_name}(models.Model):\n{}",
+            fields
+                .iter()
+                .map(|field| format!("    {field}\n"))
+                .collect::<String>()
+        ));
+        models.push(format!(
+            "    {}: {model_name},\n",
+            python_literal(&options.tag_type.json_value(event_type_key))
+        ));
+    }
+
+    let models = if models.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}}}", models.concat())
+    };
+    definitions.push(format!(
+        "# Maps each event type to the model of its content.\n{}_MODELS = {models}\n",
+        snake_case(root_name).to_uppercase()
+    ));
+    Ok(format!(
+        "from django.db import models\n\n\n{}",
+        definitions.join("\n\n")
+    ))
+}
+
+/// Formats a field declaration. Optional and nullable fields accept `NULL` and blank values in
+/// forms, and `db_column` keeps the original key as the column name of a renamed field.
+fn format_field(
+    name: &str,
+    inferred_type: &InferredType,
+    optional: bool,
+    db_column: Option<&str>,
+) -> String {
+    let (class, mut args) = field_class(inferred_type);
+    if optional || accepts_none(inferred_type) {
+        args.extend(["null=True".to_string(), "blank=True".to_string()]);
+    }
+    if let Some(db_column) = db_column {
+        args.push(format!(
+            "db_column={}",
+            python_literal(&Value::from(db_column))
+        ));
+    }
+    format!("{name} = models.{class}({})", args.join(", "))
+}
+
+/// The field class of `inferred_type` and its arguments. Values other than a single primitive
+/// type are stored as JSON.
+fn field_class(inferred_type: &InferredType) -> (&'static str, Vec<String>) {
+    let json = ("JSONField", Vec::new());
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_field_class(&[*prim_type]).unwrap_or(json),
+        InferredType::PrimitiveUnion(types) => primitive_field_class(types).unwrap_or(json),
+        InferredType::NullableObj(inner)
+        | InferredType::Optional(inner)
+        | InferredType::Branded { inner, .. } => field_class(inner),
+        _ => json,
+    }
+}
+
+/// The field class of a union of primitive types, possibly repeated, with a single non-null
+/// member.
+fn primitive_field_class(types: &[PrimitiveType]) -> Option<(&'static str, Vec<String>)> {
+    let mut members = types
+        .iter()
+        .filter(|t| **t != PrimitiveType::Null)
+        .copied()
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    members.dedup();
+    match members.as_slice() {
+        [PrimitiveType::String] => Some(("CharField", vec!["max_length=255".to_string()])),
+        // JSON does not distinguish integers, and `FloatField` accepts them.
+        [PrimitiveType::Number] => Some(("FloatField", Vec::new())),
+        [PrimitiveType::Boolean] => Some(("BooleanField", Vec::new())),
+        _ => None,
+    }
+}
+
+/// Whether values of `inferred_type` can be `None`.
+fn accepts_none(inferred_type: &InferredType) -> bool {
+    match inferred_type {
+        InferredType::Primitive(PrimitiveType::Null)
+        | InferredType::Any
+        | InferredType::NullableObj(_)
+        | InferredType::Optional(_) => true,
+        InferredType::PrimitiveUnion(types) => types.contains(&PrimitiveType::Null),
+        InferredType::Branded { inner, .. } => accepts_none(inner),
+        _ => false,
+    }
+}
+
+/// The field name for `key`. Django reserves `pk` and the `id` of the automatic primary key, and
+/// rejects names containing `__` or ending with `_`.
+fn field_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    for c in key.chars() {
+        let c = if is_xid_continue(c) { c } else { '_' };
+        if !(c == '_' && name.ends_with('_')) {
+            name.push(c);
+        }
+    }
+    let name = name.trim_end_matches('_');
+    if !name.chars().next().is_some_and(is_xid_start)
+        || KEYWORDS.contains(&name)
+        || matches!(name, "id" | "pk")
+    {
+        format!("field_{}", name.trim_start_matches('_'))
+            .trim_end_matches('_')
+            .to_string()
+    } else {
+        name.to_string()
+    }
+}
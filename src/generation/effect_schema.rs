@@ -0,0 +1,157 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key},
+    types::{InferredType, InputData, PrimitiveType},
+};
+use anyhow::Result;
+
+pub fn generate_effect_schema(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_effect_schema_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates an Effect `Schema`, and the type decoded by it, for each event type's content and for
+/// the root union.
+pub fn generate_effect_schema_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut output = "import { Schema } from \"effect\";\n\n".to_string();
+    let mut members = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let schema_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            output.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+        output.push_str(&format!(
+            "export const {schema_name} = {};\nexport type {schema_name} = typeof {schema_name}.Type;\n\n",
+            format_schema(inferred_type, options, 0)
+        ));
+        members.push(format!(
+            "Schema.Struct({{ type: Schema.Literal({}), content: {schema_name} }})",
+            options.tag_type.format_literal(event_type_key)
+        ));
+    }
+
+    let root_schema = match members.as_slice() {
+        [] => "Schema.Never".to_string(),
+        [member] => member.clone(),
+        members => format!("Schema.Union(\n  {},\n)", members.join(",\n  ")),
+    };
+    output.push_str(&format!(
+        "export const {root_name} = {root_schema};\nexport type {root_name} = typeof {root_name}.Type;\n"
+    ));
+    Ok(output)
+}
+
+fn format_schema(
+    inferred_type: &InferredType,
+    options: &GenerationOptions,
+    depth: usize,
+) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_schema(*prim_type).to_string(),
+        InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+            "Schema.Unknown".to_string()
+        }
+        InferredType::Never => "Schema.Never".to_string(),
+        InferredType::PrimitiveUnion(types) => format!(
+            "Schema.Union({})",
+            types
+                .iter()
+                .map(|t| primitive_schema(*t))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::PrimitiveTuple(types) => tuple_schema(types),
+        InferredType::TupleUnion(tuples) => format!(
+            "Schema.Union({})",
+            tuples
+                .iter()
+                .map(|types| tuple_schema(types))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::Array(item_type) => {
+            format!("Schema.Array({})", format_schema(item_type, options, depth))
+        }
+        InferredType::Object(properties) => {
+            if properties.is_empty() {
+                return "Schema.Struct({})".to_string();
+            }
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            let indent = "  ".repeat(depth + 1);
+            let entries = properties
+                .into_iter()
+                .map(|(key, prop_def)| {
+                    let prop_type = match &prop_def.r#type {
+                        // `Schema.optional` already accepts `undefined`, so it is not nested.
+                        InferredType::Optional(inner) if prop_def.optional => &**inner,
+                        prop_type => prop_type,
+                    };
+                    let value_schema = format_schema(prop_type, options, depth + 1);
+                    let value_schema = if prop_def.optional {
+                        format!("Schema.optional({value_schema})")
+                    } else {
+                        value_schema
+                    };
+                    format!("{indent}{}: {value_schema}", format_property_key(key))
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "Schema.Struct({{\n{},\n{}}})",
+                entries.join(",\n"),
+                "  ".repeat(depth)
+            )
+        }
+        InferredType::ComplexUnion(members) => format!(
+            "Schema.Union({})",
+            members
+                .iter()
+                .map(|member| format_schema(member, options, depth))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::NullableObj(inner) => {
+            format!("Schema.NullOr({})", format_schema(inner, options, depth))
+        }
+        InferredType::Optional(inner) => {
+            format!(
+                "Schema.UndefinedOr({})",
+                format_schema(inner, options, depth)
+            )
+        }
+    }
+}
+
+fn tuple_schema(types: &[PrimitiveType]) -> String {
+    format!(
+        "Schema.Tuple({})",
+        types
+            .iter()
+            .map(|t| primitive_schema(*t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn primitive_schema(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "Schema.String",
+        PrimitiveType::Number => "Schema.Number",
+        PrimitiveType::Boolean => "Schema.Boolean",
+        PrimitiveType::Null => "Schema.Null",
+    }
+}
@@ -0,0 +1,431 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::{Result, ensure};
+use std::collections::HashSet;
+use stringcase::{camel_case, pascal_case};
+use unicode_ident::is_xid_continue;
+
+/// Words that cannot name a record field.
+const KEYWORDS: &[&str] = &[
+    "as", "case", "else", "exposing", "if", "import", "in", "let", "module", "of", "port", "then",
+    "type", "where",
+];
+
+pub fn generate_elm(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_elm_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a `<root_name>.elm` module with a type alias and a `Json.Decode` decoder for each
+/// event type's content, and a custom type for the root union whose decoder dispatches on the
+/// `type` field. Nested objects become record aliases named after their parent and key, e.g.
+/// `LoginContentProfile`, and unions become custom types with a variant per member.
+pub fn generate_elm_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    ensure!(
+        options.tag_type == TagType::String,
+        "Elm decoders match the type field as a string, so Elm output requires string tags"
+    );
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut module = Module::default();
+    let mut variants = Vec::with_capacity(types.len());
+    let mut branches = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!(
+                "-- The 'content' field contained invalid JSON: \"{}\"\n",
+                invalid_json.replace('\n', " ")
+            )
+        });
+        if let InferredType::Object(properties) = inferred_type {
+            module.push_record(&content_name, properties, options, comment);
+        } else {
+            let ElmType {
+                annotation,
+                decoder,
+                ..
+            } = module.format_type(inferred_type, &content_name, options);
+            module.push_definition(
+                &format!(
+                    "{}type alias {content_name} =\n    {annotation}",
+                    comment.unwrap_or_default()
+                ),
+                &content_name,
+                &decoder,
+            );
+        }
+
+        let event_name = type_names.get(event_type_key, "Event", options);
+        variants.push(format!("{event_name} {content_name}"));
+        branches.push(format!(
+            "                    {} ->\n                        Decode.map {event_name} (Decode.field \"content\" {})\n",
+            string_literal(event_type_key),
+            decoder_name(&content_name)
+        ));
+    }
+
+    if variants.is_empty() {
+        module.push_definition(
+            &format!("type alias {root_name} =\n    Never"),
+            root_name,
+            "Decode.fail \"No events were observed\"",
+        );
+    } else {
+        branches.push(
+            "                    _ ->\n                        Decode.fail (\"Unknown type: \" ++ tag)\n"
+                .to_string(),
+        );
+        module.push_definition(
+            &format!("type {root_name}\n    = {}", variants.join("\n    | ")),
+            root_name,
+            &format!(
+                "Decode.field \"type\" Decode.string\n        |> Decode.andThen\n            (\\tag ->\n                case tag of\n{}            )",
+                branches.join("\n")
+            ),
+        );
+    }
+    if module.and_map {
+        module.definitions.push(
+            "andMap : Decoder a -> Decoder (a -> b) -> Decoder b\nandMap =\n    Decode.map2 (|>)\n"
+                .to_string(),
+        );
+    }
+
+    Ok(format!(
+        "module {root_name} exposing (..)\n\nimport Json.Decode as Decode exposing (Decoder)\n\n\n{}",
+        module.definitions.join("\n\n")
+    ))
+}
+
+/// An Elm type annotation and the expression decoding it.
+struct ElmType {
+    annotation: String,
+    decoder: String,
+    /// Whether the annotation is a `Maybe`.
+    maybe: bool,
+}
+
+impl ElmType {
+    fn new(annotation: impl Into<String>, decoder: impl Into<String>) -> Self {
+        Self {
+            annotation: annotation.into(),
+            decoder: decoder.into(),
+            maybe: false,
+        }
+    }
+
+    /// Wraps the type in `Maybe`, decoding `null` as `Nothing`.
+    fn nullable(self) -> Self {
+        if self.maybe {
+            return self;
+        }
+        Self {
+            annotation: format!("Maybe {}", parenthesize(&self.annotation)),
+            decoder: format!("Decode.nullable {}", parenthesize(&self.decoder)),
+            maybe: true,
+        }
+    }
+}
+
+/// The top-level definitions of a module, in the order they were first needed.
+#[derive(Default)]
+struct Module {
+    definitions: Vec<String>,
+    /// Whether the `andMap` helper is used.
+    and_map: bool,
+}
+
+impl Module {
+    /// Adds a type definition followed by the decoder for `type_name`.
+    fn push_definition(&mut self, definition: &str, type_name: &str, decoder: &str) {
+        let decoder_name = decoder_name(type_name);
+        self.definitions.push(format!("{definition}\n"));
+        self.definitions.push(format!(
+            "{decoder_name} : Decoder {type_name}\n{decoder_name} =\n    {decoder}\n"
+        ));
+    }
+
+    /// Adds a record alias and its decoder, after the definitions of its nested types. The
+    /// decoder applies the record constructor to each field in turn with `andMap`.
+    fn push_record(
+        &mut self,
+        type_name: &str,
+        properties: &PropertyMap,
+        options: &GenerationOptions,
+        comment: Option<String>,
+    ) {
+        let mut properties = properties.iter().collect::<Vec<_>>();
+        if options.format.property_order == PropertyOrder::Alphabetical {
+            properties.sort_by_key(|(key, _)| *key);
+        }
+
+        let mut names = HashSet::new();
+        let mut fields = Vec::with_capacity(properties.len());
+        let mut decoder = format!("Decode.succeed {type_name}");
+        for (key, prop_def) in properties {
+            let nested_name = format!("{type_name}{}", pascal_case(key));
+            let mut elm_type = self.format_type(&prop_def.r#type, &nested_name, options);
+            let field_decoder = if prop_def.optional {
+                elm_type = elm_type.nullable();
+                format!(
+                    "Decode.oneOf [ Decode.field {} {}, Decode.succeed Nothing ]",
+                    string_literal(key),
+                    parenthesize(&elm_type.decoder)
+                )
+            } else {
+                format!(
+                    "Decode.field {} {}",
+                    string_literal(key),
+                    parenthesize(&elm_type.decoder)
+                )
+            };
+            let mut name = field_name(key);
+            while !names.insert(name.clone()) {
+                name.push('_');
+            }
+            fields.push(format!("{name} : {}", elm_type.annotation));
+            decoder.push_str(&format!("\n        |> andMap ({field_decoder})"));
+            self.and_map = true;
+        }
+
+        let record = if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {}\n    }}", fields.join("\n    , "))
+        };
+        self.push_definition(
+            &format!(
+                "{}type alias {type_name} =\n    {record}",
+                comment.unwrap_or_default()
+            ),
+            type_name,
+            &decoder,
+        );
+    }
+
+    /// Adds a custom type with a variant wrapping each member, and returns it.
+    fn push_union(&mut self, type_name: &str, variants: &[(String, ElmType)]) -> ElmType {
+        let definition = format!(
+            "type {type_name}\n    = {}",
+            variants
+                .iter()
+                .map(|(variant, member)| format!("{variant} {}", parenthesize(&member.annotation)))
+                .collect::<Vec<_>>()
+                .join("\n    | ")
+        );
+        let decoder = format!(
+            "Decode.oneOf\n        [ {}\n        ]",
+            variants
+                .iter()
+                .map(|(variant, member)| format!(
+                    "Decode.map {variant} {}",
+                    parenthesize(&member.decoder)
+                ))
+                .collect::<Vec<_>>()
+                .join("\n        , ")
+        );
+        self.push_definition(&definition, type_name, &decoder);
+        ElmType::new(type_name, decoder_name(type_name))
+    }
+
+    /// Formats an Elm type and its decoder, adding the definitions they need under names derived
+    /// from `type_name`.
+    fn format_type(
+        &mut self,
+        inferred_type: &InferredType,
+        type_name: &str,
+        options: &GenerationOptions,
+    ) -> ElmType {
+        match inferred_type {
+            InferredType::Primitive(prim_type) => primitive_type(*prim_type),
+            InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+                ElmType::new("Decode.Value", "Decode.value")
+            }
+            InferredType::Never => ElmType::new("Never", "Decode.fail \"No value was observed\""),
+            InferredType::PrimitiveUnion(types) => {
+                let mut members = types
+                    .iter()
+                    .filter(|t| **t != PrimitiveType::Null)
+                    .copied()
+                    .collect::<Vec<_>>();
+                members.sort_unstable();
+                members.dedup();
+                let union = match members.as_slice() {
+                    [] => return primitive_type(PrimitiveType::Null),
+                    [member] => primitive_type(*member),
+                    members => {
+                        let variants = members
+                            .iter()
+                            .map(|member| {
+                                let member = primitive_type(*member);
+                                (format!("{type_name}{}", member.annotation), member)
+                            })
+                            .collect::<Vec<_>>();
+                        self.push_union(type_name, &variants)
+                    }
+                };
+                if types.contains(&PrimitiveType::Null) {
+                    union.nullable()
+                } else {
+                    union
+                }
+            }
+            InferredType::PrimitiveTuple(types) => tuple_type(types),
+            InferredType::TupleUnion(_) => {
+                ElmType::new("List Decode.Value", "Decode.list Decode.value")
+            }
+            InferredType::Array(item_type) => {
+                let item = self.format_type(item_type, &format!("{type_name}Item"), options);
+                ElmType::new(
+                    format!("List {}", parenthesize(&item.annotation)),
+                    format!("Decode.list {}", parenthesize(&item.decoder)),
+                )
+            }
+            InferredType::Object(properties) => {
+                self.push_record(type_name, properties, options, None);
+                ElmType::new(type_name, decoder_name(type_name))
+            }
+            // Objects and arrays get their own variants, and any other member falls back to a
+            // `Value` variant holding the raw JSON.
+            InferredType::ComplexUnion(members) => {
+                let mut variants = Vec::<(String, ElmType)>::with_capacity(members.len());
+                for member in members {
+                    let (kind, member_type) = match member {
+                        InferredType::Object(_) => (
+                            "Object",
+                            self.format_type(member, &format!("{type_name}Record"), options),
+                        ),
+                        InferredType::Array(_) => {
+                            ("List", self.format_type(member, type_name, options))
+                        }
+                        _ => ("Value", ElmType::new("Decode.Value", "Decode.value")),
+                    };
+                    let variant = format!("{type_name}{kind}");
+                    if variants.iter().all(|(existing, _)| *existing != variant) {
+                        variants.push((variant, member_type));
+                    }
+                }
+                // The fallback must be tried last, since it accepts anything.
+                variants.sort_by_key(|(variant, _)| variant.ends_with("Value"));
+                self.push_union(type_name, &variants)
+            }
+            InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+                self.format_type(inner, type_name, options).nullable()
+            }
+        }
+    }
+}
+
+fn primitive_type(prim_type: PrimitiveType) -> ElmType {
+    match prim_type {
+        PrimitiveType::String => ElmType::new("String", "Decode.string"),
+        // JSON does not distinguish integers.
+        PrimitiveType::Number => ElmType::new("Float", "Decode.float"),
+        PrimitiveType::Boolean => ElmType::new("Bool", "Decode.bool"),
+        PrimitiveType::Null => ElmType {
+            maybe: true,
+            ..ElmType::new("Maybe Never", "Decode.null Nothing")
+        },
+    }
+}
+
+/// Formats a tuple type. Elm tuples have two or three elements, so other lengths are decoded as
+/// a unit, a single value or a list of raw JSON values.
+fn tuple_type(types: &[PrimitiveType]) -> ElmType {
+    let elements = types
+        .iter()
+        .enumerate()
+        .map(|(index, t)| {
+            let element = primitive_type(*t);
+            let decoder = format!("(Decode.index {index} {})", parenthesize(&element.decoder));
+            (element.annotation, decoder)
+        })
+        .collect::<Vec<_>>();
+    let (annotations, decoders): (Vec<_>, Vec<_>) = elements.into_iter().unzip();
+    match types {
+        [] => ElmType::new("()", "Decode.succeed ()"),
+        [t] => {
+            let element = primitive_type(*t);
+            ElmType {
+                decoder: format!("Decode.index 0 {}", parenthesize(&element.decoder)),
+                ..element
+            }
+        }
+        [_, _] => ElmType::new(
+            format!("( {} )", annotations.join(", ")),
+            format!("Decode.map2 Tuple.pair {}", decoders.join(" ")),
+        ),
+        [_, _, _] => ElmType::new(
+            format!("( {} )", annotations.join(", ")),
+            format!(
+                "Decode.map3 (\\a b c -> ( a, b, c )) {}",
+                decoders.join(" ")
+            ),
+        ),
+        _ => ElmType::new("List Decode.Value", "Decode.list Decode.value"),
+    }
+}
+
+/// Wraps a type or expression in parentheses unless it is a single term.
+fn parenthesize(term: &str) -> String {
+    if !term.contains(' ') || term.starts_with('(') && term.ends_with(')') {
+        term.to_string()
+    } else {
+        format!("({term})")
+    }
+}
+
+/// The name of the decoder for `type_name`, e.g. `loginContentDecoder`.
+fn decoder_name(type_name: &str) -> String {
+    let mut chars = type_name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect::<String>())
+        .unwrap_or_default()
+        + "Decoder"
+}
+
+/// The camelCase field name for `key`, prefixed with `field_` unless it starts with a lowercase
+/// letter and followed by `_` if it is a keyword.
+fn field_name(key: &str) -> String {
+    let mut name = camel_case(key)
+        .chars()
+        .map(|c| if is_xid_continue(c) { c } else { '_' })
+        .collect::<String>();
+    if !name.starts_with(char::is_lowercase) {
+        name.insert_str(0, "field_");
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+fn string_literal(s: &str) -> String {
+    let mut literal = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{{{:04X}}}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,267 @@
+use crate::{
+    formatting::format_property_key,
+    types::{InferredType, PrimitiveType, PropertyMap},
+};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// A content type to emit fixtures for.
+pub(super) struct FixtureSource<'a> {
+    /// The generated content type name, e.g. `LoginContent`.
+    pub type_name: String,
+    /// The fixture constant name, e.g. `exampleLogin`.
+    pub fixture_name: String,
+    /// The event type key the content type belongs to, absent for the cross-type content type.
+    pub event_type_key: Option<&'a str>,
+    pub inferred_type: &'a InferredType,
+}
+
+/// Formats a fixtures module for every object content type, built from the examples collected
+/// during inference. Returns `None` when there are no object content types.
+///
+/// Types with optional properties get two fixtures: one with every property populated and a
+/// `Minimal` one with only the required properties. With `satisfies`, fixtures are checked with
+/// the TypeScript 4.9 `satisfies` operator instead of a type annotation, keeping their literal
+/// types. With `const_assertions`, every object and array literal is followed by `as const`.
+///
+/// With `type_check_root`, a `_typeCheck` object mapping each event type key to its fixture is
+/// checked against the tags of that root union, catching event types missing from it.
+pub(super) fn format_fixtures(
+    import_path: &str,
+    sources: &[FixtureSource],
+    satisfies: bool,
+    const_assertions: bool,
+    type_check_root: Option<&str>,
+) -> Option<String> {
+    let mut imports = BTreeSet::new();
+    let mut fixtures = Vec::new();
+    let mut type_check = Vec::new();
+    for source in sources {
+        let InferredType::Object(properties) = source.inferred_type else {
+            continue;
+        };
+        imports.insert(source.type_name.clone());
+        if let Some(event_type_key) = source.event_type_key {
+            type_check.push(format!(
+                "  {}: {},\n",
+                format_property_key(event_type_key),
+                source.fixture_name
+            ));
+        }
+
+        for (fixture_name, include_optional) in fixture_variants(source, properties) {
+            let literal = format_object(
+                source.inferred_type,
+                include_optional,
+                const_assertions,
+                0,
+                &mut imports,
+            );
+            fixtures.push(if satisfies {
+                format!(
+                    "export const {fixture_name} = {literal} satisfies {};\n",
+                    source.type_name
+                )
+            } else {
+                format!(
+                    "export const {fixture_name}: {} = {literal};\n",
+                    source.type_name
+                )
+            });
+        }
+    }
+
+    if fixtures.is_empty() {
+        return None;
+    }
+    if let Some(root_name) = type_check_root.filter(|_| !type_check.is_empty()) {
+        imports.insert(root_name.to_string());
+        // Event types without an object content type have no fixture to list.
+        let tags = format!("Record<{root_name}[\"type\"], unknown>");
+        let tags = if type_check.len() == sources.len() {
+            tags
+        } else {
+            format!("Partial<{tags}>")
+        };
+        fixtures.push(format!(
+            "const _typeCheck = {{\n{}}} satisfies {tags};\n",
+            type_check.concat()
+        ));
+    }
+    Some(format!(
+        "import type {{ {} }} from \"{import_path}\";\n\n{}",
+        imports.into_iter().collect::<Vec<_>>().join(", "),
+        fixtures.join("\n")
+    ))
+}
+
+/// Formats a module checking a representative value of every object content type against it
+/// with `satisfies`, meant to be type-checked in development and CI rather than imported. With
+/// `fixtures_import`, the values are the test fixtures imported from that module specifier;
+/// otherwise they are written inline like test fixtures. Returns `None` when there are no object
+/// content types.
+pub(super) fn format_satisfies_check(
+    import_path: &str,
+    sources: &[FixtureSource],
+    fixtures_import: Option<&str>,
+) -> Option<String> {
+    const HEADER: &str =
+        "// Compile-time checks of the generated types. Do not import this module.\n";
+    let Some(fixtures_import) = fixtures_import else {
+        return format_fixtures(import_path, sources, true, false, None)
+            .map(|checks| format!("{HEADER}{checks}"));
+    };
+
+    let mut imports = BTreeSet::new();
+    let mut fixture_names = Vec::new();
+    let mut checks = String::new();
+    for source in sources {
+        let InferredType::Object(properties) = source.inferred_type else {
+            continue;
+        };
+        imports.insert(source.type_name.clone());
+        for (fixture_name, _) in fixture_variants(source, properties) {
+            checks.push_str(&format!("{fixture_name} satisfies {};\n", source.type_name));
+            fixture_names.push(fixture_name);
+        }
+    }
+    if fixture_names.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{HEADER}import type {{ {} }} from \"{import_path}\";\nimport {{ {} }} from \"{fixtures_import}\";\n\n{checks}",
+        imports.into_iter().collect::<Vec<_>>().join(", "),
+        fixture_names.join(", ")
+    ))
+}
+
+/// The fixture names of an object content type, each with whether it includes the optional
+/// properties.
+fn fixture_variants(source: &FixtureSource, properties: &PropertyMap) -> Vec<(String, bool)> {
+    let mut variants = vec![(source.fixture_name.clone(), true)];
+    if properties.values().any(|prop_def| prop_def.optional) {
+        variants.push((format!("{}Minimal", source.fixture_name), false));
+    }
+    variants
+}
+
+/// Formats an object literal from the per-property examples. Nested objects always include their
+/// optional properties when an example was observed.
+fn format_object(
+    inferred_type: &InferredType,
+    include_optional: bool,
+    const_assertion: bool,
+    depth: usize,
+    imports: &mut BTreeSet<String>,
+) -> String {
+    let InferredType::Object(properties) = inferred_type else {
+        unreachable!("only object types are formatted as object literals");
+    };
+    let mut properties = properties
+        .iter()
+        .filter(|(_, prop_def)| include_optional || !prop_def.optional)
+        .filter_map(|(key, prop_def)| Some((key, &prop_def.r#type, prop_def.examples.first()?)))
+        .collect::<Vec<_>>();
+    let assertion = if const_assertion { " as const" } else { "" };
+    if properties.is_empty() {
+        return format!("{{}}{assertion}");
+    }
+    properties.sort_by_key(|(key, _, _)| *key);
+
+    let indent = "  ".repeat(depth + 1);
+    let entries = properties
+        .into_iter()
+        .map(|(key, prop_type, example)| {
+            format!(
+                "{indent}{}: {}",
+                format_property_key(key),
+                format_value(prop_type, example, const_assertion, depth + 1, imports)
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "{{\n{}\n{}}}{assertion}",
+        entries.join(",\n"),
+        "  ".repeat(depth)
+    )
+}
+
+fn format_value(
+    inferred_type: &InferredType,
+    example: &Value,
+    const_assertion: bool,
+    depth: usize,
+    imports: &mut BTreeSet<String>,
+) -> String {
+    match (inferred_type, example) {
+        (_, Value::Null) => "null".to_string(),
+        (InferredType::Object(_), _) => {
+            format_object(inferred_type, true, const_assertion, depth, imports)
+        }
+        (InferredType::NullableObj(inner) | InferredType::Optional(inner), _) => {
+            format_value(inner, example, const_assertion, depth, imports)
+        }
+        (InferredType::ComplexUnion(members), _) => {
+            let member = members.iter().find(|member| {
+                matches!(
+                    (member, example),
+                    (
+                        InferredType::Array(_) | InferredType::PrimitiveTuple(_),
+                        Value::Array(_)
+                    ) | (InferredType::Object(_), Value::Object(_))
+                )
+            });
+            match member {
+                Some(member) => format_value(member, example, const_assertion, depth, imports),
+                None => example.to_string(),
+            }
+        }
+        (InferredType::Array(item_type), Value::Array(items)) => {
+            let item = items
+                .first()
+                .map(|item| format_value(item_type, item, const_assertion, depth, imports))
+                .unwrap_or_default();
+            let assertion = if const_assertion { " as const" } else { "" };
+            format!("[{item}]{assertion}")
+        }
+        (InferredType::PrimitiveTuple(types), Value::Array(items))
+            if types.len() == items.len() =>
+        {
+            let items = types
+                .iter()
+                .zip(items)
+                .map(|(item_type, item)| {
+                    format_value(
+                        &InferredType::Primitive(*item_type),
+                        item,
+                        const_assertion,
+                        depth,
+                        imports,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let assertion = if const_assertion { " as const" } else { "" };
+            format!("[{}]{assertion}", items.join(","))
+        }
+        // With `parse_numeric_strings`, numeric strings are inferred as numbers, so they are
+        // written as the numbers they hold.
+        (InferredType::Primitive(PrimitiveType::Number), Value::String(s)) => numeric_literal(s),
+        (InferredType::PrimitiveUnion(types), Value::String(s))
+            if !types.contains(&PrimitiveType::String) =>
+        {
+            numeric_literal(s)
+        }
+        // Plain values are not assignable to branded types without an assertion.
+        (InferredType::Branded { name, .. }, _) => {
+            imports.insert(name.clone());
+            format!("{example} as {name}")
+        }
+        (_, Value::Array(_) | Value::Object(_)) if const_assertion => format!("{example} as const"),
+        _ => example.to_string(),
+    }
+}
+
+fn numeric_literal(s: &str) -> String {
+    s.parse::<f64>()
+        .map_or_else(|_| Value::from(s).to_string(), |n| n.to_string())
+}
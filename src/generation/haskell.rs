@@ -0,0 +1,386 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::{Result, ensure};
+use std::collections::HashSet;
+use stringcase::pascal_case;
+use unicode_ident::is_xid_continue;
+
+pub fn generate_haskell(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_haskell_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a `<root_name>.hs` module with a record for each event type's content and a sum
+/// type for the root union, with aeson instances derived through `Generic`. Record fields are
+/// prefixed with their type's name, e.g. `loginContentUserId`, and mapped back to their JSON keys
+/// by the instances' options. Nested objects become records named after their parent and key,
+/// e.g. `LoginContentProfile`, and unions become untagged sum types.
+pub fn generate_haskell_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    ensure!(
+        options.tag_type == TagType::String,
+        "aeson constructor tags are strings, so Haskell output requires string tags"
+    );
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut module = Module::default();
+    let mut constructors = Vec::with_capacity(types.len());
+    let mut tags = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!(
+                "-- The 'content' field contained invalid JSON: \"{}\"\n",
+                invalid_json.replace('\n', " ")
+            )
+        });
+        if let InferredType::Object(properties) = inferred_type {
+            module.push_record(&content_name, properties, options, comment);
+        } else {
+            let content_type = module.format_type(inferred_type, &content_name, options);
+            module.definitions.push(format!(
+                "{}type {content_name} = {content_type}\n",
+                comment.unwrap_or_default()
+            ));
+        }
+
+        let event_name = type_names.get(event_type_key, "Event", options);
+        constructors.push(format!("{event_name} {content_name}"));
+        tags.push(format!(
+            "({}, {})",
+            string_literal(&event_name),
+            string_literal(event_type_key)
+        ));
+    }
+
+    if constructors.is_empty() {
+        module.void = true;
+        module
+            .definitions
+            .push(format!("type {root_name} = Void\n"));
+    } else {
+        let options_name = module.fresh_name(&format!("{}JsonOptions", lower_first(root_name)));
+        module.definitions.push(format!(
+            "data {root_name}\n  = {}\n  deriving (Show, Eq, Generic)\n",
+            constructors.join("\n  | ")
+        ));
+        module.definitions.push(format!(
+            "{options_name} :: Options\n{options_name} =\n  defaultOptions\n    {{ constructorTagModifier = \\constructor -> fromMaybe constructor (lookup constructor [{}])\n    , sumEncoding = TaggedObject {{tagFieldName = \"type\", contentsFieldName = \"content\"}}\n    }}\n",
+            tags.join(", ")
+        ));
+        module.push_instances(root_name, &options_name);
+    }
+    if module.record_options {
+        module.definitions.push(
+            "-- | Options for a record whose fields are mapped to the given JSON keys.\nrecordOptions :: [(String, String)] -> Options\nrecordOptions keys =\n  defaultOptions\n    { fieldLabelModifier = \\field -> fromMaybe field (lookup field keys)\n    , omitNothingFields = True\n    }\n"
+                .to_string(),
+        );
+    }
+    if module.untagged_options {
+        module.definitions.push(
+            "untaggedOptions :: Options\nuntaggedOptions = defaultOptions {sumEncoding = UntaggedValue}\n"
+                .to_string(),
+        );
+    }
+
+    let mut imports = vec!["import Data.Aeson", "import Data.Maybe (fromMaybe)"];
+    if module.text {
+        imports.push("import Data.Text (Text)");
+    }
+    if module.void {
+        imports.push("import Data.Void (Void)");
+    }
+    imports.push("import GHC.Generics (Generic)");
+    Ok(format!(
+        "{{-# LANGUAGE DeriveGeneric #-}}\n\nmodule {root_name} where\n\n{}\n\n{}",
+        imports.join("\n"),
+        module.definitions.join("\n")
+    ))
+}
+
+/// The declarations of a module, in dependency order, and what they need.
+#[derive(Default)]
+struct Module {
+    definitions: Vec<String>,
+    /// Field and options names, which share the module's namespace.
+    names: HashSet<String>,
+    /// Whether the `recordOptions` helper is used.
+    record_options: bool,
+    /// Whether the `untaggedOptions` helper is used.
+    untagged_options: bool,
+    /// Whether `Text` is used.
+    text: bool,
+    /// Whether `Void` is used.
+    void: bool,
+}
+
+impl Module {
+    /// Returns `name`, followed by primes if it is already taken.
+    fn fresh_name(&mut self, name: &str) -> String {
+        let mut name = name.to_string();
+        while !self.names.insert(name.clone()) {
+            name.push('\'');
+        }
+        name
+    }
+
+    /// Adds `FromJSON` and `ToJSON` instances for `type_name` using `options_name`.
+    fn push_instances(&mut self, type_name: &str, options_name: &str) {
+        self.definitions.push(format!(
+            "instance FromJSON {type_name} where\n  parseJSON = genericParseJSON {options_name}\n"
+        ));
+        self.definitions.push(format!(
+            "instance ToJSON {type_name} where\n  toJSON = genericToJSON {options_name}\n"
+        ));
+    }
+
+    /// Adds a record for an object type and its instances, after the declarations of its nested
+    /// types. Optional fields are `Maybe`s, omitted from the JSON when `Nothing`.
+    fn push_record(
+        &mut self,
+        type_name: &str,
+        properties: &PropertyMap,
+        options: &GenerationOptions,
+        comment: Option<String>,
+    ) {
+        let comment = comment.unwrap_or_default();
+        // A record without fields would be encoded as a constructor tag.
+        if properties.is_empty() {
+            self.definitions.push(format!(
+                "{comment}data {type_name} = {type_name}\n  deriving (Show, Eq, Generic)\n"
+            ));
+            self.definitions.push(format!(
+                "instance FromJSON {type_name} where\n  parseJSON = withObject {} (\\_ -> pure {type_name})\n",
+                string_literal(type_name)
+            ));
+            self.definitions.push(format!(
+                "instance ToJSON {type_name} where\n  toJSON _ = object []\n"
+            ));
+            return;
+        }
+        let mut properties = properties.iter().collect::<Vec<_>>();
+        if options.format.property_order == PropertyOrder::Alphabetical {
+            properties.sort_by_key(|(key, _)| *key);
+        }
+
+        let prefix = lower_first(type_name);
+        let mut fields = Vec::with_capacity(properties.len());
+        let mut keys = Vec::with_capacity(properties.len());
+        for (key, prop_def) in properties {
+            let nested_name = format!("{type_name}{}", pascal_case(key));
+            let mut field_type = self.format_type(&prop_def.r#type, &nested_name, options);
+            if prop_def.optional && !field_type.starts_with("Maybe ") {
+                field_type = format!("Maybe {}", parenthesize(&field_type));
+            }
+            let name = self.fresh_name(&field_name(&prefix, key));
+            keys.push(format!(
+                "({}, {})",
+                string_literal(&name),
+                string_literal(key)
+            ));
+            fields.push(format!("{name} :: {field_type}"));
+        }
+
+        let options_name = self.fresh_name(&format!("{prefix}JsonOptions"));
+        self.record_options = true;
+        self.definitions.push(format!(
+            "{comment}data {type_name} = {type_name}\n  {{ {}\n  }}\n  deriving (Show, Eq, Generic)\n",
+            fields.join("\n  , ")
+        ));
+        self.definitions.push(format!(
+            "{options_name} :: Options\n{options_name} = recordOptions [{}]\n",
+            keys.join(", ")
+        ));
+        self.push_instances(type_name, &options_name);
+    }
+
+    /// Adds an untagged sum type with a constructor wrapping each member, and its instances.
+    fn push_union(&mut self, type_name: &str, constructors: &[(String, String)]) -> String {
+        self.untagged_options = true;
+        self.definitions.push(format!(
+            "data {type_name}\n  = {}\n  deriving (Show, Eq, Generic)\n",
+            constructors
+                .iter()
+                .map(|(constructor, member_type)| format!(
+                    "{constructor} {}",
+                    parenthesize(member_type)
+                ))
+                .collect::<Vec<_>>()
+                .join("\n  | ")
+        ));
+        self.push_instances(type_name, "untaggedOptions");
+        type_name.to_string()
+    }
+
+    /// Formats a Haskell type, adding the declarations it needs under names derived from
+    /// `type_name`.
+    fn format_type(
+        &mut self,
+        inferred_type: &InferredType,
+        type_name: &str,
+        options: &GenerationOptions,
+    ) -> String {
+        match inferred_type {
+            InferredType::Primitive(prim_type) => self.primitive_type(*prim_type).to_string(),
+            InferredType::Any
+            | InferredType::Never
+            | InferredType::Branded { .. }
+            | InferredType::Reference(_) => "Value".to_string(),
+            InferredType::PrimitiveUnion(types) => {
+                let mut members = types
+                    .iter()
+                    .filter(|t| **t != PrimitiveType::Null)
+                    .copied()
+                    .collect::<Vec<_>>();
+                members.sort_unstable();
+                members.dedup();
+                let union = match members.as_slice() {
+                    [] => return self.primitive_type(PrimitiveType::Null).to_string(),
+                    [member] => self.primitive_type(*member).to_string(),
+                    members => {
+                        let constructors = members
+                            .iter()
+                            .map(|member| {
+                                (
+                                    format!("{type_name}{}", pascal_case(member.as_str())),
+                                    self.primitive_type(*member).to_string(),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        self.push_union(type_name, &constructors)
+                    }
+                };
+                if types.contains(&PrimitiveType::Null) {
+                    format!("Maybe {}", parenthesize(&union))
+                } else {
+                    union
+                }
+            }
+            // aeson (de)serializes tuples as arrays, but there is no single-element tuple.
+            InferredType::PrimitiveTuple(types) => match types.as_slice() {
+                [] => "[Value]".to_string(),
+                [t] => format!("[{}]", self.primitive_type(*t)),
+                types => format!(
+                    "({})",
+                    types
+                        .iter()
+                        .map(|t| self.primitive_type(*t))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            InferredType::TupleUnion(_) => "[Value]".to_string(),
+            InferredType::Array(item_type) => format!(
+                "[{}]",
+                self.format_type(item_type, &format!("{type_name}Item"), options)
+            ),
+            InferredType::Object(properties) => {
+                self.push_record(type_name, properties, options, None);
+                type_name.to_string()
+            }
+            // Objects and arrays get their own constructors, and any other member falls back to
+            // a constructor holding the raw JSON.
+            InferredType::ComplexUnion(members) => {
+                let mut constructors = Vec::<(String, String)>::with_capacity(members.len());
+                for member in members {
+                    let (kind, member_type) = match member {
+                        InferredType::Object(_) => (
+                            "Object",
+                            self.format_type(member, &format!("{type_name}Record"), options),
+                        ),
+                        InferredType::Array(_) => {
+                            ("List", self.format_type(member, type_name, options))
+                        }
+                        _ => ("Value", "Value".to_string()),
+                    };
+                    let constructor = format!("{type_name}{kind}");
+                    if constructors
+                        .iter()
+                        .all(|(existing, _)| *existing != constructor)
+                    {
+                        constructors.push((constructor, member_type));
+                    }
+                }
+                // The fallback must be tried last, since it accepts anything.
+                constructors.sort_by_key(|(constructor, _)| constructor.ends_with("Value"));
+                self.push_union(type_name, &constructors)
+            }
+            InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+                let inner = self.format_type(inner, type_name, options);
+                if inner.starts_with("Maybe ") {
+                    inner
+                } else {
+                    format!("Maybe {}", parenthesize(&inner))
+                }
+            }
+        }
+    }
+
+    fn primitive_type(&mut self, prim_type: PrimitiveType) -> &'static str {
+        match prim_type {
+            PrimitiveType::String => {
+                self.text = true;
+                "Text"
+            }
+            // JSON does not distinguish integers.
+            PrimitiveType::Number => "Double",
+            PrimitiveType::Boolean => "Bool",
+            PrimitiveType::Null => "Maybe Value",
+        }
+    }
+}
+
+/// Wraps a type in parentheses unless it is a single term.
+fn parenthesize(haskell_type: &str) -> String {
+    if haskell_type.contains(' ') && !haskell_type.starts_with(['(', '[']) {
+        format!("({haskell_type})")
+    } else {
+        haskell_type.to_string()
+    }
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// The field name for `key` in a record whose fields start with `prefix`.
+fn field_name(prefix: &str, key: &str) -> String {
+    let suffix = pascal_case(key)
+        .chars()
+        .map(|c| if is_xid_continue(c) { c } else { '_' })
+        .collect::<String>();
+    format!("{prefix}{suffix}")
+}
+
+/// Formats a Haskell string literal. Numeric escapes are followed by `\&` so that a following
+/// digit is not read as part of them.
+fn string_literal(s: &str) -> String {
+    let mut literal = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\{}\\&", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,173 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key},
+    types::{InferredType, InputData, PrimitiveType, PropertyDefinition},
+};
+use anyhow::Result;
+
+pub fn generate_io_ts_codecs(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_io_ts_codecs_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates an `io-ts` codec, and the type derived from it, for each event type's content and
+/// for the root union.
+pub fn generate_io_ts_codecs_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut output = "import * as t from \"io-ts\";\n\n".to_string();
+    let mut members = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let codec_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            output.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+        output.push_str(&format!(
+            "export const {codec_name} = {};\nexport type {codec_name} = t.TypeOf<typeof {codec_name}>;\n\n",
+            format_codec(inferred_type, options, 0)
+        ));
+        members.push(format!(
+            "t.type({{ type: t.literal({}), content: {codec_name} }})",
+            options.tag_type.format_literal(event_type_key)
+        ));
+    }
+
+    // `t.union` requires at least two members.
+    let root_codec = match members.as_slice() {
+        [] => "t.never".to_string(),
+        [member] => member.clone(),
+        members => format!("t.union([\n  {},\n])", members.join(",\n  ")),
+    };
+    output.push_str(&format!(
+        "export const {root_name} = {root_codec};\nexport type {root_name} = t.TypeOf<typeof {root_name}>;\n"
+    ));
+    Ok(output)
+}
+
+fn format_codec(inferred_type: &InferredType, options: &GenerationOptions, depth: usize) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_codec(*prim_type).to_string(),
+        InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+            "t.unknown".to_string()
+        }
+        InferredType::Never => "t.never".to_string(),
+        InferredType::PrimitiveUnion(types) => format!(
+            "t.union([{}])",
+            types
+                .iter()
+                .map(|t| primitive_codec(*t))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::PrimitiveTuple(types) => tuple_codec(types),
+        InferredType::TupleUnion(tuples) => format!(
+            "t.union([{}])",
+            tuples
+                .iter()
+                .map(|types| tuple_codec(types))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::Array(item_type) => {
+            format!("t.array({})", format_codec(item_type, options, depth))
+        }
+        InferredType::Object(properties) => {
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            let (optional, required): (Vec<_>, Vec<_>) = properties
+                .into_iter()
+                .partition(|(_, prop_def)| prop_def.optional);
+
+            match (required.is_empty(), optional.is_empty()) {
+                (_, true) => format!("t.type({})", format_props(&required, options, depth)),
+                (true, false) => {
+                    format!("t.partial({})", format_props(&optional, options, depth))
+                }
+                (false, false) => {
+                    let indent = "  ".repeat(depth + 1);
+                    format!(
+                        "t.intersection([\n{indent}t.type({}),\n{indent}t.partial({}),\n{}])",
+                        format_props(&required, options, depth + 1),
+                        format_props(&optional, options, depth + 1),
+                        "  ".repeat(depth)
+                    )
+                }
+            }
+        }
+        InferredType::ComplexUnion(members) => format!(
+            "t.union([{}])",
+            members
+                .iter()
+                .map(|member| format_codec(member, options, depth))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::NullableObj(inner) => {
+            format!("t.union([{}, t.null])", format_codec(inner, options, depth))
+        }
+        InferredType::Optional(inner) => {
+            format!(
+                "t.union([{}, t.undefined])",
+                format_codec(inner, options, depth)
+            )
+        }
+    }
+}
+
+fn format_props(
+    properties: &[(&String, &PropertyDefinition)],
+    options: &GenerationOptions,
+    depth: usize,
+) -> String {
+    if properties.is_empty() {
+        return "{}".to_string();
+    }
+    let indent = "  ".repeat(depth + 1);
+    let entries = properties
+        .iter()
+        .map(|(key, prop_def)| {
+            format!(
+                "{indent}{}: {}",
+                format_property_key(key),
+                format_codec(&prop_def.r#type, options, depth + 1)
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("{{\n{},\n{}}}", entries.join(",\n"), "  ".repeat(depth))
+}
+
+fn tuple_codec(types: &[PrimitiveType]) -> String {
+    if types.is_empty() {
+        // `t.tuple` has no overload for the empty tuple.
+        return "t.array(t.never)".to_string();
+    }
+    format!(
+        "t.tuple([{}])",
+        types
+            .iter()
+            .map(|t| primitive_codec(*t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn primitive_codec(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "t.string",
+        PrimitiveType::Number => "t.number",
+        PrimitiveType::Boolean => "t.boolean",
+        PrimitiveType::Null => "t.null",
+    }
+}
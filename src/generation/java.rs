@@ -0,0 +1,323 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::{Result, ensure};
+use std::collections::HashSet;
+use stringcase::{camel_case, pascal_case};
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// Words that cannot name a field.
+const KEYWORDS: &[&str] = &[
+    "_",
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+pub fn generate_java(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_java_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a `<root_name>.java` compilation unit: an abstract root class annotated for Jackson
+/// polymorphic deserialization, with a nested class for each event type's content and a nested
+/// subclass for each event type. Nested objects become classes named after their parent and key,
+/// e.g. `LoginContentProfile`.
+pub fn generate_java_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    ensure!(
+        options.tag_type == TagType::String,
+        "Jackson type ids are strings, so Java output requires string tags"
+    );
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut module = Module::default();
+    let mut sub_types = Vec::with_capacity(types.len());
+    let mut events = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!(
+                "    // The 'content' field contained invalid JSON: \"{}\"\n",
+                invalid_json.replace('\n', " ")
+            )
+        });
+        // Java has no type aliases, so non-object content is declared on the event itself.
+        let (content_type, note, comment) = match inferred_type {
+            InferredType::Object(properties) => {
+                module.push_class(&content_name, properties, options, comment);
+                (content_name, String::new(), None)
+            }
+            inferred_type => {
+                let (content_type, note) =
+                    module.format_type(inferred_type, &content_name, options);
+                (content_type, note, comment)
+            }
+        };
+
+        let event_name = type_names.get(event_type_key, "Event", options);
+        sub_types.push(format!(
+            "    @JsonSubTypes.Type(value = {root_name}.{event_name}.class, name = {})",
+            string_literal(event_type_key)
+        ));
+        events.push(format!(
+            "{}    public static final class {event_name} extends {root_name} {{\n        @JsonProperty(\"content\")\n        public {content_type} content;{note}\n    }}\n",
+            comment.unwrap_or_default()
+        ));
+    }
+    let body = module
+        .definitions
+        .into_iter()
+        .chain(events)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut output = String::new();
+    if module.optional {
+        output.push_str("import com.fasterxml.jackson.annotation.JsonInclude;\n");
+    }
+    output.push_str(
+        "import com.fasterxml.jackson.annotation.JsonProperty;\nimport com.fasterxml.jackson.annotation.JsonSubTypes;\nimport com.fasterxml.jackson.annotation.JsonTypeInfo;\n",
+    );
+    if module.list {
+        output.push_str("import java.util.List;\n");
+    }
+    output.push_str(&format!(
+        "\n@JsonTypeInfo(use = JsonTypeInfo.Id.NAME, include = JsonTypeInfo.As.PROPERTY, property = \"type\")\n@JsonSubTypes({{\n{}\n}})\npublic abstract class {root_name} {{\n{body}}}\n",
+        sub_types.join(",\n")
+    ));
+    Ok(output)
+}
+
+/// The nested classes of the root class, in dependency order, and the imports they need.
+#[derive(Default)]
+struct Module {
+    definitions: Vec<String>,
+    /// Whether `java.util.List` is used.
+    list: bool,
+    /// Whether `JsonInclude` is used.
+    optional: bool,
+}
+
+impl Module {
+    /// Adds a class with a public field for each property, after the classes of its nested
+    /// objects. Optional fields are omitted from the output when `null`.
+    fn push_class(
+        &mut self,
+        class_name: &str,
+        properties: &PropertyMap,
+        options: &GenerationOptions,
+        comment: Option<String>,
+    ) {
+        let mut properties = properties.iter().collect::<Vec<_>>();
+        if options.format.property_order == PropertyOrder::Alphabetical {
+            properties.sort_by_key(|(key, _)| *key);
+        }
+
+        let mut names = HashSet::new();
+        let mut fields = Vec::with_capacity(properties.len());
+        for (key, prop_def) in properties {
+            let nested_name = format!("{class_name}{}", pascal_case(key));
+            let (field_type, note) = self.format_type(&prop_def.r#type, &nested_name, options);
+            let mut name = field_name(key);
+            while !names.insert(name.clone()) {
+                name.push('_');
+            }
+            let mut field = format!("        @JsonProperty({})\n", string_literal(key));
+            if prop_def.optional {
+                self.optional = true;
+                field.push_str("        @JsonInclude(JsonInclude.Include.NON_NULL)\n");
+            }
+            field.push_str(&format!("        public {field_type} {name};{note}\n"));
+            fields.push(field);
+        }
+        self.definitions.push(format!(
+            "{}    public static class {class_name} {{\n{}    }}\n",
+            comment.unwrap_or_default(),
+            fields.join("\n")
+        ));
+    }
+
+    /// Formats a Java type, adding the classes it needs under names derived from `class_name`.
+    /// Types that Java cannot express become `Object`, with a trailing comment describing them.
+    fn format_type(
+        &mut self,
+        inferred_type: &InferredType,
+        class_name: &str,
+        options: &GenerationOptions,
+    ) -> (String, String) {
+        match inferred_type {
+            InferredType::Primitive(prim_type) => primitive_union(&[*prim_type]),
+            InferredType::Any
+            | InferredType::Never
+            | InferredType::Branded { .. }
+            | InferredType::Reference(_) => ("Object".to_string(), String::new()),
+            InferredType::PrimitiveUnion(types) => primitive_union(types),
+            InferredType::PrimitiveTuple(types) => {
+                self.list = true;
+                let (element_type, note) = primitive_union(types);
+                (format!("List<{element_type}>"), note)
+            }
+            InferredType::TupleUnion(tuples) => {
+                self.list = true;
+                let types = tuples.iter().flatten().copied().collect::<Vec<_>>();
+                let (element_type, note) = primitive_union(&types);
+                (format!("List<{element_type}>"), note)
+            }
+            InferredType::Array(item_type) => {
+                self.list = true;
+                let (item_type, note) =
+                    self.format_type(item_type, &format!("{class_name}Item"), options);
+                (format!("List<{item_type}>"), note)
+            }
+            InferredType::Object(properties) => {
+                self.push_class(class_name, properties, options, None);
+                (class_name.to_string(), String::new())
+            }
+            InferredType::ComplexUnion(members) => {
+                let members = members
+                    .iter()
+                    .map(|member| match member {
+                        InferredType::Object(_) => "object",
+                        InferredType::Array(_) => "array",
+                        _ => "value",
+                    })
+                    .collect::<Vec<_>>();
+                ("Object".to_string(), format!(" // {}", members.join(" | ")))
+            }
+            // Every reference type is nullable.
+            InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+                self.format_type(inner, class_name, options)
+            }
+        }
+    }
+}
+
+/// Formats a union of primitive types, possibly repeated, as the single non-null member, or as
+/// `Object` with a comment listing the members.
+fn primitive_union(types: &[PrimitiveType]) -> (String, String) {
+    let mut members = types
+        .iter()
+        .filter(|t| **t != PrimitiveType::Null)
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    members.dedup();
+    match members.as_slice() {
+        [] => ("Object".to_string(), " // null".to_string()),
+        [member] => (primitive_type(**member).to_string(), String::new()),
+        members => (
+            "Object".to_string(),
+            format!(
+                " // {}",
+                members
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            ),
+        ),
+    }
+}
+
+fn primitive_type(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "String",
+        // JSON does not distinguish integers.
+        PrimitiveType::Number => "Double",
+        PrimitiveType::Boolean => "Boolean",
+        PrimitiveType::Null => "Object",
+    }
+}
+
+/// The camelCase field name for `key`, followed by `_` if it is a keyword.
+fn field_name(key: &str) -> String {
+    let mut name = camel_case(key)
+        .chars()
+        .map(|c| if is_xid_continue(c) { c } else { '_' })
+        .collect::<String>();
+    if !name.starts_with(|c| is_xid_start(c) || c == '_') {
+        name.insert(0, '_');
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+/// Formats a Java string literal. Control characters use octal escapes, since `\u` escapes are
+/// translated before the literal is parsed.
+fn string_literal(s: &str) -> String {
+    let mut literal = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\{:o}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,104 @@
+use crate::types::{InferredType, PrimitiveType};
+use serde_json::{Map, Value, json};
+
+/// Converts an inferred type into its JSON Schema (draft 2020-12) equivalent.
+pub fn to_json_schema(inferred_type: &InferredType) -> Value {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => json!({ "type": json_type_name(*prim_type) }),
+        InferredType::Any => json!({}),
+        InferredType::PrimitiveUnion(types) => json!({
+            "type": types.iter().map(|t| json_type_name(*t)).collect::<Vec<_>>()
+        }),
+        InferredType::PrimitiveTuple(types) => tuple_schema(types),
+        InferredType::TupleUnion(tuples) => json!({
+            "anyOf": tuples.iter().map(|types| tuple_schema(types)).collect::<Vec<_>>()
+        }),
+        InferredType::Array(item_type) => json!({
+            "type": "array",
+            "items": to_json_schema(item_type),
+        }),
+        InferredType::Object(properties) => {
+            let mut required = properties
+                .iter()
+                .filter(|(_, prop_def)| !prop_def.optional)
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>();
+            required.sort_unstable();
+            let properties = properties
+                .iter()
+                .map(|(key, prop_def)| (key.clone(), to_json_schema(&prop_def.r#type)))
+                .collect::<Map<_, _>>();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            })
+        }
+        InferredType::ComplexUnion(members) => json!({
+            "anyOf": members.iter().map(to_json_schema).collect::<Vec<_>>()
+        }),
+        InferredType::NullableObj(obj) => json!({
+            "anyOf": [to_json_schema(obj), { "type": "null" }]
+        }),
+        // JSON has no `undefined`, so only the defined values are described.
+        InferredType::Optional(inner) | InferredType::Branded { inner, .. } => {
+            to_json_schema(inner)
+        }
+        InferredType::Reference(name) => json!({ "$ref": format!("#/$defs/{name}") }),
+        InferredType::Never => json!({ "not": {} }),
+    }
+}
+
+/// Builds a standalone schema document for validators such as AJV, identified by `schema_id`. The
+/// document validates `root_name`, whose schema is `root`. `definitions` are placed under `$defs`,
+/// where the `$ref`s to named types resolve.
+pub fn schema_document(
+    schema_id: &str,
+    root_name: &str,
+    root: Value,
+    definitions: Vec<(String, &InferredType)>,
+) -> Value {
+    let mut defs = definitions
+        .into_iter()
+        .map(|(name, inferred_type)| (name, to_json_schema(inferred_type)))
+        .collect::<Map<_, _>>();
+    defs.insert(root_name.to_string(), root);
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": schema_id,
+        "$ref": format!("#/$defs/{root_name}"),
+        "$defs": defs,
+    })
+}
+
+/// The schema of a `{ type, content }` event whose content is the `content_name` definition.
+pub fn event_schema(tag: Value, content_name: &str) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "type": tag,
+            "content": { "$ref": format!("#/$defs/{content_name}") },
+        },
+        "required": ["type", "content"],
+    })
+}
+
+fn tuple_schema(types: &[PrimitiveType]) -> Value {
+    json!({
+        "type": "array",
+        "prefixItems": types
+            .iter()
+            .map(|t| json!({ "type": json_type_name(*t) }))
+            .collect::<Vec<_>>(),
+        "items": false,
+    })
+}
+
+fn json_type_name(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "string",
+        PrimitiveType::Number => "number",
+        PrimitiveType::Boolean => "boolean",
+        PrimitiveType::Null => "null",
+    }
+}
@@ -0,0 +1,145 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::types::{InferredType, InputData, PrimitiveType, TagType};
+use anyhow::{Result, ensure};
+use serde_json::{Map, Value, json};
+
+pub fn generate_jtd(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_jtd_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a JSON Type Definition (RFC 8927) schema for the root union, discriminated by
+/// `type`, with each event type's content schema under `definitions`. JTD schemas are anonymous,
+/// so `root_name` is left to code generators such as `jtd-codegen`.
+pub fn generate_jtd_with_options(
+    json_array: Vec<InputData>,
+    _root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    ensure!(
+        options.tag_type == TagType::String,
+        "JTD discriminators are strings, so JTD output requires string tags"
+    );
+    let InferredEvents { types, .. } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut definitions = Map::new();
+    let mut mapping = Map::new();
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        definitions.insert(content_name.clone(), to_jtd(inferred_type));
+        mapping.insert(
+            event_type_key.clone(),
+            json!({ "properties": { "content": { "ref": content_name } } }),
+        );
+    }
+
+    let schema = json!({
+        "definitions": definitions,
+        "discriminator": "type",
+        "mapping": mapping,
+    });
+    Ok(serde_json::to_string_pretty(&schema)? + "\n")
+}
+
+/// Converts an inferred type into its JTD equivalent. JTD has no tuples or unions apart from
+/// discriminated ones, so tuples become `elements` forms and other unions become the empty form,
+/// which accepts any value, with the members described in its `metadata`.
+fn to_jtd(inferred_type: &InferredType) -> Value {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_union(&[*prim_type]),
+        InferredType::Any | InferredType::Never => json!({}),
+        InferredType::PrimitiveUnion(types) => primitive_union(types),
+        InferredType::PrimitiveTuple(types) => json!({ "elements": primitive_union(types) }),
+        InferredType::TupleUnion(tuples) => {
+            let types = tuples.iter().flatten().copied().collect::<Vec<_>>();
+            json!({ "elements": primitive_union(&types) })
+        }
+        InferredType::Array(item_type) => json!({ "elements": to_jtd(item_type) }),
+        InferredType::Object(properties) => {
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            properties.sort_by_key(|(key, _)| *key);
+            let mut required = Map::new();
+            let mut optional = Map::new();
+            for (key, prop_def) in properties {
+                let schema = to_jtd(&prop_def.r#type);
+                if prop_def.optional {
+                    optional.insert(key.clone(), schema);
+                } else {
+                    required.insert(key.clone(), schema);
+                }
+            }
+            let mut schema = Map::new();
+            if !optional.is_empty() {
+                schema.insert("optionalProperties".to_string(), Value::Object(optional));
+            }
+            // The properties form needs at least one of its keywords.
+            if !required.is_empty() || schema.is_empty() {
+                schema.insert("properties".to_string(), Value::Object(required));
+            }
+            Value::Object(schema)
+        }
+        InferredType::ComplexUnion(members) => {
+            let members = members
+                .iter()
+                .map(|member| match member {
+                    InferredType::Object(_) => "object",
+                    InferredType::Array(_) => "array",
+                    _ => "value",
+                })
+                .collect::<Vec<_>>();
+            json!({ "metadata": { "description": members.join(" | ") } })
+        }
+        InferredType::NullableObj(inner) => nullable(to_jtd(inner)),
+        // Optional properties are listed under `optionalProperties`, and constraints are not
+        // expressible, so both are described by their inner type.
+        InferredType::Optional(inner) | InferredType::Branded { inner, .. } => to_jtd(inner),
+        InferredType::Reference(name) => json!({ "ref": name }),
+    }
+}
+
+/// Converts a union of primitive types, possibly repeated, into the type form of its single
+/// non-null member, or the empty form describing the members.
+fn primitive_union(types: &[PrimitiveType]) -> Value {
+    let mut members = types
+        .iter()
+        .filter(|t| **t != PrimitiveType::Null)
+        .copied()
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    members.dedup();
+    let schema = match members.as_slice() {
+        [] => json!({}),
+        [member] => json!({ "type": jtd_type_name(*member) }),
+        members => {
+            let members = members
+                .iter()
+                .map(|t| jtd_type_name(*t))
+                .collect::<Vec<_>>();
+            // The empty form already accepts `null`.
+            return json!({ "metadata": { "description": members.join(" | ") } });
+        }
+    };
+    if types.contains(&PrimitiveType::Null) {
+        nullable(schema)
+    } else {
+        schema
+    }
+}
+
+fn nullable(mut schema: Value) -> Value {
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("nullable".to_string(), Value::Bool(true));
+        schema.sort_keys();
+    }
+    schema
+}
+
+fn jtd_type_name(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "string",
+        // JSON does not distinguish integers.
+        PrimitiveType::Number => "float64",
+        PrimitiveType::Boolean => "boolean",
+        PrimitiveType::Null => unreachable!("null is expressed with `nullable`"),
+    }
+}
@@ -0,0 +1,305 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::{Context as _, Result};
+use serde_json::Value;
+use stringcase::pascal_case;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// Words that can only name a parameter when escaped with backticks.
+const KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+/// Characters that cannot appear in a backticked name on the JVM.
+const FORBIDDEN_NAME_CHARS: &[char] = &['.', ';', '[', ']', '/', '<', '>', ':', '\\', '`'];
+
+pub fn generate_kotlin(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_kotlin_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates Kotlin data classes for each event type's content and a sealed class for the root
+/// union, with a subclass per event type. Nested objects become data classes named after their
+/// parent and key, e.g. `LoginContentProfile`, and unions become sealed classes wrapping each
+/// member.
+pub fn generate_kotlin_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut definitions = Vec::new();
+    let mut events = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!("// The 'content' field contained invalid JSON: \"{invalid_json}\"\n")
+        });
+        if let InferredType::Object(properties) = inferred_type {
+            push_data_class(
+                &mut definitions,
+                &content_name,
+                properties,
+                options,
+                comment,
+            );
+        } else {
+            let content_type = format_type(&mut definitions, inferred_type, &content_name, options);
+            definitions.push(format!(
+                "{}typealias {content_name} = {content_type}\n",
+                comment.unwrap_or_default()
+            ));
+        }
+        events.push(format!(
+            "    data class {}(val content: {content_name}) : {root_name}() {{\n        override val type get() = {}\n    }}\n",
+            type_names.get(event_type_key, "Event", options),
+            kotlin_literal(options.tag_type, event_type_key)?
+        ));
+    }
+
+    definitions.push(if events.is_empty() {
+        format!("sealed class {root_name}\n")
+    } else {
+        format!(
+            "sealed class {root_name} {{\n    abstract val type: {}\n\n{}}}\n",
+            tag_type_name(options.tag_type),
+            events.join("\n")
+        )
+    });
+    Ok(definitions.join("\n"))
+}
+
+/// Adds a data class for an object type, after the classes of its nested types.
+fn push_data_class(
+    definitions: &mut Vec<String>,
+    class_name: &str,
+    properties: &PropertyMap,
+    options: &GenerationOptions,
+    comment: Option<String>,
+) {
+    let comment = comment.unwrap_or_default();
+    // A data class needs at least one parameter.
+    if properties.is_empty() {
+        definitions.push(format!("{comment}class {class_name}\n"));
+        return;
+    }
+    let mut properties = properties.iter().collect::<Vec<_>>();
+    if options.format.property_order == PropertyOrder::Alphabetical {
+        properties.sort_by_key(|(key, _)| *key);
+    }
+
+    let mut parameters = String::new();
+    for (key, prop_def) in properties {
+        let nested_name = format!("{class_name}{}", pascal_case(key));
+        let mut param_type = format_type(definitions, &prop_def.r#type, &nested_name, options);
+        let default = if prop_def.optional {
+            param_type = nullable(param_type);
+            " = null"
+        } else {
+            ""
+        };
+        parameters.push_str(&format!(
+            "    val {}: {param_type}{default},\n",
+            parameter_name(key)
+        ));
+    }
+    definitions.push(format!(
+        "{comment}data class {class_name}(\n{parameters})\n"
+    ));
+}
+
+/// Formats a Kotlin type, adding the classes it needs under names derived from `class_name`.
+fn format_type(
+    definitions: &mut Vec<String>,
+    inferred_type: &InferredType,
+    class_name: &str,
+    options: &GenerationOptions,
+) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_type(*prim_type).to_string(),
+        InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+            "Any?".to_string()
+        }
+        InferredType::Never => "Nothing".to_string(),
+        InferredType::PrimitiveUnion(types) => {
+            let members = types
+                .iter()
+                .filter(|t| **t != PrimitiveType::Null)
+                .map(|t| (primitive_type(*t), primitive_type(*t).to_string()))
+                .collect::<Vec<_>>();
+            let union = match members.as_slice() {
+                [(_, member_type)] => member_type.clone(),
+                _ => push_sealed_union(definitions, class_name, &members),
+            };
+            if types.contains(&PrimitiveType::Null) {
+                nullable(union)
+            } else {
+                union
+            }
+        }
+        InferredType::PrimitiveTuple(types) => tuple_type(types),
+        InferredType::TupleUnion(tuples) => {
+            if tuples.iter().flatten().any(|t| *t == PrimitiveType::Null) {
+                "List<Any?>".to_string()
+            } else {
+                "List<Any>".to_string()
+            }
+        }
+        InferredType::Array(item_type) => format!(
+            "List<{}>",
+            format_type(
+                definitions,
+                item_type,
+                &format!("{class_name}Item"),
+                options
+            )
+        ),
+        InferredType::Object(properties) => {
+            push_data_class(definitions, class_name, properties, options, None);
+            class_name.to_string()
+        }
+        InferredType::ComplexUnion(members) => {
+            let members = members
+                .iter()
+                .map(|member| {
+                    let kind = match member {
+                        InferredType::Object(_) => "Object",
+                        InferredType::Array(_) => "List",
+                        _ => "Other",
+                    };
+                    let member_type =
+                        format_type(definitions, member, &format!("{class_name}{kind}"), options);
+                    (kind, member_type)
+                })
+                .collect::<Vec<_>>();
+            push_sealed_union(definitions, class_name, &members)
+        }
+        InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+            nullable(format_type(definitions, inner, class_name, options))
+        }
+    }
+}
+
+/// Adds a sealed class with a `{Kind}Value` subclass wrapping each member type, and returns its
+/// name.
+fn push_sealed_union(
+    definitions: &mut Vec<String>,
+    class_name: &str,
+    members: &[(&str, String)],
+) -> String {
+    let subclasses = members
+        .iter()
+        .map(|(kind, member_type)| {
+            format!("    data class {kind}Value(val value: {member_type}) : {class_name}()\n")
+        })
+        .collect::<String>();
+    definitions.push(format!("sealed class {class_name} {{\n{subclasses}}}\n"));
+    class_name.to_string()
+}
+
+fn nullable(kotlin_type: String) -> String {
+    if kotlin_type.ends_with('?') {
+        kotlin_type
+    } else {
+        format!("{kotlin_type}?")
+    }
+}
+
+fn tuple_type(types: &[PrimitiveType]) -> String {
+    let element_types = types.iter().map(|t| primitive_type(*t)).collect::<Vec<_>>();
+    match element_types.as_slice() {
+        [first, second] => format!("Pair<{first}, {second}>"),
+        [first, second, third] => format!("Triple<{first}, {second}, {third}>"),
+        _ if types.contains(&PrimitiveType::Null) => "List<Any?>".to_string(),
+        _ => "List<Any>".to_string(),
+    }
+}
+
+fn primitive_type(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "String",
+        // JSON does not distinguish integers.
+        PrimitiveType::Number => "Double",
+        PrimitiveType::Boolean => "Boolean",
+        PrimitiveType::Null => "Nothing?",
+    }
+}
+
+/// The parameter name for `key`, escaped with backticks unless it is a plain identifier.
+fn parameter_name(key: &str) -> String {
+    let mut chars = key.chars();
+    let is_identifier =
+        chars.next().is_some_and(|c| is_xid_start(c) || c == '_') && chars.all(is_xid_continue);
+    if is_identifier && !KEYWORDS.contains(&key) {
+        return key.to_string();
+    }
+    let name = key
+        .chars()
+        .map(|c| {
+            if FORBIDDEN_NAME_CHARS.contains(&c) || c.is_control() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    format!("`{name}`")
+}
+
+fn tag_type_name(tag_type: TagType) -> &'static str {
+    match tag_type {
+        TagType::String | TagType::Any => "String",
+        TagType::Number => "Double",
+        TagType::Bool => "Boolean",
+    }
+}
+
+/// Formats a tag key as a Kotlin literal of [`tag_type_name`]'s type.
+fn kotlin_literal(tag_type: TagType, tag_key: &str) -> Result<String> {
+    Ok(match tag_type {
+        // JSON string escapes are also valid in Kotlin, apart from the `$` of string templates.
+        TagType::String | TagType::Any => Value::from(tag_key).to_string().replace('$', "\\$"),
+        TagType::Number => format!(
+            "{:?}",
+            tag_key
+                .parse::<f64>()
+                .with_context(|| format!("Event type {tag_key:?} is not a number tag"))?
+        ),
+        TagType::Bool => tag_key.to_string(),
+    })
+}
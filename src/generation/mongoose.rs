@@ -0,0 +1,209 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key, format_type_to_ts_string_with_options},
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::{Result, ensure};
+
+/// The schema type of values Mongoose does not cast or validate.
+const MIXED: &str = "Schema.Types.Mixed";
+
+pub fn generate_mongoose(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_mongoose_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a TypeScript module with a Mongoose schema for each object content type, e.g.
+/// `loginContentSchema`, and a schema for the root union with a discriminator per event type keyed
+/// on `type`. Every content type gets a TypeScript type, and object content types and the root
+/// union also get a document type, e.g. `LoginContentDocument = LoginContent & Document`.
+pub fn generate_mongoose_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    ensure!(
+        options.tag_type == TagType::String,
+        "Mongoose discriminator names are strings, so Mongoose output requires string tags"
+    );
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut output = "import { type Document, Schema } from \"mongoose\";\n\n".to_string();
+    let mut members = Vec::with_capacity(types.len());
+    let mut discriminators = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let type_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            output.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+        output.push_str(&format!(
+            "export type {type_name} = {};\n",
+            format_type_to_ts_string_with_options(inferred_type.clone(), &options.format)
+        ));
+        let content_required = !is_nullable(inferred_type);
+        let content_type = if let InferredType::Object(properties) = inferred_type {
+            let schema_name = format!("{}Schema", lower_first(&type_name));
+            output.push_str(&format!(
+                "export const {schema_name} = {};\n",
+                schema(properties, options, 0)
+            ));
+            output.push_str(&format!(
+                "export type {type_name}Document = {type_name} & Document;\n"
+            ));
+            schema_name
+        } else {
+            schema_type(inferred_type, options, 3)
+        };
+        output.push('\n');
+
+        members.push(format!(
+            "{{ type: {}, content: {type_name} }}",
+            options.tag_type.format_literal(event_type_key)
+        ));
+        discriminators.push(format!(
+            "{}.discriminator(\n  {},\n  new Schema({{\n    content: {},\n  }}),\n);\n",
+            root_schema_name(root_name),
+            options.tag_type.format_literal(event_type_key),
+            field(&content_type, content_required, 2)
+        ));
+    }
+
+    let root_type = if members.is_empty() {
+        "never".to_string()
+    } else {
+        members.join(" | ")
+    };
+    output.push_str(&format!(
+        "export type {root_name} = {root_type};\nexport const {} = new Schema({{}}, {{ discriminatorKey: \"type\" }});\n{}export type {root_name}Document = {root_name} & Document;\n",
+        root_schema_name(root_name),
+        discriminators.concat()
+    ));
+    Ok(output)
+}
+
+/// Formats a `new Schema(...)` expression for an object type at `depth` levels of indentation.
+/// Nested schemas are subdocuments, so they get no `_id` of their own.
+fn schema(properties: &PropertyMap, options: &GenerationOptions, depth: usize) -> String {
+    if properties.is_empty() {
+        return "new Schema({}, { _id: false })".to_string();
+    }
+    let mut properties = properties.iter().collect::<Vec<_>>();
+    if options.format.property_order == PropertyOrder::Alphabetical {
+        properties.sort_by_key(|(key, _)| *key);
+    }
+    let indent = "  ".repeat(depth + 1);
+    let entry_indent = "  ".repeat(depth + 2);
+    let entries = properties
+        .into_iter()
+        .map(|(key, prop_def)| {
+            let required = !prop_def.optional && !is_nullable(&prop_def.r#type);
+            format!(
+                "{entry_indent}{}: {},\n",
+                format_property_key(key),
+                field(
+                    &schema_type(&prop_def.r#type, options, depth + 3),
+                    required,
+                    depth + 2
+                )
+            )
+        })
+        .collect::<String>();
+    format!(
+        "new Schema(\n{indent}{{\n{entries}{indent}}},\n{indent}{{ _id: false }},\n{})",
+        "  ".repeat(depth)
+    )
+}
+
+/// Formats the definition of a path with its schema type, on one line unless the type spans
+/// several lines.
+fn field(schema_type: &str, required: bool, depth: usize) -> String {
+    if !schema_type.contains('\n') {
+        return format!("{{ type: {schema_type}, required: {required} }}");
+    }
+    let indent = "  ".repeat(depth + 1);
+    format!(
+        "{{\n{indent}type: {schema_type},\n{indent}required: {required},\n{}}}",
+        "  ".repeat(depth)
+    )
+}
+
+/// The schema type of `inferred_type`. Unions, which Mongoose has no schema type for, are
+/// `Schema.Types.Mixed`.
+fn schema_type(inferred_type: &InferredType, options: &GenerationOptions, depth: usize) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_union(&[*prim_type]).to_string(),
+        InferredType::PrimitiveUnion(types) => primitive_union(types).to_string(),
+        InferredType::PrimitiveTuple(types) => format!("[{{ type: {} }}]", primitive_union(types)),
+        InferredType::TupleUnion(tuples) => {
+            let types = tuples.iter().flatten().copied().collect::<Vec<_>>();
+            format!("[{{ type: {} }}]", primitive_union(&types))
+        }
+        InferredType::Array(item_type) => {
+            let item_type = schema_type(item_type, options, depth + 2);
+            if !item_type.contains('\n') {
+                return format!("[{{ type: {item_type} }}]");
+            }
+            format!(
+                "[\n{indent}  {{\n{indent}    type: {item_type},\n{indent}  }},\n{indent}]",
+                indent = "  ".repeat(depth)
+            )
+        }
+        InferredType::Object(properties) => schema(properties, options, depth),
+        // Whether a path may be null or missing is expressed with `required`, and constraints
+        // need custom validators, so these are described by their inner type.
+        InferredType::NullableObj(inner)
+        | InferredType::Optional(inner)
+        | InferredType::Branded { inner, .. } => schema_type(inner, options, depth),
+        InferredType::Any
+        | InferredType::Never
+        | InferredType::ComplexUnion(_)
+        | InferredType::Reference(_) => MIXED.to_string(),
+    }
+}
+
+/// The schema type of a union of primitive types, possibly repeated: the type of its single
+/// non-null member, or `Schema.Types.Mixed`.
+fn primitive_union(types: &[PrimitiveType]) -> &'static str {
+    let mut members = types
+        .iter()
+        .filter(|t| **t != PrimitiveType::Null)
+        .copied()
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    members.dedup();
+    match members.as_slice() {
+        [PrimitiveType::String] => "String",
+        [PrimitiveType::Number] => "Number",
+        [PrimitiveType::Boolean] => "Boolean",
+        _ => MIXED,
+    }
+}
+
+/// Whether `inferred_type` accepts `null` or `undefined`, which `required: true` rejects.
+fn is_nullable(inferred_type: &InferredType) -> bool {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => *prim_type == PrimitiveType::Null,
+        InferredType::PrimitiveUnion(types) => types.contains(&PrimitiveType::Null),
+        InferredType::Any | InferredType::NullableObj(_) | InferredType::Optional(_) => true,
+        InferredType::Branded { inner, .. } => is_nullable(inner),
+        _ => false,
+    }
+}
+
+fn root_schema_name(root_name: &str) -> String {
+    format!("{}Schema", lower_first(root_name))
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,47 @@
+use super::{
+    GenerationOptions, InferredEvents, TypeNames, infer_event_types, json_schema::to_json_schema,
+};
+use crate::types::InputData;
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+
+pub fn generate_openrpc(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_openrpc_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates an OpenRPC document with a method per event type, taking the event's content as its
+/// single `content` parameter. Content schemas are shared through `components.schemas`.
+pub fn generate_openrpc_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents { types, .. } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut schemas = Map::new();
+    let methods = types
+        .iter()
+        .map(|(event_type_key, inferred_type)| {
+            let schema_name = type_names.get(event_type_key, "Content", options);
+            schemas.insert(schema_name.clone(), to_json_schema(inferred_type));
+            json!({
+                "name": event_type_key,
+                "paramStructure": "by-name",
+                "params": [{
+                    "name": "content",
+                    "required": true,
+                    "schema": { "$ref": format!("#/components/schemas/{schema_name}") },
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let document = json!({
+        "openrpc": "1.3.2",
+        "info": { "title": root_name, "version": "1.0.0" },
+        "methods": methods,
+        "components": { "schemas": Value::Object(schemas) },
+    });
+    Ok(serde_json::to_string_pretty(&document)? + "\n")
+}
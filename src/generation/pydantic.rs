@@ -0,0 +1,290 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyDefinition, PropertyMap},
+};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
+use stringcase::pascal_case;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// Words that cannot name a Python attribute.
+pub(super) const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+pub fn generate_pydantic(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_pydantic_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates Pydantic v2 models for each event type's content, an event model per event type and
+/// the root discriminated union. Nested objects become models named after their parent and key,
+/// e.g. `LoginContentProfile`.
+pub fn generate_pydantic_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut module = Module::default();
+    let mut events = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!("# The 'content' field contained invalid JSON: \"{invalid_json}\"\n")
+        });
+        if let InferredType::Object(properties) = inferred_type {
+            module.push_model(&content_name, properties, options, comment);
+        } else {
+            let annotation = module.format_annotation(inferred_type, &content_name, options);
+            module.definitions.push(format!(
+                "{}{content_name} = {annotation}\n",
+                comment.unwrap_or_default()
+            ));
+        }
+
+        let event_name = type_names.get(event_type_key, "Event", options);
+        module.typing.insert("Literal");
+        module.pydantic.insert("BaseModel");
+        module.definitions.push(format!(
+            "class {event_name}(BaseModel):\n    type: Literal[{}]\n    content: {content_name}\n",
+            python_literal(&options.tag_type.json_value(event_type_key))
+        ));
+        events.push(event_name);
+    }
+
+    let root = match events.as_slice() {
+        [] => {
+            module.typing.insert("Never");
+            "Never".to_string()
+        }
+        [event] => event.clone(),
+        events => {
+            module.typing.extend(["Annotated", "Union"]);
+            module.pydantic.insert("Field");
+            format!(
+                "Annotated[Union[{}], Field(discriminator=\"type\")]",
+                events.join(", ")
+            )
+        }
+    };
+    module.definitions.push(format!("{root_name} = {root}\n"));
+
+    let mut output = String::new();
+    for (package, names) in [("typing", &module.typing), ("pydantic", &module.pydantic)] {
+        if !names.is_empty() {
+            output.push_str(&format!(
+                "from {package} import {}\n",
+                names.iter().copied().collect::<Vec<_>>().join(", ")
+            ));
+        }
+    }
+    output.push_str("\n\n");
+    output.push_str(&module.definitions.join("\n\n"));
+    Ok(output)
+}
+
+/// The definitions of a module, in dependency order, and the names they import.
+#[derive(Default)]
+struct Module {
+    typing: BTreeSet<&'static str>,
+    pydantic: BTreeSet<&'static str>,
+    definitions: Vec<String>,
+}
+
+impl Module {
+    /// Adds a model for an object type, after the models of its nested objects.
+    fn push_model(
+        &mut self,
+        model_name: &str,
+        properties: &PropertyMap,
+        options: &GenerationOptions,
+        comment: Option<String>,
+    ) {
+        self.pydantic.insert("BaseModel");
+        let mut properties = properties.iter().collect::<Vec<_>>();
+        if options.format.property_order == PropertyOrder::Alphabetical {
+            properties.sort_by_key(|(key, _)| *key);
+        }
+
+        let mut field_names = HashSet::new();
+        let mut fields = String::new();
+        for (key, prop_def) in properties {
+            let field_line =
+                self.format_field(model_name, key, prop_def, options, &mut field_names);
+            fields.push_str(&format!("    {field_line}\n"));
+        }
+        if fields.is_empty() {
+            fields.push_str("    pass\n");
+        }
+        self.definitions.push(format!(
+            "{}class {model_name}(BaseModel):\n{fields}",
+            comment.unwrap_or_default()
+        ));
+    }
+
+    fn format_field(
+        &mut self,
+        model_name: &str,
+        key: &str,
+        prop_def: &PropertyDefinition,
+        options: &GenerationOptions,
+        field_names: &mut HashSet<String>,
+    ) -> String {
+        let nested_name = format!("{model_name}{}", pascal_case(key));
+        let mut annotation = self.format_annotation(&prop_def.r#type, &nested_name, options);
+        if prop_def.optional && !accepts_none(&prop_def.r#type) {
+            annotation.push_str(" | None");
+        }
+
+        let mut field_name = field_name(key);
+        while !field_names.insert(field_name.clone()) {
+            field_name.push('_');
+        }
+        let default = match (field_name != key, prop_def.optional) {
+            (false, false) => String::new(),
+            (false, true) => " = None".to_string(),
+            (true, optional) => {
+                self.pydantic.insert("Field");
+                let default = if optional { "default=None, " } else { "" };
+                format!(
+                    " = Field({default}alias={})",
+                    python_literal(&Value::from(key))
+                )
+            }
+        };
+        format!("{field_name}: {annotation}{default}")
+    }
+
+    /// Formats a type annotation, adding a model named `model_name` for an object type.
+    fn format_annotation(
+        &mut self,
+        inferred_type: &InferredType,
+        model_name: &str,
+        options: &GenerationOptions,
+    ) -> String {
+        match inferred_type {
+            InferredType::Primitive(prim_type) => primitive_annotation(*prim_type).to_string(),
+            InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+                self.typing.insert("Any");
+                "Any".to_string()
+            }
+            InferredType::Never => {
+                self.typing.insert("Never");
+                "Never".to_string()
+            }
+            InferredType::PrimitiveUnion(types) => {
+                union_annotation(types.iter().map(|t| primitive_annotation(*t).to_string()))
+            }
+            InferredType::PrimitiveTuple(types) => tuple_annotation(types),
+            InferredType::TupleUnion(tuples) => {
+                union_annotation(tuples.iter().map(|types| tuple_annotation(types)))
+            }
+            InferredType::Array(item_type) => format!(
+                "list[{}]",
+                self.format_annotation(item_type, &format!("{model_name}Item"), options)
+            ),
+            InferredType::Object(properties) => {
+                self.push_model(model_name, properties, options, None);
+                model_name.to_string()
+            }
+            InferredType::ComplexUnion(members) => {
+                let members = members
+                    .iter()
+                    .map(|member| self.format_annotation(member, model_name, options))
+                    .collect::<Vec<_>>();
+                union_annotation(members.into_iter())
+            }
+            InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+                format!(
+                    "{} | None",
+                    self.format_annotation(inner, model_name, options)
+                )
+            }
+        }
+    }
+}
+
+/// Whether the annotation of `inferred_type` already accepts `None`.
+fn accepts_none(inferred_type: &InferredType) -> bool {
+    match inferred_type {
+        InferredType::Primitive(PrimitiveType::Null)
+        | InferredType::Any
+        | InferredType::NullableObj(_)
+        | InferredType::Optional(_) => true,
+        InferredType::PrimitiveUnion(types) => types.contains(&PrimitiveType::Null),
+        _ => false,
+    }
+}
+
+/// The attribute name for `key`, which is aliased to the key when they differ. Leading
+/// underscores are reserved for private attributes and `model_` for Pydantic itself.
+fn field_name(key: &str) -> String {
+    let mut chars = key.chars();
+    let is_identifier = chars.next().is_some_and(is_xid_start) && chars.all(is_xid_continue);
+    if is_identifier && !KEYWORDS.contains(&key) && !key.starts_with("model_") {
+        return key.to_string();
+    }
+    let mut name = key
+        .chars()
+        .map(|c| if is_xid_continue(c) { c } else { '_' })
+        .collect::<String>();
+    if !name.chars().next().is_some_and(is_xid_start) || name.starts_with("model_") {
+        name.insert_str(0, "field_");
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.push('_');
+    }
+    name
+}
+
+fn union_annotation(members: impl Iterator<Item = String>) -> String {
+    members.collect::<Vec<_>>().join(" | ")
+}
+
+fn tuple_annotation(types: &[PrimitiveType]) -> String {
+    if types.is_empty() {
+        return "tuple[()]".to_string();
+    }
+    format!(
+        "tuple[{}]",
+        types
+            .iter()
+            .map(|t| primitive_annotation(*t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn primitive_annotation(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "str",
+        // JSON does not distinguish integers, and `float` accepts them.
+        PrimitiveType::Number => "float",
+        PrimitiveType::Boolean => "bool",
+        PrimitiveType::Null => "None",
+    }
+}
+
+/// Formats a tag value as a Python literal. Values that cannot appear in `Literal`, such as
+/// objects, are keyed by their JSON serialization.
+pub(super) fn python_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "None".to_string(),
+        Value::Bool(true) => "True".to_string(),
+        Value::Bool(false) => "False".to_string(),
+        // JSON string escapes are also valid in Python.
+        Value::Number(_) | Value::String(_) => value.to_string(),
+        Value::Array(_) | Value::Object(_) => Value::String(value.to_string()).to_string(),
+    }
+}
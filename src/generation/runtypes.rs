@@ -0,0 +1,176 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key},
+    types::{InferredType, InputData, PrimitiveType},
+};
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+pub fn generate_runtypes(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_runtypes_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a `runtypes` runtype, and the static type derived from it, for each event type's
+/// content and for the root union. Only the runtypes that are used are imported.
+pub fn generate_runtypes_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut imports = BTreeSet::new();
+    let mut body = String::new();
+    let mut members = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let runtype_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            body.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+        body.push_str(&format!(
+            "export const {runtype_name} = {};\nexport type {runtype_name} = Static<typeof {runtype_name}>;\n\n",
+            format_runtype(inferred_type, options, 0, &mut imports)
+        ));
+        members.push(format!(
+            "Record({{ type: Literal({}), content: {runtype_name} }})",
+            options.tag_type.format_literal(event_type_key)
+        ));
+    }
+
+    let root_runtype = match members.as_slice() {
+        [] => runtype("Never", &mut imports),
+        [member] => member.clone(),
+        members => {
+            imports.insert("Union");
+            format!("Union(\n  {},\n)", members.join(",\n  "))
+        }
+    };
+    if !members.is_empty() {
+        imports.extend(["Literal", "Record"]);
+    }
+    body.push_str(&format!(
+        "export const {root_name} = {root_runtype};\nexport type {root_name} = Static<typeof {root_name}>;\n"
+    ));
+
+    Ok(format!(
+        "import {{ type Static, {} }} from \"runtypes\";\n\n{body}",
+        imports.into_iter().collect::<Vec<_>>().join(", ")
+    ))
+}
+
+fn format_runtype(
+    inferred_type: &InferredType,
+    options: &GenerationOptions,
+    depth: usize,
+    imports: &mut BTreeSet<&'static str>,
+) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_runtype(*prim_type, imports),
+        InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+            runtype("Unknown", imports)
+        }
+        InferredType::Never => runtype("Never", imports),
+        InferredType::PrimitiveUnion(types) => {
+            let members = types
+                .iter()
+                .map(|t| primitive_runtype(*t, imports))
+                .collect::<Vec<_>>();
+            combinator("Union", &members, imports)
+        }
+        InferredType::PrimitiveTuple(types) => tuple_runtype(types, imports),
+        InferredType::TupleUnion(tuples) => {
+            let members = tuples
+                .iter()
+                .map(|types| tuple_runtype(types, imports))
+                .collect::<Vec<_>>();
+            combinator("Union", &members, imports)
+        }
+        InferredType::Array(item_type) => {
+            let item_runtype = format_runtype(item_type, options, depth, imports);
+            combinator("Array", &[item_runtype], imports)
+        }
+        InferredType::Object(properties) => {
+            imports.insert("Record");
+            if properties.is_empty() {
+                return "Record({})".to_string();
+            }
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            let indent = "  ".repeat(depth + 1);
+            let entries = properties
+                .into_iter()
+                .map(|(key, prop_def)| {
+                    let mut value_runtype =
+                        format_runtype(&prop_def.r#type, options, depth + 1, imports);
+                    if prop_def.optional {
+                        value_runtype = combinator("Optional", &[value_runtype], imports);
+                    }
+                    format!("{indent}{}: {value_runtype}", format_property_key(key))
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "Record({{\n{},\n{}}})",
+                entries.join(",\n"),
+                "  ".repeat(depth)
+            )
+        }
+        InferredType::ComplexUnion(members) => {
+            let members = members
+                .iter()
+                .map(|member| format_runtype(member, options, depth, imports))
+                .collect::<Vec<_>>();
+            combinator("Union", &members, imports)
+        }
+        InferredType::NullableObj(inner) => {
+            let inner_runtype = format_runtype(inner, options, depth, imports);
+            let null_runtype = runtype("Null", imports);
+            combinator("Union", &[inner_runtype, null_runtype], imports)
+        }
+        // `Optional` is only allowed as a `Record` field, so a present `undefined` value is
+        // expressed as a union.
+        InferredType::Optional(inner) => {
+            let inner_runtype = format_runtype(inner, options, depth, imports);
+            let undefined_runtype = runtype("Undefined", imports);
+            combinator("Union", &[inner_runtype, undefined_runtype], imports)
+        }
+    }
+}
+
+/// Records `name` as an imported runtype and returns it.
+fn runtype(name: &'static str, imports: &mut BTreeSet<&'static str>) -> String {
+    imports.insert(name);
+    name.to_string()
+}
+
+/// Records `name` as an imported combinator and returns its application to `args`.
+fn combinator(name: &'static str, args: &[String], imports: &mut BTreeSet<&'static str>) -> String {
+    imports.insert(name);
+    format!("{name}({})", args.join(", "))
+}
+
+fn tuple_runtype(types: &[PrimitiveType], imports: &mut BTreeSet<&'static str>) -> String {
+    let members = types
+        .iter()
+        .map(|t| primitive_runtype(*t, imports))
+        .collect::<Vec<_>>();
+    combinator("Tuple", &members, imports)
+}
+
+fn primitive_runtype(prim_type: PrimitiveType, imports: &mut BTreeSet<&'static str>) -> String {
+    let name = match prim_type {
+        PrimitiveType::String => "String",
+        PrimitiveType::Number => "Number",
+        PrimitiveType::Boolean => "Boolean",
+        PrimitiveType::Null => "Null",
+    };
+    runtype(name, imports)
+}
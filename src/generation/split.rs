@@ -0,0 +1,65 @@
+use super::{
+    EventNameCase, GeneratedFiles, GenerationOptions, event_type_name, generate_typescript_files,
+};
+use crate::types::InputData;
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Generates a module per event type prefix group, such as `user` for `user/created` and
+/// `user/updated`, or `com.example.users` for `com.example.users.created`. Each module holds the group's content types and a partial union of its events,
+/// named like `UserEvents`. The root module re-exports every partial union and combines them into
+/// `root_name`.
+///
+/// The prefix is the first `prefix_length` components of the event type split at `separator`.
+/// Modules are named after the prefix with each separator replaced by `-`. Test fixtures are not
+/// generated for split output.
+pub fn generate_split_typescript_files(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    separator: &str,
+    prefix_length: usize,
+    options: &GenerationOptions,
+) -> Result<GeneratedFiles> {
+    let mut groups = BTreeMap::<String, Vec<InputData>>::new();
+    for input_data in json_array {
+        let prefix = input_data
+            .r#type
+            .split(separator)
+            .take(prefix_length)
+            .collect::<Vec<_>>()
+            .join(separator);
+        groups.entry(prefix).or_default().push(input_data);
+    }
+
+    let mut modules = BTreeMap::new();
+    let mut imports = String::new();
+    let mut union_names = Vec::with_capacity(groups.len());
+    for (prefix, records) in groups {
+        let union_name = format!(
+            "{}{root_name}",
+            event_type_name(&prefix, EventNameCase::Pascal)
+        );
+        let module_name = prefix.replace(separator, "-");
+        let generated = generate_typescript_files(records, &union_name, options)?;
+        imports.push_str(&format!(
+            "import type {{ {union_name} }} from \"./{module_name}\";\n"
+        ));
+        modules.insert(module_name, generated.definitions);
+        union_names.push(union_name);
+    }
+
+    let definitions = if union_names.is_empty() {
+        format!("export type {root_name} = never;\n")
+    } else {
+        format!(
+            "{imports}\nexport type {{ {} }};\nexport type {root_name} = {};\n",
+            union_names.join(", "),
+            union_names.join(" | ")
+        )
+    };
+    Ok(GeneratedFiles {
+        definitions,
+        modules,
+        ..Default::default()
+    })
+}
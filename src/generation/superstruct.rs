@@ -0,0 +1,185 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key},
+    types::{InferredType, InputData, PrimitiveType},
+};
+use anyhow::Result;
+use std::collections::BTreeSet;
+
+pub fn generate_superstruct(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_superstruct_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a `superstruct` struct, and the type inferred from it, for each event type's content
+/// and for the root union. Only the struct factories that are used are imported.
+pub fn generate_superstruct_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut imports = BTreeSet::new();
+    let mut body = String::new();
+    let mut members = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let struct_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            body.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+        body.push_str(&format!(
+            "export const {struct_name} = {};\nexport type {struct_name} = Infer<typeof {struct_name}>;\n\n",
+            format_struct(inferred_type, options, 0, &mut imports)
+        ));
+        members.push(format!(
+            "object({{ type: literal({}), content: {struct_name} }})",
+            options.tag_type.format_literal(event_type_key)
+        ));
+    }
+
+    // `union` requires at least one member.
+    let root_struct = match members.as_slice() {
+        [] => {
+            imports.insert("never");
+            "never()".to_string()
+        }
+        [member] => member.clone(),
+        members => {
+            imports.insert("union");
+            format!("union([\n  {},\n])", members.join(",\n  "))
+        }
+    };
+    if !members.is_empty() {
+        imports.extend(["literal", "object"]);
+    }
+    body.push_str(&format!(
+        "export const {root_name} = {root_struct};\nexport type {root_name} = Infer<typeof {root_name}>;\n"
+    ));
+
+    Ok(format!(
+        "import {{ type Infer, {} }} from \"superstruct\";\n\n{body}",
+        imports.into_iter().collect::<Vec<_>>().join(", ")
+    ))
+}
+
+fn format_struct(
+    inferred_type: &InferredType,
+    options: &GenerationOptions,
+    depth: usize,
+    imports: &mut BTreeSet<&'static str>,
+) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_struct(*prim_type, imports).to_string(),
+        InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+            factory("any", imports, "any()")
+        }
+        InferredType::Never => factory("never", imports, "never()"),
+        InferredType::PrimitiveUnion(types) => {
+            let members = types
+                .iter()
+                .map(|t| primitive_struct(*t, imports))
+                .collect::<Vec<_>>();
+            factory("union", imports, format!("union([{}])", members.join(", ")))
+        }
+        InferredType::PrimitiveTuple(types) => tuple_struct(types, imports),
+        InferredType::TupleUnion(tuples) => {
+            let members = tuples
+                .iter()
+                .map(|types| tuple_struct(types, imports))
+                .collect::<Vec<_>>();
+            factory("union", imports, format!("union([{}])", members.join(", ")))
+        }
+        InferredType::Array(item_type) => {
+            let item_struct = format_struct(item_type, options, depth, imports);
+            factory("array", imports, format!("array({item_struct})"))
+        }
+        InferredType::Object(properties) => {
+            imports.insert("object");
+            if properties.is_empty() {
+                return "object({})".to_string();
+            }
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            let indent = "  ".repeat(depth + 1);
+            let entries = properties
+                .into_iter()
+                .map(|(key, prop_def)| {
+                    let mut value_struct =
+                        format_struct(&prop_def.r#type, options, depth + 1, imports);
+                    // `optional` already accepts `undefined`, so it is not nested.
+                    if prop_def.optional && !matches!(prop_def.r#type, InferredType::Optional(_)) {
+                        value_struct =
+                            factory("optional", imports, format!("optional({value_struct})"));
+                    }
+                    format!("{indent}{}: {value_struct}", format_property_key(key))
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "object({{\n{},\n{}}})",
+                entries.join(",\n"),
+                "  ".repeat(depth)
+            )
+        }
+        InferredType::ComplexUnion(members) => {
+            let members = members
+                .iter()
+                .map(|member| format_struct(member, options, depth, imports))
+                .collect::<Vec<_>>();
+            factory("union", imports, format!("union([{}])", members.join(", ")))
+        }
+        InferredType::NullableObj(inner) => {
+            let inner_struct = format_struct(inner, options, depth, imports);
+            factory("nullable", imports, format!("nullable({inner_struct})"))
+        }
+        InferredType::Optional(inner) => {
+            let inner_struct = format_struct(inner, options, depth, imports);
+            factory("optional", imports, format!("optional({inner_struct})"))
+        }
+    }
+}
+
+/// Records `name` as an imported struct factory and returns the struct expression using it.
+fn factory(
+    name: &'static str,
+    imports: &mut BTreeSet<&'static str>,
+    formatted: impl Into<String>,
+) -> String {
+    imports.insert(name);
+    formatted.into()
+}
+
+fn tuple_struct(types: &[PrimitiveType], imports: &mut BTreeSet<&'static str>) -> String {
+    if types.is_empty() {
+        // `tuple` requires at least one element.
+        imports.extend(["array", "never"]);
+        return "array(never())".to_string();
+    }
+    let members = types
+        .iter()
+        .map(|t| primitive_struct(*t, imports))
+        .collect::<Vec<_>>();
+    factory("tuple", imports, format!("tuple([{}])", members.join(", ")))
+}
+
+fn primitive_struct(
+    prim_type: PrimitiveType,
+    imports: &mut BTreeSet<&'static str>,
+) -> &'static str {
+    let (name, formatted) = match prim_type {
+        PrimitiveType::String => ("string", "string()"),
+        PrimitiveType::Number => ("number", "number()"),
+        PrimitiveType::Boolean => ("boolean", "boolean()"),
+        PrimitiveType::Null => ("literal", "literal(null)"),
+    };
+    imports.insert(name);
+    formatted
+}
@@ -0,0 +1,499 @@
+use super::{
+    EventNameCase, GenerationOptions, InferredEvents, TypeNames, event_type_name, infer_event_types,
+};
+use crate::{
+    formatting::PropertyOrder,
+    types::{InferredType, InputData, PrimitiveType, PropertyMap, TagType},
+};
+use anyhow::{Context as _, Result, ensure};
+use std::collections::HashSet;
+use stringcase::pascal_case;
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+/// Words that can only name a property or case when escaped with backticks.
+const KEYWORDS: &[&str] = &[
+    "Any",
+    "Self",
+    "as",
+    "associatedtype",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "continue",
+    "default",
+    "defer",
+    "deinit",
+    "do",
+    "else",
+    "enum",
+    "extension",
+    "fallthrough",
+    "false",
+    "fileprivate",
+    "for",
+    "func",
+    "guard",
+    "if",
+    "import",
+    "in",
+    "init",
+    "inout",
+    "internal",
+    "is",
+    "let",
+    "nil",
+    "open",
+    "operator",
+    "private",
+    "protocol",
+    "public",
+    "repeat",
+    "rethrows",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "subscript",
+    "super",
+    "switch",
+    "throw",
+    "throws",
+    "true",
+    "try",
+    "typealias",
+    "var",
+    "where",
+    "while",
+];
+
+/// An enum holding any JSON value, for values whose type could not be inferred.
+const JSON_VALUE: &str = r#"enum JSONValue: Codable {
+    case null
+    case bool(Bool)
+    case number(Double)
+    case string(String)
+    case array([JSONValue])
+    case object([String: JSONValue])
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if container.decodeNil() {
+            self = .null
+        } else if let value = try? container.decode(Bool.self) {
+            self = .bool(value)
+        } else if let value = try? container.decode(Double.self) {
+            self = .number(value)
+        } else if let value = try? container.decode(String.self) {
+            self = .string(value)
+        } else if let value = try? container.decode([JSONValue].self) {
+            self = .array(value)
+        } else {
+            self = .object(try container.decode([String: JSONValue].self))
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .null: try container.encodeNil()
+        case .bool(let value): try container.encode(value)
+        case .number(let value): try container.encode(value)
+        case .string(let value): try container.encode(value)
+        case .array(let value): try container.encode(value)
+        case .object(let value): try container.encode(value)
+        }
+    }
+}
+"#;
+
+pub fn generate_swift(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_swift_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates `Codable` Swift structs for each event type's content and an enum for the root
+/// union, with a case per event type holding its content. Nested objects become structs named
+/// after their parent and key, e.g. `LoginContentProfile`, and unions become enums that decode
+/// whichever member matches.
+pub fn generate_swift_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    ensure!(
+        options.tag_type != TagType::Any,
+        "Swift output cannot switch over tags of any JSON type"
+    );
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut module = Module::default();
+    let mut cases = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let content_name = type_names.get(event_type_key, "Content", options);
+        let comment = invalid_json.get(event_type_key).map(|invalid_json| {
+            format!("// The 'content' field contained invalid JSON: \"{invalid_json}\"\n")
+        });
+        if let InferredType::Object(properties) = inferred_type {
+            module.push_struct(&content_name, properties, options, comment);
+        } else {
+            let content_type = module.format_type(inferred_type, &content_name, options);
+            module.definitions.push(format!(
+                "{}typealias {content_name} = {content_type}\n",
+                comment.unwrap_or_default()
+            ));
+        }
+        let mut case_name = format!(
+            "{}{}",
+            event_type_name(event_type_key, EventNameCase::Pascal),
+            type_names.disambiguation(event_type_key)
+        );
+        if let Some(first) = case_name.get(..1) {
+            case_name.replace_range(..1, &first.to_lowercase());
+        }
+        cases.push((
+            escape_name(&identifier(&case_name)),
+            tag_literal(options.tag_type, event_type_key)?,
+            content_name,
+        ));
+    }
+    module.push_root_enum(root_name, options.tag_type, &cases);
+
+    let mut output = "import Foundation\n\n".to_string();
+    if module.json_value {
+        output.push_str(JSON_VALUE);
+        output.push('\n');
+    }
+    output.push_str(&module.definitions.join("\n"));
+    Ok(output)
+}
+
+/// The definitions of a module, in dependency order.
+#[derive(Default)]
+struct Module {
+    definitions: Vec<String>,
+    /// Whether the [`JSON_VALUE`] enum is used.
+    json_value: bool,
+}
+
+impl Module {
+    /// Adds a struct for an object type, after the types of its properties. Keys that cannot name
+    /// a property are mapped with `CodingKeys`.
+    fn push_struct(
+        &mut self,
+        struct_name: &str,
+        properties: &PropertyMap,
+        options: &GenerationOptions,
+        comment: Option<String>,
+    ) {
+        let mut properties = properties.iter().collect::<Vec<_>>();
+        if options.format.property_order == PropertyOrder::Alphabetical {
+            properties.sort_by_key(|(key, _)| *key);
+        }
+
+        let mut names = HashSet::new();
+        let mut fields = String::new();
+        let mut coding_keys = String::new();
+        let mut renamed = false;
+        for (key, prop_def) in properties {
+            let nested_name = format!("{struct_name}{}", pascal_case(key));
+            let mut field_type = self.format_type(&prop_def.r#type, &nested_name, options);
+            if prop_def.optional && !field_type.ends_with('?') {
+                field_type.push('?');
+            }
+            let mut name = identifier(key);
+            while !names.insert(name.clone()) {
+                name.push('_');
+            }
+            let renamed_key = (name != *key).then(|| string_literal(key));
+            let name = escape_name(&name);
+            let note = if prop_def.r#type == InferredType::Primitive(PrimitiveType::Null) {
+                " // Always null in the observed data."
+            } else {
+                ""
+            };
+            fields.push_str(&format!("    var {name}: {field_type}{note}\n"));
+            match renamed_key {
+                Some(key) => {
+                    renamed = true;
+                    coding_keys.push_str(&format!("        case {name} = {key}\n"));
+                }
+                None => coding_keys.push_str(&format!("        case {name}\n")),
+            }
+        }
+        if renamed {
+            fields.push_str(&format!(
+                "\n    enum CodingKeys: String, CodingKey {{\n{coding_keys}    }}\n"
+            ));
+        }
+        self.definitions.push(format!(
+            "{}struct {struct_name}: Codable {{\n{fields}}}\n",
+            comment.unwrap_or_default()
+        ));
+    }
+
+    /// Formats a Swift type, adding the types it needs under names derived from `type_name`.
+    fn format_type(
+        &mut self,
+        inferred_type: &InferredType,
+        type_name: &str,
+        options: &GenerationOptions,
+    ) -> String {
+        match inferred_type {
+            InferredType::Primitive(prim_type) => primitive_type(*prim_type).to_string(),
+            InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+                self.json_value = true;
+                "JSONValue".to_string()
+            }
+            InferredType::Never => "Never".to_string(),
+            InferredType::PrimitiveUnion(types) => self.primitive_union(types, type_name),
+            // Tuples are not `Codable`, so they are decoded as arrays of their element types.
+            InferredType::PrimitiveTuple(types) => {
+                format!(
+                    "[{}]",
+                    self.primitive_union(types, &format!("{type_name}Element"))
+                )
+            }
+            InferredType::TupleUnion(tuples) => {
+                let types = tuples.iter().flatten().copied().collect::<Vec<_>>();
+                format!(
+                    "[{}]",
+                    self.primitive_union(&types, &format!("{type_name}Element"))
+                )
+            }
+            InferredType::Array(item_type) => format!(
+                "[{}]",
+                self.format_type(item_type, &format!("{type_name}Item"), options)
+            ),
+            InferredType::Object(properties) => {
+                self.push_struct(type_name, properties, options, None);
+                type_name.to_string()
+            }
+            InferredType::ComplexUnion(members) => {
+                let members = members
+                    .iter()
+                    .map(|member| {
+                        let case = match member {
+                            InferredType::Object(_) => "object",
+                            InferredType::Array(_) => "array",
+                            _ => "other",
+                        };
+                        let member_type = self.format_type(
+                            member,
+                            &format!("{type_name}{}", pascal_case(case)),
+                            options,
+                        );
+                        (case, member_type)
+                    })
+                    .collect::<Vec<_>>();
+                self.push_union_enum(type_name, &members)
+            }
+            InferredType::NullableObj(inner) | InferredType::Optional(inner) => {
+                let inner_type = self.format_type(inner, type_name, options);
+                if inner_type.ends_with('?') {
+                    inner_type
+                } else {
+                    format!("{inner_type}?")
+                }
+            }
+        }
+    }
+
+    /// Formats a union of primitive types, which is optional if it includes `null`.
+    fn primitive_union(&mut self, types: &[PrimitiveType], type_name: &str) -> String {
+        let mut members = Vec::new();
+        for prim_type in types {
+            let member = match prim_type {
+                PrimitiveType::String => ("string", "String"),
+                PrimitiveType::Number => ("double", "Double"),
+                PrimitiveType::Boolean => ("bool", "Bool"),
+                PrimitiveType::Null => continue,
+            };
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+        let optional = types.contains(&PrimitiveType::Null);
+        let union = match members.as_slice() {
+            [] if optional => return "Never?".to_string(),
+            [] => {
+                self.json_value = true;
+                return "JSONValue".to_string();
+            }
+            [(_, member_type)] => member_type.to_string(),
+            members => {
+                let members = members
+                    .iter()
+                    .map(|(case, member_type)| (*case, member_type.to_string()))
+                    .collect::<Vec<_>>();
+                self.push_union_enum(type_name, &members)
+            }
+        };
+        if optional { format!("{union}?") } else { union }
+    }
+
+    /// Adds an enum with a case holding each member type, decoded by trying each in turn, and
+    /// returns its name.
+    fn push_union_enum(&mut self, enum_name: &str, members: &[(&str, String)]) -> String {
+        let cases = members
+            .iter()
+            .map(|(case, member_type)| format!("    case {case}({member_type})\n"))
+            .collect::<String>();
+        let decoders = members
+            .iter()
+            .map(|(case, member_type)| {
+                format!(
+                    "if let value = try? container.decode({member_type}.self) {{\n            self = .{case}(value)\n        }} else "
+                )
+            })
+            .collect::<String>();
+        let encoders = members
+            .iter()
+            .map(|(case, _)| {
+                format!("        case .{case}(let value): try container.encode(value)\n")
+            })
+            .collect::<String>();
+        self.definitions.push(format!(
+            r#"enum {enum_name}: Codable {{
+{cases}
+    init(from decoder: Decoder) throws {{
+        let container = try decoder.singleValueContainer()
+        {decoders}{{
+            throw DecodingError.typeMismatch({enum_name}.self, DecodingError.Context(codingPath: decoder.codingPath, debugDescription: "No member of {enum_name} matched"))
+        }}
+    }}
+
+    func encode(to encoder: Encoder) throws {{
+        var container = encoder.singleValueContainer()
+        switch self {{
+{encoders}        }}
+    }}
+}}
+"#
+        ));
+        enum_name.to_string()
+    }
+
+    /// Adds the root enum, which switches over the `type` key to decode the `content` key.
+    fn push_root_enum(
+        &mut self,
+        root_name: &str,
+        tag_type: TagType,
+        cases: &[(String, String, String)],
+    ) {
+        let case_declarations = cases
+            .iter()
+            .map(|(case, _, content_name)| format!("    case {case}({content_name})\n"))
+            .collect::<String>();
+        let decoders = cases
+            .iter()
+            .map(|(case, tag, content_name)| {
+                format!(
+                    "        case {tag}:\n            self = .{case}(try container.decode({content_name}.self, forKey: .content))\n"
+                )
+            })
+            .collect::<String>();
+        let encoders = cases
+            .iter()
+            .map(|(case, tag, _)| {
+                format!(
+                    "        case .{case}(let content):\n            try container.encode({tag}, forKey: .type)\n            try container.encode(content, forKey: .content)\n"
+                )
+            })
+            .collect::<String>();
+        let tag_type = match tag_type {
+            TagType::String | TagType::Any => "String",
+            TagType::Number => "Double",
+            TagType::Bool => "Bool",
+        };
+        self.definitions.push(format!(
+            r#"enum {root_name}: Codable {{
+{case_declarations}
+    private enum CodingKeys: String, CodingKey {{
+        case type
+        case content
+    }}
+
+    init(from decoder: Decoder) throws {{
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        switch try container.decode({tag_type}.self, forKey: .type) {{
+{decoders}        case let type:
+            throw DecodingError.dataCorruptedError(forKey: .type, in: container, debugDescription: "Unknown event type \(type)")
+        }}
+    }}
+
+    func encode(to encoder: Encoder) throws {{
+        var container = encoder.container(keyedBy: CodingKeys.self)
+        switch self {{
+{encoders}        }}
+    }}
+}}
+"#
+        ));
+    }
+}
+
+/// Replaces the characters of `name` that cannot appear in an identifier.
+fn identifier(name: &str) -> String {
+    let mut identifier = name
+        .chars()
+        .map(|c| if is_xid_continue(c) { c } else { '_' })
+        .collect::<String>();
+    if !identifier.starts_with(|c| is_xid_start(c) || c == '_') {
+        identifier.insert(0, '_');
+    }
+    identifier
+}
+
+fn escape_name(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("`{name}`")
+    } else {
+        name.to_string()
+    }
+}
+
+fn primitive_type(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "String",
+        // JSON does not distinguish integers.
+        PrimitiveType::Number => "Double",
+        PrimitiveType::Boolean => "Bool",
+        // Only `nil` is ever decoded.
+        PrimitiveType::Null => "Never?",
+    }
+}
+
+fn tag_literal(tag_type: TagType, tag_key: &str) -> Result<String> {
+    Ok(match tag_type {
+        TagType::String | TagType::Any => string_literal(tag_key),
+        TagType::Number => format!(
+            "{:?}",
+            tag_key
+                .parse::<f64>()
+                .with_context(|| format!("Event type {tag_key:?} is not a number tag"))?
+        ),
+        TagType::Bool => tag_key.to_string(),
+    })
+}
+
+fn string_literal(s: &str) -> String {
+    let mut literal = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
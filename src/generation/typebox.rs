@@ -0,0 +1,153 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key},
+    types::{InferredType, InputData, PrimitiveType},
+};
+use anyhow::Result;
+
+pub fn generate_typebox(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_typebox_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a TypeBox schema, and the static type derived from it, for each event type's content
+/// and for the root union.
+pub fn generate_typebox_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut output = "import { type Static, Type } from \"@sinclair/typebox\";\n\n".to_string();
+    let mut members = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let schema_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            output.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+        output.push_str(&format!(
+            "export const {schema_name} = {};\nexport type {schema_name} = Static<typeof {schema_name}>;\n\n",
+            format_schema(inferred_type, options, 0)
+        ));
+        members.push(format!(
+            "Type.Object({{ type: Type.Literal({}), content: {schema_name} }})",
+            options.tag_type.format_literal(event_type_key)
+        ));
+    }
+
+    let root_schema = match members.as_slice() {
+        [] => "Type.Never()".to_string(),
+        [member] => member.clone(),
+        members => format!("Type.Union([\n  {},\n])", members.join(",\n  ")),
+    };
+    output.push_str(&format!(
+        "export const {root_name} = {root_schema};\nexport type {root_name} = Static<typeof {root_name}>;\n"
+    ));
+    Ok(output)
+}
+
+fn format_schema(
+    inferred_type: &InferredType,
+    options: &GenerationOptions,
+    depth: usize,
+) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_schema(*prim_type).to_string(),
+        InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+            "Type.Any()".to_string()
+        }
+        InferredType::Never => "Type.Never()".to_string(),
+        InferredType::PrimitiveUnion(types) => format!(
+            "Type.Union([{}])",
+            types
+                .iter()
+                .map(|t| primitive_schema(*t))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::PrimitiveTuple(types) => tuple_schema(types),
+        InferredType::TupleUnion(tuples) => format!(
+            "Type.Union([{}])",
+            tuples
+                .iter()
+                .map(|types| tuple_schema(types))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::Array(item_type) => {
+            format!("Type.Array({})", format_schema(item_type, options, depth))
+        }
+        InferredType::Object(properties) => {
+            if properties.is_empty() {
+                return "Type.Object({})".to_string();
+            }
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            let indent = "  ".repeat(depth + 1);
+            let entries = properties
+                .into_iter()
+                .map(|(key, prop_def)| {
+                    let value_schema = format_schema(&prop_def.r#type, options, depth + 1);
+                    let value_schema = if prop_def.optional {
+                        format!("Type.Optional({value_schema})")
+                    } else {
+                        value_schema
+                    };
+                    format!("{indent}{}: {value_schema}", format_property_key(key))
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "Type.Object({{\n{},\n{}}})",
+                entries.join(",\n"),
+                "  ".repeat(depth)
+            )
+        }
+        InferredType::ComplexUnion(members) => format!(
+            "Type.Union([{}])",
+            members
+                .iter()
+                .map(|member| format_schema(member, options, depth))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::NullableObj(inner) => format!(
+            "Type.Union([{}, Type.Null()])",
+            format_schema(inner, options, depth)
+        ),
+        // `Type.Optional` only marks the property as optional, so a present `undefined` value is
+        // expressed as a union.
+        InferredType::Optional(inner) => format!(
+            "Type.Union([{}, Type.Undefined()])",
+            format_schema(inner, options, depth)
+        ),
+    }
+}
+
+fn tuple_schema(types: &[PrimitiveType]) -> String {
+    format!(
+        "Type.Tuple([{}])",
+        types
+            .iter()
+            .map(|t| primitive_schema(*t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn primitive_schema(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "Type.String()",
+        PrimitiveType::Number => "Type.Number()",
+        PrimitiveType::Boolean => "Type.Boolean()",
+        PrimitiveType::Null => "Type.Null()",
+    }
+}
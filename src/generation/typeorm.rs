@@ -0,0 +1,164 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key, format_type_to_ts_string_with_options},
+    types::{InferredType, InputData, PrimitiveType},
+};
+use anyhow::Result;
+
+pub fn generate_typeorm(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_typeorm_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a TypeORM entity for each event type's content, with a generated primary key and a
+/// column per property of object content types or a single `content` column otherwise, the root
+/// union of the entities, and the list of entities to register with a data source, e.g.
+/// `eventsEntities`.
+pub fn generate_typeorm_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut output =
+        "import { Column, Entity, PrimaryGeneratedColumn } from \"typeorm\";\n\n".to_string();
+    let mut members = Vec::with_capacity(types.len());
+    let mut entities = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let entity_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            output.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+
+        let mut columns = Vec::new();
+        if let InferredType::Object(properties) = inferred_type {
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            for (key, prop_def) in properties {
+                columns.push((
+                    format_property_key(key).into_owned(),
+                    &prop_def.r#type,
+                    prop_def.optional,
+                ));
+            }
+        } else {
+            columns.push(("content".to_string(), inferred_type, false));
+        }
+
+        // The primary key is named `id` unless a column already is.
+        let mut primary_key = "id".to_string();
+        while columns.iter().any(|(name, ..)| *name == primary_key) {
+            primary_key.insert(0, '_');
+        }
+        output.push_str(&format!(
+            "@Entity()\nexport class {entity_name} {{\n  @PrimaryGeneratedColumn()\n  {primary_key}!: number;\n"
+        ));
+        for (name, inferred_type, optional) in columns {
+            let marker = if optional { "?" } else { "!" };
+            output.push_str(&format!(
+                "\n  {}\n  {name}{marker}: {};\n",
+                column_decorator(inferred_type, optional),
+                format_type_to_ts_string_with_options(inferred_type.clone(), &options.format)
+            ));
+        }
+        output.push_str("}\n\n");
+
+        members.push(format!(
+            "{{ type: {}, content: {entity_name} }}",
+            options.tag_type.format_literal(event_type_key)
+        ));
+        entities.push(entity_name);
+    }
+
+    let root_type = if members.is_empty() {
+        "never".to_string()
+    } else {
+        members.join(" | ")
+    };
+    output.push_str(&format!(
+        "export type {root_name} = {root_type};\n\nexport const {}Entities = [{}];\n",
+        lower_first(root_name),
+        entities.join(", ")
+    ));
+    Ok(output)
+}
+
+/// Formats the `@Column` decorator of a property. Numbers are declared as `float`, since the type
+/// reflected from `number` is an integer column, and nullable primitives need an explicit type,
+/// since their union type cannot be reflected. Arrays of strings are stored as `simple-array` and
+/// other structured values as `json`.
+fn column_decorator(inferred_type: &InferredType, optional: bool) -> String {
+    let column_type = column_type(inferred_type);
+    let nullable = optional || accepts_null(inferred_type);
+    match (column_type, nullable) {
+        ("varchar" | "boolean", false) => "@Column()".to_string(),
+        (column_type, false) => format!("@Column(\"{column_type}\")"),
+        (column_type, true) => format!("@Column(\"{column_type}\", {{ nullable: true }})"),
+    }
+}
+
+/// The database column type of `inferred_type`.
+fn column_type(inferred_type: &InferredType) -> &'static str {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_union(&[*prim_type]),
+        InferredType::PrimitiveUnion(types) => primitive_union(types),
+        InferredType::Array(item_type) => match &**item_type {
+            // `simple-array` joins the items with commas, so values read back are strings.
+            InferredType::Primitive(PrimitiveType::String) => "simple-array",
+            _ => "json",
+        },
+        InferredType::NullableObj(inner)
+        | InferredType::Optional(inner)
+        | InferredType::Branded { inner, .. } => column_type(inner),
+        _ => "json",
+    }
+}
+
+/// The column type of a union of primitive types, possibly repeated: the type of its single
+/// non-null member, or `json`.
+fn primitive_union(types: &[PrimitiveType]) -> &'static str {
+    let mut members = types
+        .iter()
+        .filter(|t| **t != PrimitiveType::Null)
+        .copied()
+        .collect::<Vec<_>>();
+    members.sort_unstable();
+    members.dedup();
+    match members.as_slice() {
+        [PrimitiveType::String] => "varchar",
+        // JSON does not distinguish integers.
+        [PrimitiveType::Number] => "float",
+        [PrimitiveType::Boolean] => "boolean",
+        _ => "json",
+    }
+}
+
+/// Whether values of `inferred_type` can be `null`.
+fn accepts_null(inferred_type: &InferredType) -> bool {
+    match inferred_type {
+        InferredType::Primitive(PrimitiveType::Null)
+        | InferredType::Any
+        | InferredType::NullableObj(_)
+        | InferredType::Optional(_) => true,
+        InferredType::PrimitiveUnion(types) => types.contains(&PrimitiveType::Null),
+        InferredType::Branded { inner, .. } => accepts_null(inner),
+        _ => false,
+    }
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,149 @@
+use super::{GenerationOptions, InferredEvents, TypeNames, infer_event_types};
+use crate::{
+    formatting::{PropertyOrder, format_property_key},
+    types::{InferredType, InputData, PrimitiveType},
+};
+use anyhow::Result;
+
+pub fn generate_valibot(json_array: Vec<InputData>, root_name: &str) -> Result<String> {
+    generate_valibot_with_options(json_array, root_name, &GenerationOptions::default())
+}
+
+/// Generates a valibot schema, and the output type inferred from it, for each event type's content
+/// and for the root union.
+pub fn generate_valibot_with_options(
+    json_array: Vec<InputData>,
+    root_name: &str,
+    options: &GenerationOptions,
+) -> Result<String> {
+    let InferredEvents {
+        types,
+        invalid_json,
+        ..
+    } = infer_event_types(json_array, options)?;
+    let type_names = TypeNames::resolve(types.keys(), |_| "Content", options)?;
+
+    let mut output = "import * as v from \"valibot\";\n\n".to_string();
+    let mut members = Vec::with_capacity(types.len());
+    for (event_type_key, inferred_type) in &types {
+        let schema_name = type_names.get(event_type_key, "Content", options);
+        if let Some(invalid_json) = invalid_json.get(event_type_key) {
+            output.push_str(&format!(
+                "// The 'content' field contained invalid JSON: \"{invalid_json}\"\n"
+            ));
+        }
+        output.push_str(&format!(
+            "export const {schema_name} = {};\nexport type {schema_name} = v.InferOutput<typeof {schema_name}>;\n\n",
+            format_schema(inferred_type, options, 0)
+        ));
+        members.push(format!(
+            "v.object({{ type: v.literal({}), content: {schema_name} }})",
+            options.tag_type.format_literal(event_type_key)
+        ));
+    }
+
+    let root_schema = match members.as_slice() {
+        [] => "v.never()".to_string(),
+        [member] => member.clone(),
+        members => format!("v.union([\n  {},\n])", members.join(",\n  ")),
+    };
+    output.push_str(&format!(
+        "export const {root_name} = {root_schema};\nexport type {root_name} = v.InferOutput<typeof {root_name}>;\n"
+    ));
+    Ok(output)
+}
+
+fn format_schema(
+    inferred_type: &InferredType,
+    options: &GenerationOptions,
+    depth: usize,
+) -> String {
+    match inferred_type {
+        InferredType::Primitive(prim_type) => primitive_schema(*prim_type).to_string(),
+        InferredType::Any | InferredType::Branded { .. } | InferredType::Reference(_) => {
+            "v.any()".to_string()
+        }
+        InferredType::Never => "v.never()".to_string(),
+        InferredType::PrimitiveUnion(types) => format!(
+            "v.union([{}])",
+            types
+                .iter()
+                .map(|t| primitive_schema(*t))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::PrimitiveTuple(types) => tuple_schema(types),
+        InferredType::TupleUnion(tuples) => format!(
+            "v.union([{}])",
+            tuples
+                .iter()
+                .map(|types| tuple_schema(types))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::Array(item_type) => {
+            format!("v.array({})", format_schema(item_type, options, depth))
+        }
+        InferredType::Object(properties) => {
+            if properties.is_empty() {
+                return "v.object({})".to_string();
+            }
+            let mut properties = properties.iter().collect::<Vec<_>>();
+            if options.format.property_order == PropertyOrder::Alphabetical {
+                properties.sort_by_key(|(key, _)| *key);
+            }
+            let indent = "  ".repeat(depth + 1);
+            let entries = properties
+                .into_iter()
+                .map(|(key, prop_def)| {
+                    let mut value_schema = format_schema(&prop_def.r#type, options, depth + 1);
+                    // `v.optional` already accepts `undefined`, so it is not nested.
+                    if prop_def.optional && !matches!(prop_def.r#type, InferredType::Optional(_)) {
+                        value_schema = format!("v.optional({value_schema})");
+                    }
+                    format!("{indent}{}: {value_schema}", format_property_key(key))
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "v.object({{\n{},\n{}}})",
+                entries.join(",\n"),
+                "  ".repeat(depth)
+            )
+        }
+        InferredType::ComplexUnion(members) => format!(
+            "v.union([{}])",
+            members
+                .iter()
+                .map(|member| format_schema(member, options, depth))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        InferredType::NullableObj(inner) => {
+            format!("v.nullable({})", format_schema(inner, options, depth))
+        }
+        InferredType::Optional(inner) => {
+            format!("v.optional({})", format_schema(inner, options, depth))
+        }
+    }
+}
+
+fn tuple_schema(types: &[PrimitiveType]) -> String {
+    format!(
+        "v.tuple([{}])",
+        types
+            .iter()
+            .map(|t| primitive_schema(*t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn primitive_schema(prim_type: PrimitiveType) -> &'static str {
+    match prim_type {
+        PrimitiveType::String => "v.string()",
+        PrimitiveType::Number => "v.number()",
+        PrimitiveType::Boolean => "v.boolean()",
+        // `null` is a reserved word, hence the trailing underscore.
+        PrimitiveType::Null => "v.null_()",
+    }
+}
@@ -1,15 +1,39 @@
-use crate::types::{InferredType, PrimitiveType, PropertyDefinition};
+use crate::types::{InferredType, PrimitiveType, PropertyDefinition, PropertyMap};
 use serde_json::Value;
-use std::collections::HashMap;
 
 const EMPTY_TUPLE: InferredType = InferredType::PrimitiveTuple(Vec::new());
 
+/// Options controlling how observed types are inferred and merged.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct InferenceOptions {
+    /// Merge tuples of differing shapes into a union of tuples instead of relaxing them to an
+    /// array.
+    pub strict_tuples: bool,
+    /// Record up to this many distinct observed values of every object property in
+    /// [`PropertyDefinition::examples`].
+    pub max_examples: usize,
+    /// Truncate the strings in collected examples to this many characters, followed by `…`.
+    pub example_string_limit: Option<usize>,
+    /// Infer strings holding a finite number, such as `"29.99"`, as `number`.
+    pub parse_numeric_strings: bool,
+    /// Infer object properties that are either `null` or a single primitive type as optional
+    /// properties of that type, instead of `T | null`.
+    pub null_as_optional: bool,
+}
+
 pub fn infer_type_from_value(value: Value) -> InferredType {
+    infer_type_from_value_with_options(value, &InferenceOptions::default())
+}
+
+pub fn infer_type_from_value_with_options(
+    value: Value,
+    options: &InferenceOptions,
+) -> InferredType {
     match value {
         Value::Null => InferredType::Primitive(PrimitiveType::Null),
         Value::Bool(_) => InferredType::Primitive(PrimitiveType::Boolean),
         Value::Number(_) => InferredType::Primitive(PrimitiveType::Number),
-        Value::String(_) => InferredType::Primitive(PrimitiveType::String),
+        Value::String(s) => InferredType::Primitive(string_type(&s, options)),
         Value::Array(arr) => {
             // First, attempt to infer a tuple type (only for primitive types).
             let tuple = 'block: {
@@ -19,11 +43,11 @@ pub fn infer_type_from_value(value: Value) -> InferredType {
                         Value::Null => tuple.push(PrimitiveType::Null),
                         Value::Bool(_) => tuple.push(PrimitiveType::Boolean),
                         Value::Number(_) => tuple.push(PrimitiveType::Number),
-                        Value::String(_) => tuple.push(PrimitiveType::String),
+                        Value::String(s) => tuple.push(string_type(s, options)),
                         _ => break 'block None,
                     }
                 }
-                tuple.sort();
+                // Tuples are positional, so the element order must be preserved.
                 Some(InferredType::PrimitiveTuple(tuple))
             };
 
@@ -31,8 +55,8 @@ pub fn infer_type_from_value(value: Value) -> InferredType {
                 // Otherwise, fall back to array type inference.
                 match arr
                     .into_iter()
-                    .map(infer_type_from_value)
-                    .reduce(merge_types)
+                    .map(|val| infer_type_from_value_with_options(val, options))
+                    .reduce(|type1, type2| merge_types_with_options(type1, type2, options))
                 {
                     Some(item_type) => InferredType::Array(Box::new(item_type)),
                     None => EMPTY_TUPLE,
@@ -40,14 +64,24 @@ pub fn infer_type_from_value(value: Value) -> InferredType {
             })
         }
         Value::Object(obj) => {
-            let properties: HashMap<String, PropertyDefinition> = obj
+            let properties: PropertyMap = obj
                 .into_iter()
                 .map(|(key, val)| {
+                    let examples = if options.max_examples > 0 {
+                        let mut example = val.clone();
+                        if let Some(limit) = options.example_string_limit {
+                            truncate_strings(&mut example, limit);
+                        }
+                        vec![example]
+                    } else {
+                        Vec::new()
+                    };
                     (
                         key,
                         PropertyDefinition {
-                            r#type: infer_type_from_value(val),
+                            r#type: infer_type_from_value_with_options(val, options),
                             optional: false,
+                            examples,
                         },
                     )
                 })
@@ -57,7 +91,75 @@ pub fn infer_type_from_value(value: Value) -> InferredType {
     }
 }
 
+/// Truncates every string within `value` that is longer than `limit` characters.
+fn truncate_strings(value: &mut Value, limit: usize) {
+    match value {
+        Value::String(s) => {
+            if let Some((index, _)) = s.char_indices().nth(limit) {
+                s.truncate(index);
+                s.push('…');
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                truncate_strings(item, limit);
+            }
+        }
+        Value::Object(obj) => {
+            for val in obj.values_mut() {
+                truncate_strings(val, limit);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn string_type(s: &str, options: &InferenceOptions) -> PrimitiveType {
+    if options.parse_numeric_strings && s.parse::<f64>().is_ok_and(f64::is_finite) {
+        PrimitiveType::Number
+    } else {
+        PrimitiveType::String
+    }
+}
+
+/// The other member of a `T | null` primitive union, which `null_as_optional` turns into an
+/// optional `T`.
+fn null_union_member(inferred_type: &InferredType) -> Option<PrimitiveType> {
+    match inferred_type {
+        InferredType::PrimitiveUnion(types) => match types.as_slice() {
+            [prim_type, PrimitiveType::Null] | [PrimitiveType::Null, prim_type] => Some(*prim_type),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Widens a tuple to an array through the tuple and array merge, so that a union with an object
+/// has a single array member whichever order its members were merged in. The empty tuple has no
+/// item type to widen to, so it is kept.
+fn widen_tuple(inferred_type: InferredType, options: &InferenceOptions) -> InferredType {
+    match inferred_type {
+        InferredType::PrimitiveTuple(ref types) if types.is_empty() => inferred_type,
+        InferredType::PrimitiveTuple(_) | InferredType::TupleUnion(_) => merge_types_with_options(
+            InferredType::Array(Box::new(InferredType::Never)),
+            inferred_type,
+            options,
+        ),
+        inferred_type => inferred_type,
+    }
+}
+
 pub fn merge_types(type1: InferredType, type2: InferredType) -> InferredType {
+    merge_types_with_options(type1, type2, &InferenceOptions::default())
+}
+
+pub fn merge_types_with_options(
+    type1: InferredType,
+    type2: InferredType,
+    options: &InferenceOptions,
+) -> InferredType {
+    let merge_types = |type1, type2| merge_types_with_options(type1, type2, options);
+
     if type1 == type2 {
         return type1;
     }
@@ -65,6 +167,13 @@ pub fn merge_types(type1: InferredType, type2: InferredType) -> InferredType {
     match (type1, type2) {
         (InferredType::Any, _) | (_, InferredType::Any) => InferredType::Any,
         (InferredType::Never, t) | (t, InferredType::Never) => t,
+        // `undefined` is orthogonal to every other type, including `null`, so it stays outermost.
+        (InferredType::Optional(inner1), InferredType::Optional(inner2)) => {
+            InferredType::Optional(Box::new(merge_types(*inner1, *inner2)))
+        }
+        (InferredType::Optional(inner), t) | (t, InferredType::Optional(inner)) => {
+            InferredType::Optional(Box::new(merge_types(*inner, t)))
+        }
         (InferredType::Primitive(p1), InferredType::Primitive(p2)) => {
             InferredType::PrimitiveUnion(if p1 < p2 { vec![p1, p2] } else { vec![p2, p1] })
         }
@@ -91,7 +200,41 @@ pub fn merge_types(type1: InferredType, type2: InferredType) -> InferredType {
             merged_types.sort();
             InferredType::PrimitiveUnion(merged_types)
         }
+        (InferredType::PrimitiveTuple(types1), InferredType::PrimitiveTuple(types2))
+            if options.strict_tuples =>
+        {
+            let mut tuples = vec![types1, types2];
+            tuples.sort();
+            InferredType::TupleUnion(tuples)
+        }
+        (InferredType::TupleUnion(mut tuples), InferredType::PrimitiveTuple(types))
+        | (InferredType::PrimitiveTuple(types), InferredType::TupleUnion(mut tuples)) => {
+            if !tuples.contains(&types) {
+                tuples.push(types);
+                tuples.sort();
+            }
+            InferredType::TupleUnion(tuples)
+        }
+        (InferredType::TupleUnion(mut tuples1), InferredType::TupleUnion(tuples2)) => {
+            for types in tuples2 {
+                if !tuples1.contains(&types) {
+                    tuples1.push(types);
+                }
+            }
+            tuples1.sort();
+            InferredType::TupleUnion(tuples1)
+        }
+        (InferredType::TupleUnion(tuples), InferredType::Array(item_type))
+        | (InferredType::Array(item_type), InferredType::TupleUnion(tuples)) => {
+            // An array absorbs each tuple in turn, just like a single tuple.
+            tuples
+                .into_iter()
+                .fold(InferredType::Array(item_type), |merged, types| {
+                    merge_types(merged, InferredType::PrimitiveTuple(types))
+                })
+        }
         (InferredType::PrimitiveTuple(types1), InferredType::PrimitiveTuple(types2)) => {
+            // Element-by-element comparison: `[string, number]` and `[number, string]` differ.
             if types1 == types2 {
                 InferredType::PrimitiveTuple(types1)
             } else {
@@ -150,10 +293,10 @@ pub fn merge_types(type1: InferredType, type2: InferredType) -> InferredType {
             if !all_same_type {
                 // If types differ, create a union of all unique types
                 let mut unique_types = types;
-                if let Some(p) = primitive_item_type {
-                    if !unique_types.contains(&p) {
-                        unique_types.push(p);
-                    }
+                if let Some(p) = primitive_item_type
+                    && !unique_types.contains(&p)
+                {
+                    unique_types.push(p);
                 }
                 unique_types.sort();
 
@@ -179,46 +322,118 @@ pub fn merge_types(type1: InferredType, type2: InferredType) -> InferredType {
         (InferredType::Array(item_type1), InferredType::Array(item_type2)) => {
             InferredType::Array(Box::new(merge_types(*item_type1, *item_type2)))
         }
-        (InferredType::Object(obj1), InferredType::Object(mut obj2)) => {
-            let mut merged_props = HashMap::new();
+        (InferredType::Object(obj1), InferredType::Object(obj2)) => {
+            // Keys from `obj1` come first, followed by keys only present in `obj2`.
+            let mut merged_props = PropertyMap::with_capacity(obj1.len().max(obj2.len()));
 
             for (key, prop1) in obj1 {
-                let prop_def = match obj2.remove(&key) {
-                    Some(p2) => PropertyDefinition {
-                        r#type: merge_types(prop1.r#type, p2.r#type),
-                        optional: prop1.optional || p2.optional,
-                    },
-                    None => PropertyDefinition {
-                        optional: true,
-                        ..prop1
-                    },
-                };
-                merged_props.insert(key, prop_def);
+                let optional = prop1.optional || !obj2.contains_key(&key);
+                merged_props.insert(key, PropertyDefinition { optional, ..prop1 });
             }
             for (key, prop2) in obj2 {
-                merged_props.insert(
-                    key,
-                    PropertyDefinition {
-                        r#type: prop2.r#type,
-                        optional: true,
-                    },
-                );
+                match merged_props.get_mut(&key) {
+                    Some(prop1) => {
+                        let type1 = std::mem::replace(&mut prop1.r#type, InferredType::Never);
+                        prop1.r#type = merge_types(type1, prop2.r#type);
+                        prop1.optional |= prop2.optional;
+                        let mut examples = prop2.examples;
+                        if options.null_as_optional
+                            && let Some(prim_type) = null_union_member(&prop1.r#type)
+                        {
+                            prop1.r#type = InferredType::Primitive(prim_type);
+                            prop1.optional = true;
+                            // `null` examples no longer match the type, so they do not take up
+                            // room for other values.
+                            prop1.examples.retain(|example| !example.is_null());
+                            examples.retain(|example| !example.is_null());
+                        }
+                        for example in examples {
+                            if prop1.examples.len() >= options.max_examples {
+                                break;
+                            }
+                            if !prop1.examples.contains(&example) {
+                                prop1.examples.push(example);
+                            }
+                        }
+                    }
+                    None => {
+                        merged_props.insert(
+                            key,
+                            PropertyDefinition {
+                                optional: true,
+                                ..prop2
+                            },
+                        );
+                    }
+                }
             }
             InferredType::Object(merged_props)
         }
+        (
+            array @ (InferredType::Array(_)
+            | InferredType::PrimitiveTuple(_)
+            | InferredType::TupleUnion(_)),
+            InferredType::Object(properties),
+        )
+        | (
+            InferredType::Object(properties),
+            array @ (InferredType::Array(_)
+            | InferredType::PrimitiveTuple(_)
+            | InferredType::TupleUnion(_)),
+        ) => InferredType::ComplexUnion(vec![
+            widen_tuple(array, options),
+            InferredType::Object(properties),
+        ]),
+        (InferredType::ComplexUnion(members1), InferredType::ComplexUnion(members2)) => members2
+            .into_iter()
+            .fold(InferredType::ComplexUnion(members1), |merged, member| {
+                merge_types(merged, member)
+            }),
+        (
+            InferredType::ComplexUnion(mut members),
+            t @ (InferredType::Array(_)
+            | InferredType::PrimitiveTuple(_)
+            | InferredType::TupleUnion(_)
+            | InferredType::Object(_)),
+        )
+        | (
+            t @ (InferredType::Array(_)
+            | InferredType::PrimitiveTuple(_)
+            | InferredType::TupleUnion(_)
+            | InferredType::Object(_)),
+            InferredType::ComplexUnion(mut members),
+        ) => {
+            // Each kind appears at most once, so `t` is merged into the member of the same kind.
+            let is_object = matches!(t, InferredType::Object(_));
+            match members
+                .iter_mut()
+                .find(|member| matches!(member, InferredType::Object(_)) == is_object)
+            {
+                Some(member) => {
+                    let merged = std::mem::replace(member, InferredType::Never);
+                    *member = merge_types(merged, t);
+                }
+                None => members.push(widen_tuple(t, options)),
+            }
+            InferredType::ComplexUnion(members)
+        }
         (t, InferredType::Primitive(PrimitiveType::Null))
         | (InferredType::Primitive(PrimitiveType::Null), t) => match t {
-            InferredType::Object(_) | InferredType::Array(_) => {
-                InferredType::NullableObj(Box::new(t))
+            InferredType::NullableObj(_) => t,
+            InferredType::PrimitiveUnion(_) | InferredType::Primitive(_) => {
+                unreachable!("primitives are merged with null above")
             }
-            _ => unreachable!(),
+            _ => InferredType::NullableObj(Box::new(t)),
         },
         (InferredType::NullableObj(obj), InferredType::NullableObj(obj2)) => {
             InferredType::NullableObj(Box::new(merge_types(*obj, *obj2)))
         }
-        (InferredType::NullableObj(obj), t) | (t, InferredType::NullableObj(obj)) => {
-            InferredType::NullableObj(Box::new(merge_types(*obj, t)))
-        }
+        // Merge the non-null parts first, then add `null` back so that a primitive result becomes
+        // a primitive union rather than a nullable object.
+        (InferredType::NullableObj(obj), t) | (t, InferredType::NullableObj(obj)) => merge_types(
+            merge_types(*obj, t),
+            InferredType::Primitive(PrimitiveType::Null),
+        ),
         _ => InferredType::Any,
     }
 }
@@ -1,6 +1,7 @@
 pub mod formatting;
 pub mod generation;
 pub mod inference;
+pub mod transform;
 pub mod types;
 
 #[cfg(test)]
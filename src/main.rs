@@ -1,78 +1,1534 @@
-use anyhow::{Context as _, Result};
-use clap::Parser;
-use infer_json_stream::{generation::generate_typescript_definitions, types::InputData};
-use rayon::iter::{IntoParallelIterator as _, ParallelBridge, ParallelIterator};
+use anyhow::{Context as _, Result, ensure};
+use clap::{Parser, ValueEnum};
+use infer_json_stream::{
+    formatting::{FormatOptions, PropertyOrder},
+    generation::{
+        AnyTypeError, ContentMergeStrategy, EventNameCase, FetchHelpers, FormatInfo,
+        GeneratedFiles, GenerationOptions, HttpMethod, OutputFormat, PickSpec,
+        RequestResponseSuffixes, TsdocTag, TypeNameCollisionStrategy,
+        cerberus::generate_cerberus_with_options, clojure::generate_clojure_spec_with_options,
+        csharp::generate_csharp_with_options, django::generate_django_with_options,
+        effect_schema::generate_effect_schema_with_options, elm::generate_elm_with_options,
+        generate_typescript_files, haskell::generate_haskell_with_options,
+        io_ts::generate_io_ts_codecs_with_options, java::generate_java_with_options,
+        jtd::generate_jtd_with_options, kotlin::generate_kotlin_with_options,
+        mongoose::generate_mongoose_with_options, openrpc::generate_openrpc_with_options,
+        pydantic::generate_pydantic_with_options, runtypes::generate_runtypes_with_options,
+        split::generate_split_typescript_files, superstruct::generate_superstruct_with_options,
+        swift::generate_swift_with_options, typebox::generate_typebox_with_options,
+        typeorm::generate_typeorm_with_options, valibot::generate_valibot_with_options,
+    },
+    inference::InferenceOptions,
+    types::{InputData, TagType},
+};
+use rayon::iter::{
+    Either, IndexedParallelIterator as _, IntoParallelIterator as _, ParallelBridge,
+    ParallelIterator,
+};
+use regex::Regex;
+use serde::Serialize;
 use serde_json::Value;
-use std::fs;
+use serde_json_path::JsonPath;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{IsTerminal as _, Write as _},
+    num::NonZeroUsize,
+    path::Path,
+    process::{Command, Stdio},
+};
+use stringcase::{camel_case, pascal_case, snake_case};
+use tracing::info_span;
+use tracing_subscriber::fmt::format::FmtSpan;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[arg(short, long, default_value = "input.json")]
     input: String,
-    #[arg(short, long, default_value = "output.ts")]
-    output: String,
+    /// Output file path. Defaults to `output.<extension>` for the output format, or `output.d.ts`
+    /// with `--emit-module-declaration`.
+    #[arg(short, long)]
+    output: Option<String>,
     #[arg(short, long, default_value = "Events")]
     root_name: String,
     #[arg(long, default_value = "type")]
     tag: String,
     #[arg(long, default_value = "content")]
     content: String,
+    /// RFC 6901 JSON Pointer to the object holding the tag and content fields in each record,
+    /// e.g. `/envelope`.
+    #[arg(long)]
+    json_pointer: Option<String>,
+    /// RFC 6901 JSON Pointer to the tag, used instead of `--tag`, e.g. `/meta/kind`.
+    #[arg(long, conflicts_with = "tag")]
+    tag_pointer: Option<String>,
+    /// RFC 6901 JSON Pointer to the content, used instead of `--content`, e.g.
+    /// `/events/0/payload`.
+    #[arg(long, conflicts_with = "content")]
+    content_pointer: Option<String>,
+    /// RFC 9535 JSONPath expression locating the tag, used instead of `--tag`, e.g.
+    /// `$.meta.kind`. The first match is used.
+    #[arg(
+        long,
+        conflicts_with_all = ["tag", "json_pointer", "tag_pointer", "content_pointer"]
+    )]
+    tag_json_path: Option<String>,
+    /// RFC 9535 JSONPath expression locating the content, used instead of `--content`, e.g.
+    /// `$.payload.events[0]`. The first match is used.
+    #[arg(
+        long,
+        conflicts_with_all = ["content", "json_pointer", "tag_pointer", "content_pointer"]
+    )]
+    content_json_path: Option<String>,
+    /// Field holding an array of payloads, each of which is processed as a separate record of the
+    /// same event type. Used instead of `--content`.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "content",
+            "json_pointer",
+            "tag_pointer",
+            "content_pointer",
+            "tag_json_path",
+            "content_json_path",
+        ]
+    )]
+    content_list_field: Option<String>,
+    /// JSON type of the tag field values.
+    #[arg(long, value_enum, default_value_t = TagTypeArg::String)]
+    tag_type: TagTypeArg,
+    /// Normalize tag values before grouping records by them, so that e.g. `UserCreated` and
+    /// `USER_CREATED` are the same event type.
+    #[arg(long, value_enum, value_name = "TRANSFORM")]
+    tag_value_transform: Option<TagValueTransform>,
     #[arg(long)]
     json_array: bool,
+    /// Parse every record before reporting errors, instead of stopping at the first one.
+    #[arg(long)]
+    aggregate_errors: bool,
+    /// With `--aggregate-errors`, print every error and exit with code 1 (the default).
+    #[arg(long, requires = "aggregate_errors", conflicts_with = "warn_on_error")]
+    fail_on_error: bool,
+    /// With `--aggregate-errors`, print every error as a warning and continue with the records
+    /// that could be parsed.
+    #[arg(long, requires = "aggregate_errors")]
+    warn_on_error: bool,
+    /// Input file format. Detected from the file extension when omitted.
+    #[arg(long, value_enum)]
+    input_format: Option<InputFormat>,
+    /// Keep every CSV value as a string instead of inferring numbers and booleans.
+    #[cfg(feature = "csv")]
+    #[arg(long)]
+    strict_csv_types: bool,
+    /// Text encoding of JSON or CSV input, transcoded to UTF-8 before parsing.
+    #[arg(long, value_enum, default_value_t = InputEncoding::Utf8)]
+    input_encoding: InputEncoding,
+    /// Prefix for generated content type names (e.g. `I` produces `ILoginContent`).
+    #[arg(long, default_value = "")]
+    output_type_prefix: String,
+    /// Suffix for generated content type names (e.g. `Dto` produces `LoginContentDto`).
+    #[arg(long, default_value = "")]
+    output_type_suffix: String,
+    /// Emit type narrowing helper functions for the root union type.
+    #[arg(long)]
+    emit_union_helpers: bool,
+    /// Emit a `matchEvent` function calling the handler for the event's type, requiring a handler
+    /// for every event type.
+    #[arg(long)]
+    emit_discriminated_union_helpers: bool,
+    /// Emit a `fetch` wrapper for each event type, e.g. `sendLogin`, and a `dispatchEvent`
+    /// function for the root union.
+    #[arg(long, requires = "api_endpoint")]
+    emit_fetch_types: bool,
+    /// The URL the `--emit-fetch-types` helpers send events to.
+    #[arg(long, value_name = "URL", requires = "emit_fetch_types")]
+    api_endpoint: Option<String>,
+    /// The HTTP method of the `--emit-fetch-types` helpers.
+    #[arg(long, value_enum, ignore_case = true, default_value = "post")]
+    method: HttpMethod,
+    /// Emit Fastify route options for each event type, e.g. `loginRouteOptions`, validating the
+    /// request body against the content's JSON Schema.
+    #[arg(long, conflicts_with = "recursive_types")]
+    emit_fastify_schema: bool,
+    /// Emit `Id`/`_id` suffixed fields as branded `number`/`string` types.
+    #[arg(long)]
+    emit_branded_ids: bool,
+    /// Group `<base><request-suffix>`/`<base><response-suffix>` event types into operation types.
+    #[arg(long)]
+    emit_request_response_pairs: bool,
+    #[arg(long, default_value = "_request")]
+    request_suffix: String,
+    #[arg(long, default_value = "_response")]
+    response_suffix: String,
+    /// Wrap the output in an ambient `declare module "<name>"` block.
+    #[arg(long)]
+    emit_module_declaration: Option<String>,
+    /// How content types are inferred across event types.
+    #[arg(long, value_enum, default_value_t = ContentMergeStrategy::PerType)]
+    content_merge_strategy: ContentMergeStrategy,
+    /// Exclude properties whose whole key matches this regex. Can be repeated.
+    #[arg(long)]
+    property_filter_regex: Vec<String>,
+    /// Exclude top-level content properties present in fewer than this fraction of an event
+    /// type's records, e.g. `0.05` for 5%.
+    #[arg(long, value_name = "THRESHOLD")]
+    prune_rare_properties: Option<f64>,
+    /// Casing applied to event type names when deriving type names.
+    #[arg(long, value_enum, default_value_t = EventNameCase::Pascal)]
+    event_name_case: EventNameCase,
+    /// Use event type names as-is for type names. Shorthand for `--event-name-case preserve`.
+    #[arg(long, conflicts_with = "event_name_case")]
+    preserve_event_names: bool,
+    /// How to handle event types whose content types would get the same name.
+    #[arg(long, value_enum, default_value_t = TypeNameCollisionStrategy::Error)]
+    field_name_collision_strategy: TypeNameCollisionStrategy,
+    /// Order of object properties in the output. `insertion` requires the `preserve-order` feature.
+    #[arg(long, value_enum, default_value_t = PropertyOrder::Alphabetical)]
+    sort_order: PropertyOrder,
+    /// Merge differently shaped tuples into a union of tuples instead of an array.
+    #[arg(long)]
+    strict_tuples: bool,
+    /// Infer strings holding a number, such as `"29.99"`, as `number`.
+    #[arg(long)]
+    parse_numbers_as_strings: bool,
+    /// Infer properties that are either `null` or a single primitive type, such as
+    /// `string | null`, as optional properties of that type.
+    #[arg(long)]
+    null_as_optional: bool,
+    /// Precede each property with a TSDoc comment derived from its key, e.g. `/** User id. */`.
+    #[arg(long)]
+    property_docs_from_key: bool,
+    /// Precede each property with a comment holding its JSON Schema equivalent.
+    #[arg(long)]
+    emit_json_schema_comments: bool,
+    /// Precede each property with a comment listing its first distinct observed values, e.g.
+    /// `// e.g.: "alice@example.com", "bob@example.com"`.
+    #[arg(long)]
+    field_comment_from_examples: bool,
+    /// Number of distinct values listed by `--field-comment-from-examples`.
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    max_examples: usize,
+    /// Add a `[key: string]` index signature, typed as the union of the property types, to each
+    /// object type so that it can be extended with further properties.
+    #[arg(long)]
+    emit_index_signature: bool,
+    /// Precede each property and content type inferred as `any` with a comment flagging it for
+    /// review.
+    #[arg(
+        long,
+        visible_alias = "emit-type-assertions-comment",
+        conflicts_with = "compress_output"
+    )]
+    emit_any_comments: bool,
+    /// The text of the `--emit-any-comments` comments.
+    #[arg(
+        long,
+        value_name = "TEXT",
+        default_value = "TODO: could not infer type, please review",
+        requires = "emit_any_comments"
+    )]
+    any_comment: String,
+    /// Also disable the `@typescript-eslint/no-explicit-any` lint for each `any` flagged by
+    /// `--emit-any-comments`.
+    #[arg(long, requires = "emit_any_comments")]
+    ts_strict: bool,
+    /// Also write a `<root_name>.fixtures.ts` module with example values for each content type.
+    #[arg(long)]
+    emit_test_fixtures: bool,
+    /// Use the `satisfies` operator (TypeScript 4.9+) instead of type annotations in test fixtures.
+    #[arg(long, requires = "emit_test_fixtures")]
+    emit_satisfies: bool,
+    /// Truncate strings in test fixtures and example comments to this many characters, followed
+    /// by `…`.
+    #[arg(long, value_name = "N", default_value_t = 50)]
+    truncate_strings: usize,
+    /// Also write a `<root_name>.schema.json` JSON Schema document, e.g. for AJV.
+    #[arg(long, conflicts_with = "split_output_by")]
+    emit_ajv_schema: bool,
+    /// The `$id` of the `--emit-ajv-schema` document. Defaults to
+    /// `https://example.com/<root_name>.schema.json` in lowercase.
+    #[arg(long, value_name = "URL", requires = "emit_ajv_schema")]
+    schema_id: Option<String>,
+    /// Also write a `<root_name>.validate.ts` module checking an example value of each content
+    /// type with `satisfies` (TypeScript 4.9+), for type-checking in development and CI. Reuses
+    /// the test fixtures when `--emit-test-fixtures` is also given.
+    #[arg(long)]
+    emit_typescript_satisfies_check: bool,
+    /// Follow every object and array literal in test fixtures with `as const`.
+    #[arg(long, requires = "emit_test_fixtures")]
+    emit_const_assertions: bool,
+    /// Add a `_typeCheck` object to the test fixtures checking that they cover the root union's
+    /// event types.
+    #[arg(long, requires = "emit_test_fixtures")]
+    emit_type_assertions: bool,
+    /// The minimum TypeScript version the output must support, e.g. `4.9`.
+    #[arg(long)]
+    min_ts_version: Option<String>,
+    /// Emit recursive types for objects nested inside an object with the same property names.
+    #[arg(long)]
+    recursive_types: bool,
+    /// Fail with exit code 3 if `any` was inferred anywhere, listing where it occurred.
+    #[arg(long)]
+    error_on_any: bool,
+    /// Emit a `Pick<…>` alias for some fields of a content type, e.g. `Login:userId,timestamp`.
+    /// Can be repeated.
+    #[arg(long)]
+    emit_pick_types: Vec<String>,
+    /// Name of the alias for the `--emit-pick-types` spec at the same position, instead of
+    /// `{TypeName}Without{RemainingFields}`. Can be repeated.
+    #[arg(long)]
+    pick_alias: Vec<String>,
+    /// Minify the generated TypeScript onto a single line, without a trailing newline. Comments
+    /// are dropped.
+    #[arg(long)]
+    compress_output: bool,
+    /// Start the output with a `/** @module <name> */` comment, placing the generated types in
+    /// that module of the TypeDoc documentation.
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["compress_output", "split_output_by"])]
+    emit_typedoc_module: Option<String>,
+    /// Add a TSDoc modifier tag, such as `@public`, before every generated type. Can be repeated.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "TAG",
+        conflicts_with = "compress_output"
+    )]
+    tsdoc_tag: Vec<TsdocTag>,
+    /// Text prepended verbatim to the output, such as `// @ts-nocheck` followed by a newline.
+    /// Can be repeated; the headers are concatenated in order.
+    #[arg(long)]
+    output_header: Vec<String>,
+    /// Text appended verbatim to the output, such as `export default Events;` followed by a
+    /// newline. Can be repeated; the footers are concatenated in order.
+    #[arg(long)]
+    output_footer: Vec<String>,
+    /// Format the written TypeScript with `prettier --parser typescript` when `prettier` is in
+    /// `PATH`. Falls back to the unformatted output with a warning otherwise.
+    #[arg(long)]
+    output_prettier: bool,
+    /// Write a module per group of event types sharing their first N `/`-separated components,
+    /// e.g. `user` for `user/created`. The output file becomes the root module combining them.
+    #[arg(
+        long,
+        visible_alias = "group-levels",
+        value_name = "PREFIX_LENGTH",
+        conflicts_with_all = [
+            "emit_module_declaration",
+            "emit_test_fixtures",
+            "emit_typescript_satisfies_check"
+        ]
+    )]
+    split_output_by: Option<NonZeroUsize>,
+    /// The separator between the components counted by `--split-output-by`, e.g. `.` for
+    /// `com.example.users.created`.
+    #[arg(
+        long,
+        value_name = "SEP",
+        default_value = "/",
+        requires = "split_output_by"
+    )]
+    group_by_prefix_separator: String,
+    /// Emit a `typia.createValidate` runtime validator for each content type.
+    #[arg(long)]
+    emit_runtime_types: bool,
+    /// Emit a `class-validator` decorated `{TypeName}Dto` class for each object content type.
+    #[arg(long)]
+    emit_class_validators: bool,
+    /// Declare object content types as classes with a constructor taking each property, e.g.
+    /// `new LoginContent(123, 456)`, instead of type aliases.
+    #[arg(long, conflicts_with = "emit_module_declaration")]
+    emit_class_interfaces: bool,
+    /// Add a `toJSON()` method to the `--emit-class-interfaces` classes.
+    #[arg(long, alias = "emit-class-toJSON", requires = "emit_class_interfaces")]
+    emit_class_to_json: bool,
+    /// Emit a `{TypeName}Update` mapped type with every property optional for each object
+    /// content type.
+    #[arg(long)]
+    emit_mapped_types: bool,
+    /// Write a JSON summary of the processed inputs to this path.
+    #[arg(long)]
+    batch_summary: Option<String>,
+    /// The output format. See `--help-formats` for the full list.
+    #[arg(long, value_enum, default_value_t = OutputFormat::TypeScript)]
+    format: OutputFormat,
+    /// Print the supported output formats and exit.
+    #[arg(long)]
+    help_formats: bool,
+    #[arg(long, value_enum, default_value_t = LogLevel::Info)]
+    log_level: LogLevel,
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<LogLevel> for tracing::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => tracing::Level::TRACE,
+            LogLevel::Debug => tracing::Level::DEBUG,
+            LogLevel::Info => tracing::Level::INFO,
+            LogLevel::Warn => tracing::Level::WARN,
+            LogLevel::Error => tracing::Level::ERROR,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TagTypeArg {
+    String,
+    Number,
+    Bool,
+    /// Accepts any JSON value, keyed by its JSON serialization.
+    Any,
+}
+
+impl From<TagTypeArg> for TagType {
+    fn from(tag_type: TagTypeArg) -> Self {
+        match tag_type {
+            TagTypeArg::String => TagType::String,
+            TagTypeArg::Number => TagType::Number,
+            TagTypeArg::Bool => TagType::Bool,
+            TagTypeArg::Any => TagType::Any,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputFormat {
+    Json,
+    #[cfg(feature = "msgpack")]
+    Msgpack,
+    /// Each row is a record tagged by the `--tag` column, with the other columns as its content.
+    #[cfg(feature = "csv")]
+    Csv,
+}
+
+impl InputFormat {
+    fn detect(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "msgpack")]
+            Some("msgpack") => InputFormat::Msgpack,
+            #[cfg(feature = "csv")]
+            Some("csv") => InputFormat::Csv,
+            _ => InputFormat::Json,
+        }
+    }
+}
+
+/// Where the tag and content of each input record are read from.
+enum RecordFields<'a> {
+    Keys {
+        tag: &'a str,
+        content: &'a str,
+    },
+    Pointers {
+        tag: String,
+        content: String,
+    },
+    JsonPaths {
+        tag: JsonPath,
+        content: JsonPath,
+    },
+    /// The `list` field holds an array of payloads.
+    List {
+        tag: &'a str,
+        list: &'a str,
+    },
+}
+
+impl<'a> RecordFields<'a> {
+    fn from_args(args: &'a Args) -> Result<Self> {
+        if let Some(list) = &args.content_list_field {
+            return Ok(RecordFields::List {
+                tag: &args.tag,
+                list,
+            });
+        }
+        if args.tag_json_path.is_some() || args.content_json_path.is_some() {
+            // A field given by key is located by the equivalent name selector.
+            let parse = |path: &Option<String>, key: &str| {
+                let path = path
+                    .clone()
+                    .unwrap_or_else(|| format!("$[{}]", Value::from(key)));
+                JsonPath::parse(&path).with_context(|| format!("Invalid JSONPath {path:?}"))
+            };
+            return Ok(RecordFields::JsonPaths {
+                tag: parse(&args.tag_json_path, &args.tag)?,
+                content: parse(&args.content_json_path, &args.content)?,
+            });
+        }
+        if args.json_pointer.is_none()
+            && args.tag_pointer.is_none()
+            && args.content_pointer.is_none()
+        {
+            return Ok(RecordFields::Keys {
+                tag: &args.tag,
+                content: &args.content,
+            });
+        }
+        for pointer in [&args.json_pointer, &args.tag_pointer, &args.content_pointer]
+            .into_iter()
+            .flatten()
+        {
+            ensure!(
+                pointer.is_empty() || pointer.starts_with('/'),
+                "Invalid JSON pointer {pointer:?}: must be empty or start with `/`"
+            );
+        }
+        // Pointers compose by concatenation, so the fields are resolved relative to the base.
+        let base = args.json_pointer.as_deref().unwrap_or("");
+        let resolve = |pointer: &Option<String>, key: &str| match pointer {
+            Some(pointer) => format!("{base}{pointer}"),
+            None => format!("{base}/{}", key.replace('~', "~0").replace('/', "~1")),
+        };
+        Ok(RecordFields::Pointers {
+            tag: resolve(&args.tag_pointer, &args.tag),
+            content: resolve(&args.content_pointer, &args.content),
+        })
+    }
+}
+
+/// How errors collected with `--aggregate-errors` are reported.
+#[derive(Clone, Copy)]
+enum ErrorReporting {
+    Fail,
+    Warn,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputEncoding {
+    Utf8,
+    /// ISO-8859-1, decoded as its Windows-1252 superset.
+    Latin1,
+    Utf16le,
+    Utf16be,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TagValueTransform {
+    Lowercase,
+    Snake,
+    Camel,
+    Pascal,
+}
+
+impl TagValueTransform {
+    fn apply(self, tag: &str) -> String {
+        match self {
+            TagValueTransform::Lowercase => tag.to_lowercase(),
+            TagValueTransform::Snake => snake_case(tag),
+            TagValueTransform::Camel => camel_case(tag),
+            TagValueTransform::Pascal => pascal_case(tag),
+        }
+    }
+}
+
+impl InputEncoding {
+    fn decode(self, bytes: Vec<u8>) -> Result<String> {
+        let encoding = match self {
+            InputEncoding::Utf8 => {
+                return String::from_utf8(bytes).context(
+                    "Input is not valid UTF-8; specify its encoding with --input-encoding",
+                );
+            }
+            InputEncoding::Latin1 => encoding_rs::WINDOWS_1252,
+            InputEncoding::Utf16le => encoding_rs::UTF_16LE,
+            InputEncoding::Utf16be => encoding_rs::UTF_16BE,
+        };
+        let (text, had_errors) = encoding.decode_with_bom_removal(&bytes);
+        ensure!(!had_errors, "Input is not valid {}", encoding.name());
+        Ok(text.into_owned())
+    }
+}
+
+#[derive(Serialize)]
+struct BatchSummary<'a> {
+    inputs: Vec<InputSummary<'a>>,
+    output: &'a str,
+    total_time_ms: u128,
+}
+
+#[derive(Serialize)]
+struct InputSummary<'a> {
+    path: &'a str,
+    records: usize,
+    event_types: BTreeMap<String, usize>,
+    /// The records skipped with `--warn-on-error`.
+    errors: Vec<RecordError>,
+}
+
+impl<'a> InputSummary<'a> {
+    fn new(path: &'a str, records: &[InputData], errors: Vec<RecordError>) -> Self {
+        let mut event_types = BTreeMap::<String, usize>::new();
+        for item in records {
+            *event_types.entry(item.r#type.clone()).or_default() += 1;
+        }
+        InputSummary {
+            path,
+            records: records.len(),
+            event_types,
+            errors,
+        }
+    }
+}
+
+/// A record that could not be parsed, at the position described by [`parse_json`].
+#[derive(Debug, Serialize)]
+struct RecordError {
+    record: usize,
+    message: String,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
+    if args.help_formats {
+        print!("{}", format_formats_table(&OutputFormat::all_info()));
+        return Ok(());
+    }
+    init_tracing(args.log_level, args.log_format);
+    let total_start = std::time::Instant::now();
+    let output_path = args.output.clone().unwrap_or_else(|| {
+        if args.emit_module_declaration.is_some() {
+            "output.d.ts".to_string()
+        } else {
+            format!("output.{}", args.format.info().extension)
+        }
+    });
 
-    let read_start = std::time::Instant::now();
-    let bytes = fs::read(&args.input)?;
-    let json_input = String::from_utf8(bytes)?;
-    println!("File reading took: {:?}", read_start.elapsed());
+    let input_format = args
+        .input_format
+        .unwrap_or_else(|| InputFormat::detect(&args.input));
 
-    let parse_start = std::time::Instant::now();
-    let json_array = if args.json_array {
-        let par_iter = serde_json::from_str::<Vec<Value>>(&json_input)?.into_par_iter();
-        parse_json(par_iter, &args.tag, &args.content)
+    let record_fields = RecordFields::from_args(&args)?;
+    ensure!(
+        args.split_output_by.is_none() || matches!(args.format, OutputFormat::TypeScript),
+        "--split-output-by is only supported with --format typescript"
+    );
+    ensure!(
+        !args.output_prettier || args.format.info().extension == "ts",
+        "--output-prettier is only supported with TypeScript output formats"
+    );
+    ensure!(
+        args.tag_value_transform.is_none() || matches!(args.tag_type, TagTypeArg::String),
+        "--tag-value-transform is only supported with --tag-type string"
+    );
+    ensure!(
+        !args.emit_ajv_schema || matches!(args.format, OutputFormat::TypeScript),
+        "--emit-ajv-schema is only supported with --format typescript"
+    );
+    ensure!(
+        !args.emit_branded_ids || matches!(args.format, OutputFormat::TypeScript),
+        "--emit-branded-ids is only supported with --format typescript"
+    );
+    ensure!(
+        !args.recursive_types || matches!(args.format, OutputFormat::TypeScript),
+        "--recursive-types is only supported with --format typescript"
+    );
+    ensure!(
+        !args.group_by_prefix_separator.is_empty(),
+        "--group-by-prefix-separator must not be empty"
+    );
+    let error_reporting = args.aggregate_errors.then_some(if args.warn_on_error {
+        ErrorReporting::Warn
     } else {
-        let par_iter = json_input
-            .lines()
-            .par_bridge()
-            .filter(|line| !line.trim().is_empty())
-            .map(|line| serde_json::from_str::<Value>(line).expect("Failed to parse JSON line"));
-        parse_json(par_iter, &args.tag, &args.content)
-    }?;
-    println!("JSON parsing took: {:?}", parse_start.elapsed());
-
-    let gen_start = std::time::Instant::now();
-    let ts_output = generate_typescript_definitions(json_array, &args.root_name)?;
-    println!("TypeScript generation took: {:?}", gen_start.elapsed());
-
-    let write_start = std::time::Instant::now();
-    fs::write(&args.output, ts_output)?;
-    println!("File writing took: {:?}", write_start.elapsed());
+        ErrorReporting::Fail
+    });
+
+    let bytes = info_span!("read_input", path = %args.input).in_scope(|| fs::read(&args.input))?;
+
+    let (json_array, record_errors) = info_span!("parse_json").in_scope(|| match input_format {
+        InputFormat::Json => {
+            let json_input = args.input_encoding.decode(bytes)?;
+            if args.json_array {
+                let par_iter = serde_json::from_str::<Vec<Value>>(&json_input)?
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(index, value)| (index + 1, Ok(value)));
+                parse_json(
+                    par_iter,
+                    &record_fields,
+                    args.tag_type.into(),
+                    args.tag_value_transform,
+                    error_reporting,
+                )
+            } else {
+                let par_iter = json_input
+                    .lines()
+                    .enumerate()
+                    .par_bridge()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(index, line)| {
+                        let value = serde_json::from_str::<Value>(line)
+                            .context("Failed to parse JSON line");
+                        (index + 1, value)
+                    });
+                parse_json(
+                    par_iter,
+                    &record_fields,
+                    args.tag_type.into(),
+                    args.tag_value_transform,
+                    error_reporting,
+                )
+            }
+        }
+        #[cfg(feature = "msgpack")]
+        InputFormat::Msgpack => {
+            let par_iter = read_msgpack_values(&bytes)?
+                .into_par_iter()
+                .enumerate()
+                .map(|(index, value)| (index + 1, Ok(value)));
+            parse_json(
+                par_iter,
+                &record_fields,
+                args.tag_type.into(),
+                args.tag_value_transform,
+                error_reporting,
+            )
+        }
+        #[cfg(feature = "csv")]
+        InputFormat::Csv => {
+            ensure!(
+                matches!(args.tag_type, TagTypeArg::String),
+                "CSV input is only supported with --tag-type string"
+            );
+            let csv_input = args.input_encoding.decode(bytes)?;
+            read_csv_records(
+                &csv_input,
+                &args.tag,
+                args.strict_csv_types,
+                args.tag_value_transform,
+            )
+            .map(|records| (records, Vec::new()))
+        }
+    })?;
+    tracing::info!(records = json_array.len(), "Parsed input records");
+
+    let input_summary = args
+        .batch_summary
+        .is_some()
+        .then(|| InputSummary::new(&args.input, &json_array, record_errors));
+
+    let property_filters = args
+        .property_filter_regex
+        .iter()
+        .map(|pattern| {
+            Regex::new(&format!("^(?:{pattern})$"))
+                .with_context(|| format!("Invalid --property-filter-regex: {pattern}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        args.prune_rare_properties
+            .is_none_or(|threshold| (0.0..=1.0).contains(&threshold)),
+        "--prune-rare-properties must be between 0 and 1"
+    );
+    ensure!(
+        args.pick_alias.len() <= args.emit_pick_types.len(),
+        "More --pick-alias values than --emit-pick-types specs"
+    );
+    let mut pick_aliases = args.pick_alias.into_iter();
+    let pick_types = args
+        .emit_pick_types
+        .iter()
+        .map(|spec| {
+            let spec = spec
+                .parse::<PickSpec>()
+                .with_context(|| format!("Invalid --emit-pick-types: {spec}"))?;
+            Ok(PickSpec {
+                alias: pick_aliases.next(),
+                ..spec
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(min_ts_version) = &args.min_ts_version {
+        let version = parse_ts_version(min_ts_version)
+            .with_context(|| format!("Invalid --min-ts-version: {min_ts_version}"))?;
+        if args.emit_satisfies && version < (4, 9) {
+            tracing::warn!(
+                min_ts_version,
+                "--emit-satisfies requires TypeScript 4.9 or later"
+            );
+        }
+        if args.emit_typescript_satisfies_check && version < (4, 9) {
+            tracing::warn!(
+                min_ts_version,
+                "--emit-typescript-satisfies-check requires TypeScript 4.9 or later"
+            );
+        }
+    }
+    let types_import = || {
+        args.emit_module_declaration
+            .clone()
+            .unwrap_or_else(|| fixtures_import_path(&output_path))
+    };
+    let test_fixtures_import = args.emit_test_fixtures.then(types_import);
+    let satisfies_check_import = args.emit_typescript_satisfies_check.then(types_import);
+    let options = GenerationOptions {
+        type_prefix: args.output_type_prefix,
+        type_suffix: args.output_type_suffix,
+        emit_union_helpers: args.emit_union_helpers,
+        emit_match_helper: args.emit_discriminated_union_helpers,
+        emit_fastify_schemas: args.emit_fastify_schema,
+        fetch_helpers: args.api_endpoint.map(|endpoint| FetchHelpers {
+            endpoint,
+            method: args.method,
+        }),
+        tag_type: args.tag_type.into(),
+        emit_branded_ids: args.emit_branded_ids,
+        request_response_pairs: args.emit_request_response_pairs.then_some(
+            RequestResponseSuffixes {
+                request: args.request_suffix,
+                response: args.response_suffix,
+            },
+        ),
+        module_declaration: args.emit_module_declaration,
+        content_merge_strategy: args.content_merge_strategy,
+        property_filters,
+        rare_property_threshold: args.prune_rare_properties,
+        event_name_case: if args.preserve_event_names {
+            EventNameCase::Preserve
+        } else {
+            args.event_name_case
+        },
+        type_name_collision_strategy: args.field_name_collision_strategy,
+        inference: InferenceOptions {
+            strict_tuples: args.strict_tuples,
+            parse_numeric_strings: args.parse_numbers_as_strings,
+            null_as_optional: args.null_as_optional,
+            max_examples: if args.field_comment_from_examples {
+                args.max_examples
+            } else {
+                0
+            },
+            example_string_limit: Some(args.truncate_strings),
+        },
+        format: FormatOptions {
+            property_order: args.sort_order,
+            json_schema_comments: args.emit_json_schema_comments,
+            property_docs_from_key: args.property_docs_from_key,
+            any_comment: args.emit_any_comments.then_some(args.any_comment),
+            suppress_any_lint: args.ts_strict,
+            index_signatures: args.emit_index_signature,
+            example_comments: args.field_comment_from_examples,
+        },
+        emit_class_validators: args.emit_class_validators,
+        emit_class_declarations: args.emit_class_interfaces,
+        class_to_json: args.emit_class_to_json,
+        emit_mapped_types: args.emit_mapped_types,
+        test_fixtures_import,
+        satisfies_check_import,
+        json_schema_id: args.emit_ajv_schema.then(|| {
+            args.schema_id.unwrap_or_else(|| {
+                format!(
+                    "https://example.com/{}.schema.json",
+                    args.root_name.to_lowercase()
+                )
+            })
+        }),
+        fixtures_satisfies: args.emit_satisfies,
+        fixtures_const_assertions: args.emit_const_assertions,
+        fixtures_type_check: args.emit_type_assertions,
+        recursive_types: args.recursive_types,
+        error_on_any: args.error_on_any,
+        pick_types,
+        compress_output: args.compress_output,
+        typedoc_module: args.emit_typedoc_module,
+        tsdoc_tags: args.tsdoc_tag,
+        emit_runtime_types: args.emit_runtime_types,
+    };
+    let generated = info_span!("generate_typescript").in_scope(|| match args.format {
+        OutputFormat::TypeScript => match args.split_output_by {
+            Some(prefix_length) => generate_split_typescript_files(
+                json_array,
+                &args.root_name,
+                &args.group_by_prefix_separator,
+                prefix_length.get(),
+                &options,
+            ),
+            None => generate_typescript_files(json_array, &args.root_name, &options),
+        },
+        OutputFormat::IoTs => Ok(GeneratedFiles {
+            definitions: generate_io_ts_codecs_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Openrpc => Ok(GeneratedFiles {
+            definitions: generate_openrpc_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Superstruct => Ok(GeneratedFiles {
+            definitions: generate_superstruct_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Runtypes => Ok(GeneratedFiles {
+            definitions: generate_runtypes_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Valibot => Ok(GeneratedFiles {
+            definitions: generate_valibot_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Typebox => Ok(GeneratedFiles {
+            definitions: generate_typebox_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::EffectSchema => Ok(GeneratedFiles {
+            definitions: generate_effect_schema_with_options(
+                json_array,
+                &args.root_name,
+                &options,
+            )?,
+            ..Default::default()
+        }),
+        OutputFormat::Pydantic => Ok(GeneratedFiles {
+            definitions: generate_pydantic_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Kotlin => Ok(GeneratedFiles {
+            definitions: generate_kotlin_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Swift => Ok(GeneratedFiles {
+            definitions: generate_swift_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Csharp => Ok(GeneratedFiles {
+            definitions: generate_csharp_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Java => Ok(GeneratedFiles {
+            definitions: generate_java_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Elm => Ok(GeneratedFiles {
+            definitions: generate_elm_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Haskell => Ok(GeneratedFiles {
+            definitions: generate_haskell_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::ClojureSpec => Ok(GeneratedFiles {
+            definitions: generate_clojure_spec_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Jtd => Ok(GeneratedFiles {
+            definitions: generate_jtd_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Cerberus => Ok(GeneratedFiles {
+            definitions: generate_cerberus_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Mongoose => Ok(GeneratedFiles {
+            definitions: generate_mongoose_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Django => Ok(GeneratedFiles {
+            definitions: generate_django_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+        OutputFormat::Typeorm => Ok(GeneratedFiles {
+            definitions: generate_typeorm_with_options(json_array, &args.root_name, &options)?,
+            ..Default::default()
+        }),
+    });
+    let generated = match generated {
+        Err(error) if error.is::<AnyTypeError>() => {
+            eprintln!("{error}");
+            std::process::exit(3);
+        }
+        generated => generated?,
+    };
+
+    let format_output = |source: String| {
+        if args.output_prettier {
+            info_span!("prettier").in_scope(|| format_with_prettier(source))
+        } else {
+            source
+        }
+    };
+    let definitions = wrap_output(
+        &args.output_header,
+        &generated.definitions,
+        &args.output_footer,
+    );
+    let definitions = format_output(definitions);
+    info_span!("write_output", path = %output_path)
+        .in_scope(|| fs::write(&output_path, definitions))?;
+    for (module_name, module) in generated.modules {
+        let module_path = Path::new(&output_path).with_file_name(format!("{module_name}.ts"));
+        let module = format_output(wrap_output(&args.output_header, &module, &[]));
+        info_span!("write_module", path = %module_path.display())
+            .in_scope(|| fs::write(&module_path, module))?;
+    }
+    if let Some(test_fixtures) = generated.test_fixtures {
+        let fixtures_path =
+            Path::new(&output_path).with_file_name(format!("{}.fixtures.ts", args.root_name));
+        let test_fixtures = format_output(test_fixtures);
+        info_span!("write_test_fixtures", path = %fixtures_path.display())
+            .in_scope(|| fs::write(&fixtures_path, test_fixtures))?;
+    }
+    if let Some(satisfies_check) = generated.satisfies_check {
+        let check_path =
+            Path::new(&output_path).with_file_name(format!("{}.validate.ts", args.root_name));
+        let satisfies_check = format_output(satisfies_check);
+        info_span!("write_satisfies_check", path = %check_path.display())
+            .in_scope(|| fs::write(&check_path, satisfies_check))?;
+    }
+    if let Some(json_schema) = generated.json_schema {
+        let schema_path =
+            Path::new(&output_path).with_file_name(format!("{}.schema.json", args.root_name));
+        info_span!("write_json_schema", path = %schema_path.display())
+            .in_scope(|| fs::write(&schema_path, json_schema))?;
+    }
+
+    if let (Some(summary_path), Some(input_summary)) = (&args.batch_summary, input_summary) {
+        let summary = BatchSummary {
+            inputs: vec![input_summary],
+            output: &output_path,
+            total_time_ms: total_start.elapsed().as_millis(),
+        };
+        fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
+    }
 
     Ok(())
 }
 
+/// Surrounds generated source with the concatenated `--output-header` and `--output-footer` texts.
+fn wrap_output(headers: &[String], source: &str, footers: &[String]) -> String {
+    headers.concat() + source + &footers.concat()
+}
+
+/// Installs the global subscriber, which logs to stderr. Span close events carry each phase's
+/// timing.
+fn init_tracing(level: LogLevel, format: LogFormat) {
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::from(level))
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal());
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+}
+
+/// Parses a `major.minor` TypeScript version. The minor version defaults to 0.
+fn parse_ts_version(version: &str) -> Result<(u32, u32)> {
+    let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+    Ok((major.parse()?, minor.parse()?))
+}
+
+/// Formats TypeScript with `prettier`, returning it unchanged with a warning if that fails, e.g.
+/// because `prettier` is not installed.
+fn format_with_prettier(source: String) -> String {
+    let mut command = Command::new("prettier");
+    command.args(["--parser", "typescript"]);
+    format_with(command, source)
+}
+
+/// Pipes `source` through a formatter `command`, returning it unchanged with a warning if that
+/// fails.
+fn format_with(command: Command, source: String) -> String {
+    match run_formatter(command, &source) {
+        Ok(formatted) => formatted,
+        Err(error) => {
+            tracing::warn!("Writing unformatted output: {error:#}");
+            source
+        }
+    }
+}
+
+fn run_formatter(mut command: Command, source: &str) -> Result<String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Could not run {program}"))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    // Write from another thread so that a full stdout pipe cannot block the write.
+    let (output, written) = std::thread::scope(|scope| {
+        let writer = scope.spawn(move || stdin.write_all(source.as_bytes()));
+        let output = child.wait_with_output();
+        (output, writer.join().expect("the writer does not panic"))
+    });
+    let output = output?;
+    // A formatter that exits early closes its stdin, so its own error explains a failed write.
+    ensure!(
+        output.status.success(),
+        "{program} failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    written?;
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// The relative module specifier the fixtures module uses to import the generated types.
+fn fixtures_import_path(output_path: &str) -> String {
+    let file_name = Path::new(output_path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(output_path);
+    let module_name = [".d.ts", ".ts"]
+        .into_iter()
+        .find_map(|extension| file_name.strip_suffix(extension))
+        .unwrap_or(file_name);
+    format!("./{module_name}")
+}
+
+fn format_formats_table(formats: &[FormatInfo]) -> String {
+    let name_width = formats
+        .iter()
+        .map(|format| format.name.len())
+        .chain(["NAME".len()])
+        .max()
+        .unwrap_or_default();
+    let feature_width = formats
+        .iter()
+        .filter_map(|format| format.feature.map(str::len))
+        .chain(["FEATURE".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!(
+        "{:name_width$}  {:9}  {:feature_width$}  DESCRIPTION\n",
+        "NAME", "EXTENSION", "FEATURE"
+    );
+    for format in formats {
+        table.push_str(&format!(
+            "{:name_width$}  {:9}  {:feature_width$}  {}\n",
+            format.name,
+            format!(".{}", format.extension),
+            format.feature.unwrap_or("-"),
+            format.description
+        ));
+    }
+    table
+}
+
+/// Extracts the records from `(position, value)` pairs, where the position is the 1-based line
+/// number for JSON Lines input and the 1-based record number otherwise.
+///
+/// Without `error_reporting`, the first error is returned. Otherwise every record is parsed and
+/// all errors are reported together, and with [`ErrorReporting::Warn`] the skipped records are
+/// returned along with the parsed ones.
 fn parse_json(
-    par_iter: impl ParallelIterator<Item = Value>,
+    par_iter: impl ParallelIterator<Item = (usize, Result<Value>)>,
+    fields: &RecordFields,
+    tag_type: TagType,
+    tag_value_transform: Option<TagValueTransform>,
+    error_reporting: Option<ErrorReporting>,
+) -> Result<(Vec<InputData>, Vec<RecordError>)> {
+    let results = par_iter.map(|(position, value)| {
+        let records = value.and_then(|value| match fields {
+            RecordFields::Keys { tag, content } => {
+                InputData::from_json_value_with_tag_type(&value, tag, tag_type, content)
+                    .map(|record| vec![record])
+            }
+            RecordFields::Pointers { tag, content } => {
+                InputData::from_json_pointers(&value, tag, tag_type, content)
+                    .map(|record| vec![record])
+            }
+            RecordFields::JsonPaths { tag, content } => {
+                InputData::from_json_paths(&value, tag, tag_type, content)
+                    .map(|record| vec![record])
+            }
+            RecordFields::List { tag, list } => {
+                InputData::from_json_list_with_tag_type(&value, tag, tag_type, list)
+            }
+        });
+        let records = records.map(|mut records| {
+            if let Some(transform) = tag_value_transform {
+                for record in &mut records {
+                    record.r#type = transform.apply(&record.r#type);
+                }
+            }
+            records
+        });
+        (position, records)
+    });
+    let Some(error_reporting) = error_reporting else {
+        let records = results
+            .map(|(_, records)| records)
+            .collect::<Result<Vec<_>>>()?;
+        return Ok((records.into_iter().flatten().collect(), Vec::new()));
+    };
+
+    let (records, mut errors): (Vec<_>, Vec<_>) =
+        results.partition_map(|(position, records)| match records {
+            Ok(records) => Either::Left(records),
+            Err(error) => Either::Right((position, error)),
+        });
+    errors.sort_unstable_by_key(|(position, _)| *position);
+    match error_reporting {
+        ErrorReporting::Fail => {
+            for (position, error) in &errors {
+                eprintln!("Record {position}: {error:#}");
+            }
+            ensure!(
+                errors.is_empty(),
+                "{} of {} records could not be parsed",
+                errors.len(),
+                errors.len() + records.len()
+            );
+        }
+        ErrorReporting::Warn => {
+            for (position, error) in &errors {
+                tracing::warn!(record = position, "{error:#}");
+            }
+        }
+    }
+    let errors = errors
+        .into_iter()
+        .map(|(position, error)| RecordError {
+            record: position,
+            message: format!("{error:#}"),
+        })
+        .collect();
+    Ok((records.into_iter().flatten().collect(), errors))
+}
+
+/// Reads each CSV row as a record whose tag is the `tag` column and whose content is an object of
+/// the other columns, keyed by their headers. Unless `strict_types`, values that are JSON numbers
+/// or booleans are converted to them.
+#[cfg(feature = "csv")]
+fn read_csv_records(
+    csv_input: &str,
     tag: &str,
-    content: &str,
+    strict_types: bool,
+    tag_value_transform: Option<TagValueTransform>,
 ) -> Result<Vec<InputData>> {
-    par_iter
-        .map(|value| {
-            let r#type = value
-                .get(tag)
-                .and_then(Value::as_str)
-                .with_context(|| format!("Missing or invalid {tag} field in value: {value}"))?
-                .to_string();
-            let content = value
-                .get(content)
-                .and_then(Value::as_str)
-                .with_context(|| format!("Missing or invalid {content} field in type {type}"))?
-                .to_string();
-            Ok(InputData { r#type, content })
+    let mut reader = csv::Reader::from_reader(csv_input.as_bytes());
+    let headers = reader
+        .headers()
+        .context("Failed to read the CSV header")?
+        .clone();
+    let tag_index = headers
+        .iter()
+        .position(|header| header == tag)
+        .with_context(|| format!("Missing {tag} column in the CSV header"))?;
+    reader
+        .records()
+        .enumerate()
+        .map(|(index, record)| {
+            let record =
+                record.with_context(|| format!("Failed to parse CSV row {}", index + 1))?;
+            let content = headers
+                .iter()
+                .zip(record.iter())
+                .enumerate()
+                .filter(|(column, _)| *column != tag_index)
+                .map(|(_, (header, field))| {
+                    let value = match field.parse::<bool>() {
+                        Ok(flag) if !strict_types => Value::Bool(flag),
+                        _ => match field.parse::<serde_json::Number>() {
+                            Ok(number) if !strict_types => Value::Number(number),
+                            _ => Value::String(field.to_string()),
+                        },
+                    };
+                    (header.to_string(), value)
+                })
+                .collect::<serde_json::Map<_, _>>();
+            let tag_value = &record[tag_index];
+            Ok(InputData {
+                r#type: match tag_value_transform {
+                    Some(transform) => transform.apply(tag_value),
+                    None => tag_value.to_string(),
+                },
+                content: Value::Object(content).to_string(),
+            })
         })
         .collect()
 }
+
+/// Reads every MessagePack document in the stream sequentially.
+#[cfg(feature = "msgpack")]
+fn read_msgpack_values(mut bytes: &[u8]) -> Result<Vec<Value>> {
+    let mut values = Vec::new();
+    while !bytes.is_empty() {
+        let value = rmp_serde::from_read(&mut bytes).with_context(|| {
+            format!(
+                "Failed to decode MessagePack document #{}",
+                values.len() + 1
+            )
+        })?;
+        values.push(value);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_input_format() {
+        assert!(matches!(
+            InputFormat::detect("events.jsonl"),
+            InputFormat::Json
+        ));
+        #[cfg(feature = "msgpack")]
+        assert!(matches!(
+            InputFormat::detect("logs/events.msgpack"),
+            InputFormat::Msgpack
+        ));
+    }
+
+    #[test]
+    fn test_output_header() {
+        let headers = [
+            "// @ts-nocheck\n".to_string(),
+            "/* eslint-disable */\n".to_string(),
+        ];
+        assert_eq!(
+            wrap_output(&headers, "export type Events = never;\n", &[]),
+            "// @ts-nocheck\n/* eslint-disable */\nexport type Events = never;\n"
+        );
+        assert_eq!(wrap_output(&[], "type A = 1;\n", &[]), "type A = 1;\n");
+    }
+
+    #[test]
+    fn test_output_footer() {
+        let footers = [
+            "\nexport default Events;\n".to_string(),
+            "// end\n".to_string(),
+        ];
+        assert_eq!(
+            wrap_output(
+                &["// header\n".to_string()],
+                "export type Events = never;\n",
+                &footers
+            ),
+            "// header\nexport type Events = never;\n\nexport default Events;\n// end\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_format_with() {
+        let source = "export type Events = never;\n".to_string();
+        assert_eq!(format_with(Command::new("cat"), source.clone()), source);
+
+        let missing = Command::new("infer-json-stream-missing-formatter");
+        let error = run_formatter(missing, &source).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not run infer-json-stream-missing-formatter"
+        );
+        let missing = Command::new("infer-json-stream-missing-formatter");
+        assert_eq!(format_with(missing, source.clone()), source);
+
+        let mut failing = Command::new("sh");
+        failing.args(["-c", "echo 'SyntaxError' >&2; exit 2"]);
+        let error = run_formatter(failing, &source).unwrap_err();
+        assert_eq!(error.to_string(), "sh failed: SyntaxError");
+    }
+
+    #[test]
+    fn test_input_encoding_decode() {
+        assert_eq!(
+            InputEncoding::Utf8
+                .decode("café".as_bytes().to_vec())
+                .unwrap(),
+            "café"
+        );
+        assert_eq!(
+            InputEncoding::Latin1
+                .decode(b"caf\xe9 \x80".to_vec())
+                .unwrap(),
+            "café €"
+        );
+        assert_eq!(
+            InputEncoding::Utf16le
+                .decode(b"\xff\xfe{\x00}\x00".to_vec())
+                .unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            InputEncoding::Utf16be
+                .decode(b"\x00{\x00}".to_vec())
+                .unwrap(),
+            "{}"
+        );
+
+        let error = InputEncoding::Utf8.decode(b"caf\xe9".to_vec()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Input is not valid UTF-8; specify its encoding with --input-encoding"
+        );
+        let error = InputEncoding::Utf16le
+            .decode(b"\x00\xdc".to_vec())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Input is not valid UTF-16LE");
+    }
+
+    const LINES: [&str; 4] = [
+        r#"{"type":"login","content":"{}"}"#,
+        "{",
+        r#"{"type":"login","content":"{}"}"#,
+        r#"{"content":"{}"}"#,
+    ];
+
+    /// Parses JSON Lines with `type` tags and `content` fields.
+    fn parse_lines(
+        lines: &[&str],
+        error_reporting: Option<ErrorReporting>,
+    ) -> Result<(Vec<InputData>, Vec<RecordError>)> {
+        let par_iter = lines.into_par_iter().enumerate().map(|(index, line)| {
+            let value = serde_json::from_str::<Value>(line).context("Failed to parse JSON line");
+            (index + 1, value)
+        });
+        let fields = RecordFields::Keys {
+            tag: "type",
+            content: "content",
+        };
+        parse_json(par_iter, &fields, TagType::String, None, error_reporting)
+    }
+
+    #[test]
+    fn test_aggregate_errors() {
+        let error = parse_lines(&LINES[..2], None).unwrap_err();
+        assert_eq!(error.to_string(), "Failed to parse JSON line");
+
+        let error = parse_lines(&LINES, Some(ErrorReporting::Fail)).unwrap_err();
+        assert_eq!(error.to_string(), "2 of 4 records could not be parsed");
+
+        let (records, errors) = parse_lines(&LINES[..1], Some(ErrorReporting::Fail)).unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_tag_value_transform() {
+        for (transform, expected) in [
+            (TagValueTransform::Lowercase, "usercreated"),
+            (TagValueTransform::Snake, "user_created"),
+            (TagValueTransform::Camel, "userCreated"),
+            (TagValueTransform::Pascal, "UserCreated"),
+        ] {
+            assert_eq!(transform.apply("UserCreated"), expected);
+        }
+
+        let lines = [
+            r#"{"type":"UserCreated","content":"{}"}"#,
+            r#"{"type":"user_created","content":"{}"}"#,
+        ];
+        let par_iter = lines.into_par_iter().enumerate().map(|(index, line)| {
+            (
+                index + 1,
+                serde_json::from_str::<Value>(line).map_err(Into::into),
+            )
+        });
+        let fields = RecordFields::Keys {
+            tag: "type",
+            content: "content",
+        };
+        let (records, _) = parse_json(
+            par_iter,
+            &fields,
+            TagType::String,
+            Some(TagValueTransform::Snake),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| record.r#type.as_str())
+                .collect::<Vec<_>>(),
+            ["user_created", "user_created"]
+        );
+    }
+
+    #[test]
+    fn test_batch_summary_reports_skipped_records() {
+        let (records, errors) = parse_lines(&LINES, Some(ErrorReporting::Warn)).unwrap();
+
+        let summary = InputSummary::new("events.jsonl", &records, errors);
+        assert_eq!(
+            serde_json::to_string_pretty(&summary).unwrap(),
+            r#"{
+  "path": "events.jsonl",
+  "records": 2,
+  "event_types": {
+    "login": 2
+  },
+  "errors": [
+    {
+      "record": 2,
+      "message": "Failed to parse JSON line: EOF while parsing an object at line 1 column 1"
+    },
+    {
+      "record": 4,
+      "message": "Missing or invalid type field in value: {\"content\":\"{}\"}"
+    }
+  ]
+}"#
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_read_csv_records() {
+        let csv_input = "active,id,name,type\ntrue,1,alice,login\nfalse,02,\"b, c\",logout\n";
+        let contents = |strict_types| {
+            read_csv_records(csv_input, "type", strict_types, None)
+                .unwrap()
+                .into_iter()
+                .map(|record| format!("{}: {}", record.r#type, record.content))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            contents(false),
+            [
+                r#"login: {"active":true,"id":1,"name":"alice"}"#,
+                r#"logout: {"active":false,"id":"02","name":"b, c"}"#,
+            ]
+        );
+        assert_eq!(
+            contents(true),
+            [
+                r#"login: {"active":"true","id":"1","name":"alice"}"#,
+                r#"logout: {"active":"false","id":"02","name":"b, c"}"#,
+            ]
+        );
+
+        let error = read_csv_records(csv_input, "event", false, None).unwrap_err();
+        assert_eq!(error.to_string(), "Missing event column in the CSV header");
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_read_msgpack_values() {
+        use serde_json::json;
+
+        let first = json!({ "type": "login", "content": { "user_id": 1, "name": "a" } });
+        let second = json!({ "type": "logout", "content": [true, null, 1.5] });
+        let mut bytes = rmp_serde::to_vec(&first).unwrap();
+        bytes.extend(rmp_serde::to_vec(&second).unwrap());
+        assert_eq!(read_msgpack_values(&bytes).unwrap(), vec![first, second]);
+
+        assert!(read_msgpack_values(&[]).unwrap().is_empty());
+        let error = read_msgpack_values(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to decode MessagePack document #2"
+        );
+    }
+}
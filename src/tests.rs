@@ -1,10 +1,42 @@
 use crate::{
-    generation::generate_typescript_definitions,
-    inference::{infer_type_from_value, merge_types},
-    types::{InferredType, InputData, PrimitiveType, PropertyDefinition},
+    formatting::{FormatOptions, display_type_tree, format_type_to_ts_string},
+    generation::{
+        AnyTypeError, ContentMergeStrategy, EventNameCase, FetchHelpers, FormatInfo,
+        GenerationOptions, HttpMethod, OutputFormat, PickSpec, RequestResponseSuffixes, TsdocTag,
+        TypeNameCollisionStrategy,
+        cerberus::generate_cerberus,
+        clojure::generate_clojure_spec,
+        csharp::generate_csharp,
+        django::generate_django,
+        effect_schema::generate_effect_schema,
+        elm::generate_elm,
+        generate_typescript_definitions, generate_typescript_definitions_with_options,
+        generate_typescript_files,
+        haskell::generate_haskell,
+        io_ts::generate_io_ts_codecs,
+        java::generate_java,
+        jtd::generate_jtd,
+        kotlin::{generate_kotlin, generate_kotlin_with_options},
+        mongoose::generate_mongoose,
+        openrpc::generate_openrpc,
+        pydantic::generate_pydantic,
+        runtypes::generate_runtypes,
+        split::generate_split_typescript_files,
+        superstruct::generate_superstruct,
+        swift::{generate_swift, generate_swift_with_options},
+        typebox::generate_typebox,
+        typeorm::generate_typeorm,
+        valibot::generate_valibot,
+    },
+    inference::{
+        InferenceOptions, infer_type_from_value, infer_type_from_value_with_options, merge_types,
+        merge_types_with_options,
+    },
+    types::{InferredType, InputData, PrimitiveType, PropertyDefinition, PropertyMap, TagType},
 };
 use rstest::rstest;
-use std::{borrow::Cow, collections::HashMap};
+use serde_json_path::JsonPath;
+use std::{borrow::Cow, str::FromStr};
 
 #[rstest]
 #[case::simple_primitives(
@@ -116,7 +148,19 @@ export type Events = { type: "tupleEvent", content: TupleEventContent };
         { "type": "tupleEvent", "content": "\"{\\\"mixed\\\":[10, \\\"hello\\\"]}\"" }
     ]"#,
     r#"export type TupleEventContent = {
-  mixed: [string, number]
+  mixed: [number, string]
+};
+
+export type Events = { type: "tupleEvent", content: TupleEventContent };
+"#
+)]
+#[case::tuple_order_mismatch(
+    r#"[
+        { "type": "tupleEvent", "content": "\"{\\\"pair\\\":[\\\"a\\\", 1]}\"" },
+        { "type": "tupleEvent", "content": "\"{\\\"pair\\\":[1, \\\"a\\\"]}\"" }
+    ]"#,
+    r#"export type TupleEventContent = {
+  pair: Array<string | number>
 };
 
 export type Events = { type: "tupleEvent", content: TupleEventContent };
@@ -526,12 +570,13 @@ fn test_merge_primitive_types() {
 #[test]
 fn test_merge_objects() {
     // Create two simple object types for testing.
-    let mut obj1 = HashMap::new();
+    let mut obj1 = PropertyMap::new();
     obj1.insert(
         "id".to_string(),
         PropertyDefinition {
             r#type: InferredType::Primitive(PrimitiveType::Number),
             optional: false,
+            examples: Vec::new(),
         },
     );
     obj1.insert(
@@ -539,15 +584,17 @@ fn test_merge_objects() {
         PropertyDefinition {
             r#type: InferredType::Primitive(PrimitiveType::String),
             optional: false,
+            examples: Vec::new(),
         },
     );
 
-    let mut obj2 = HashMap::new();
+    let mut obj2 = PropertyMap::new();
     obj2.insert(
         "id".to_string(),
         PropertyDefinition {
             r#type: InferredType::Primitive(PrimitiveType::Number),
             optional: false,
+            examples: Vec::new(),
         },
     );
     obj2.insert(
@@ -555,6 +602,7 @@ fn test_merge_objects() {
         PropertyDefinition {
             r#type: InferredType::Primitive(PrimitiveType::Number),
             optional: false,
+            examples: Vec::new(),
         },
     );
 
@@ -651,3 +699,3521 @@ fn test_custom_primitive_type_ordering() {
     sorted_types.sort();
     assert_eq!(sorted_types, types,);
 }
+
+#[rstest]
+#[case::prefix("I", "", "ILoginContent")]
+#[case::suffix("", "Dto", "LoginContentDto")]
+#[case::prefix_and_suffix("I", "Dto", "ILoginContentDto")]
+fn test_output_type_prefix_and_suffix(
+    #[case] type_prefix: &str,
+    #[case] type_suffix: &str,
+    #[case] expected_name: &str,
+) {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        type_prefix: type_prefix.to_string(),
+        type_suffix: type_suffix.to_string(),
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        format!(
+            "export type {expected_name} = {{\n  userId: number\n}};\n\nexport type Events = {{ type: \"login\", content: {expected_name} }};\n"
+        )
+    );
+}
+
+#[test]
+fn test_inferred_type_display() {
+    assert_eq!(
+        InferredType::Primitive(PrimitiveType::String).to_string(),
+        "string"
+    );
+    assert_eq!(InferredType::Any.to_string(), "any");
+    assert_eq!(InferredType::Never.to_string(), "never");
+    assert_eq!(
+        InferredType::Array(Box::new(InferredType::Primitive(PrimitiveType::Number))).to_string(),
+        "number[]"
+    );
+    assert_eq!(
+        InferredType::Array(Box::new(InferredType::PrimitiveUnion(vec![
+            PrimitiveType::String,
+            PrimitiveType::Number
+        ])))
+        .to_string(),
+        "(string | number)[]"
+    );
+    assert_eq!(
+        InferredType::PrimitiveTuple(vec![PrimitiveType::Number, PrimitiveType::String])
+            .to_string(),
+        "[number, string]"
+    );
+
+    let mut properties = PropertyMap::new();
+    properties.insert(
+        "b".to_string(),
+        PropertyDefinition {
+            r#type: InferredType::Primitive(PrimitiveType::String),
+            optional: true,
+            examples: Vec::new(),
+        },
+    );
+    properties.insert(
+        "a".to_string(),
+        PropertyDefinition {
+            r#type: InferredType::Primitive(PrimitiveType::Number),
+            optional: false,
+            examples: Vec::new(),
+        },
+    );
+    let object = InferredType::Object(properties);
+    assert_eq!(object.to_string(), "{ a: number, b?: string }");
+    assert_eq!(
+        InferredType::NullableObj(Box::new(object)).to_string(),
+        "{ a: number, b?: string } | null"
+    );
+}
+
+#[test]
+fn test_emit_union_helpers() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        emit_union_helpers: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(ts_output.contains(
+        "export function getEventType<T extends Events>(event: T): T[\"type\"] {\n  return event.type;\n}"
+    ));
+    assert!(ts_output.contains(
+        "export function isLogin(event: Events): event is Extract<Events, { type: \"login\" }> {\n  return event.type === \"login\";\n}"
+    ));
+    assert!(ts_output.contains(
+        "export function isLogout(event: Events): event is Extract<Events, { type: \"logout\" }> {"
+    ));
+}
+
+#[test]
+fn test_inferred_type_predicates() {
+    let inferred_type = merge_types(
+        infer_type_from_value(serde_json::json!({ "profile": { "name": "a" } })),
+        infer_type_from_value(serde_json::json!({ "profile": null, "note": "x" })),
+    );
+    assert!(inferred_type.is_optional_anywhere());
+    assert!(!inferred_type.is_nullable());
+    assert!(!inferred_type.contains_any());
+    assert!(!inferred_type.contains_never());
+    let InferredType::Object(properties) = &inferred_type else {
+        panic!("expected an object, got {inferred_type}");
+    };
+    assert!(properties["profile"].r#type.is_nullable());
+
+    let nested = InferredType::Array(Box::new(InferredType::ComplexUnion(vec![
+        InferredType::Any,
+        InferredType::Never,
+    ])));
+    assert!(nested.contains_any());
+    assert!(nested.contains_never());
+    assert!(!nested.is_optional_anywhere());
+    assert!(
+        InferredType::PrimitiveUnion(vec![PrimitiveType::String, PrimitiveType::Null])
+            .is_nullable()
+    );
+}
+
+#[test]
+fn test_union_member_order_is_deterministic() {
+    let input_data = (0..64)
+        .rev()
+        .map(|i| InputData {
+            r#type: format!("event{i:02}"),
+            content: "1".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let ts_output = generate_typescript_definitions(input_data, "Events").unwrap();
+    let union = ts_output.lines().last().unwrap();
+    let expected = (0..64)
+        .map(|i| format!("{{ type: \"event{i:02}\", content: Event{i:02}Content }}"))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    assert_eq!(union, format!("export type Events = {expected};"));
+}
+
+#[test]
+fn test_any_comments() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"tags\":[{},1],\"meta\":{\"extra\":{}},\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"tags\":[],\"meta\":{\"extra\":1},\"userId\":2}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        format: FormatOptions {
+            any_comment: Some("TODO: review".to_string()),
+            suppress_any_lint: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type LoginContent = {
+  meta: {
+  // TODO: review
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  extra: any
+};
+  // TODO: review
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  tags: Array<any>;
+  userId: number
+};
+
+export type Events = { type: "login", content: LoginContent };
+"#
+    );
+}
+
+#[test]
+fn test_numeric_tag_type() {
+    let input_data = vec![
+        InputData {
+            r#type: "42".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "7".to_string(),
+            content: "{\"ok\":true}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        tag_type: TagType::Number,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(ts_output.contains("export type Type42Content = {"));
+    assert!(ts_output.ends_with(
+        "export type Events = { type: 42, content: Type42Content } | { type: 7, content: Type7Content };\n"
+    ));
+}
+
+#[test]
+fn test_tag_type_extract() {
+    let value = serde_json::json!(42);
+    assert_eq!(TagType::Number.extract(&value), Some("42".to_string()));
+    assert_eq!(TagType::String.extract(&value), None);
+    assert_eq!(
+        TagType::Bool.extract(&serde_json::json!(true)),
+        Some("true".to_string())
+    );
+    assert_eq!(
+        TagType::Any.extract(&serde_json::json!("login")),
+        Some("\"login\"".to_string())
+    );
+}
+
+#[test]
+fn test_emit_branded_ids() {
+    let input_data = vec![InputData {
+        r#type: "purchase".to_string(),
+        content: "{\"userId\":1,\"order_id\":\"a1\",\"items\":[{\"productId\":2}],\"id\":3}"
+            .to_string(),
+    }];
+    let options = GenerationOptions {
+        emit_branded_ids: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type OrderId = string & { readonly __brand: "OrderId" };
+
+export type ProductId = number & { readonly __brand: "ProductId" };
+
+export type UserId = number & { readonly __brand: "UserId" };
+
+export type PurchaseContent = {
+  id: number;
+  items: Array<{
+  productId: ProductId
+}>;
+  order_id: OrderId;
+  userId: UserId
+};
+
+export type Events = { type: "purchase", content: PurchaseContent };
+"#
+    );
+}
+
+#[test]
+fn test_emit_request_response_pairs() {
+    let input_data = vec![
+        InputData {
+            r#type: "userFetch_request".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "userFetch_response".to_string(),
+            content: "{\"name\":\"Alice\"}".to_string(),
+        },
+        InputData {
+            r#type: "orphan_request".to_string(),
+            content: "{}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        request_response_pairs: Some(RequestResponseSuffixes {
+            request: "_request".to_string(),
+            response: "_response".to_string(),
+        }),
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type OrphanRequestContent = object;
+
+export type UserFetchRequest = {
+  userId: number
+};
+
+export type UserFetchResponse = {
+  name: string
+};
+
+export type UserFetchOperation = { request: UserFetchRequest; response: UserFetchResponse };
+
+export type Events = { type: "orphan_request", content: OrphanRequestContent } | { type: "userFetch_request", content: UserFetchRequest } | { type: "userFetch_response", content: UserFetchResponse };
+"#
+    );
+}
+
+#[test]
+fn test_tuple_preserves_element_order() {
+    assert_eq!(
+        infer_type_from_value(serde_json::json!(["a", 1, true])),
+        InferredType::PrimitiveTuple(vec![
+            PrimitiveType::String,
+            PrimitiveType::Number,
+            PrimitiveType::Boolean
+        ])
+    );
+    assert_eq!(
+        infer_type_from_value(serde_json::json!([1, "a"])),
+        InferredType::PrimitiveTuple(vec![PrimitiveType::Number, PrimitiveType::String])
+    );
+}
+
+#[test]
+fn test_emit_module_declaration() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        emit_union_helpers: true,
+        module_declaration: Some("my-events".to_string()),
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"declare module "my-events" {
+  export type LoginContent = {
+    userId: number
+  };
+
+  export type Events = { type: "login", content: LoginContent };
+
+  export function getEventType<T extends Events>(event: T): T["type"];
+
+  export function isLogin(event: Events): event is Extract<Events, { type: "login" }>;
+}
+"#
+    );
+}
+
+#[test]
+fn test_cross_type_content_merge_strategy() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"timestamp\":2}".to_string(),
+        },
+        InputData {
+            r#type: "purchase".to_string(),
+            content: "{\"userId\":1,\"amount\":29.99}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        content_merge_strategy: ContentMergeStrategy::CrossType,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type UnifiedContent = {
+  amount?: number;
+  timestamp?: number;
+  userId: number
+};
+
+export type Events = { type: string, content: UnifiedContent };
+"#
+    );
+}
+
+#[test]
+fn test_cross_type_content_merge_strategy_comments() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "broken".to_string(),
+            content: "{invalid".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        content_merge_strategy: ContentMergeStrategy::CrossType,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"// The 'content' field of 'broken' contained invalid JSON: "{invalid"
+export type UnifiedContent = any;
+
+export type Events = { type: string, content: UnifiedContent };
+"#
+    );
+}
+
+#[test]
+fn test_cross_type_content_merge_strategy_rejects_per_type_options() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        content_merge_strategy: ContentMergeStrategy::CrossType,
+        emit_union_helpers: true,
+        emit_class_declarations: true,
+        ..Default::default()
+    };
+
+    let error =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "--emit-union-helpers, --emit-class-interfaces cannot be used with --content-merge-strategy cross-type"
+    );
+}
+
+#[test]
+fn test_property_filter_regex() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1,\"_debug\":true,\"meta\":{\"__metadata\":{}},\"user_name\":\"a\"}"
+            .to_string(),
+    }];
+    let options = GenerationOptions {
+        property_filters: vec![regex::Regex::new("^(?:_.*)$").unwrap()],
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type LoginContent = {
+  meta: object;
+  userId: number;
+  user_name: string
+};
+
+export type Events = { type: "login", content: LoginContent };
+"#
+    );
+}
+
+#[rstest]
+#[case::pascal(EventNameCase::Pascal, "UserCreatedContent")]
+#[case::camel(EventNameCase::Camel, "userCreatedContent")]
+#[case::snake(EventNameCase::Snake, "user_createdContent")]
+#[case::preserve(EventNameCase::Preserve, "user_createdContent")]
+fn test_event_name_case(#[case] event_name_case: EventNameCase, #[case] expected_name: &str) {
+    let input_data = vec![InputData {
+        r#type: "user_created".to_string(),
+        content: "{}".to_string(),
+    }];
+    let options = GenerationOptions {
+        event_name_case,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(ts_output.starts_with(&format!("export type {expected_name} = object;")));
+}
+
+#[test]
+fn test_preserve_event_names_sanitizes_identifiers() {
+    let input_data = vec![InputData {
+        r#type: "UserAPI.created".to_string(),
+        content: "{}".to_string(),
+    }];
+    let options = GenerationOptions {
+        event_name_case: EventNameCase::Preserve,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(ts_output.contains(
+        "export type Events = { type: \"UserAPI.created\", content: UserAPI_createdContent };"
+    ));
+}
+
+#[test]
+fn test_emit_class_validators() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"tags\":[\"a\"],\"note\":\"x\",\"meta\":{\"a\":1}}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":2,\"tags\":[\"b\"],\"note\":null}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "1".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        emit_class_validators: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"import { IsArray, IsNumber, IsObject, IsOptional, IsString } from "class-validator";
+
+export type LoginContent = {
+  meta?: {
+  a: number
+};
+  note: string | null;
+  tags: [string];
+  userId: number
+};
+
+export class LoginContentDto {
+  @IsOptional()
+  @IsObject()
+  meta?: {
+  a: number
+};
+  @IsOptional()
+  @IsString()
+  note!: string | null;
+  @IsArray()
+  tags!: [string];
+  @IsNumber()
+  userId!: number;
+}
+
+export type LogoutContent = number;
+
+export type Events = { type: "login", content: LoginContent } | { type: "logout", content: LogoutContent };
+"#
+    );
+}
+
+#[test]
+fn test_emit_json_schema_comments() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"tags\":[\"a\",\"b\"],\"note\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":2,\"tags\":[\"c\"]}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        format: FormatOptions {
+            json_schema_comments: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type LoginContent = {
+  /* {"description":"note","type":"string"} */
+  note?: string;
+  /* {"description":"tags","items":{"type":"string"},"type":"array"} */
+  tags: Array<string>;
+  /* {"description":"userId","type":"number"} */
+  userId: number
+};
+
+export type Events = { type: "login", content: LoginContent };
+"#
+    );
+}
+
+#[test]
+fn test_field_comment_from_examples() {
+    let input_data = ["alice", "bob", "alice", "carol", "dave"]
+        .into_iter()
+        .map(|name| InputData {
+            r#type: "login".to_string(),
+            content: format!("{{\"user\":{{\"name\":\"{name}\"}},\"retry\":false}}"),
+        })
+        .collect();
+    let options = GenerationOptions {
+        inference: InferenceOptions {
+            max_examples: 3,
+            ..Default::default()
+        },
+        format: FormatOptions {
+            example_comments: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type LoginContent = {
+  // e.g.: false
+  retry: boolean;
+  // e.g.: {"name":"alice"}, {"name":"bob"}, {"name":"carol"}
+  user: {
+  // e.g.: "alice", "bob", "carol"
+  name: string
+}
+};
+
+export type Events = { type: "login", content: LoginContent };
+"#
+    );
+}
+
+#[test]
+fn test_emit_index_signature() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"name\":\"a\",\"note\":\"hi\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":2,\"name\":\"b\"}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        format: FormatOptions {
+            index_signatures: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type LoginContent = {
+  [key: string]: string | number | undefined;
+  name: string;
+  note?: string;
+  userId: number
+};
+
+export type Events = { type: "login", content: LoginContent };
+"#
+    );
+}
+
+#[test]
+fn test_input_data_from_json_value() {
+    let value = serde_json::json!({ "event": "login", "payload": "{}" });
+
+    let input_data = InputData::from_json_value(&value, "event", "payload").unwrap();
+    assert_eq!(input_data.r#type, "login");
+    assert_eq!(input_data.content, "{}");
+}
+
+#[rstest]
+#[case::missing_tag(
+    serde_json::json!({ "payload": "{}" }),
+    "Missing or invalid event field in value: {\"payload\":\"{}\"}"
+)]
+#[case::missing_content(
+    serde_json::json!({ "event": "login" }),
+    "Missing or invalid payload field in type login"
+)]
+#[case::non_string_tag(
+    serde_json::json!({ "event": 1, "payload": "{}" }),
+    "Missing or invalid event field in value: {\"event\":1,\"payload\":\"{}\"}"
+)]
+#[case::non_string_content(
+    serde_json::json!({ "event": "login", "payload": {} }),
+    "Missing or invalid payload field in type login"
+)]
+fn test_input_data_from_json_value_errors(
+    #[case] value: serde_json::Value,
+    #[case] expected_error: &str,
+) {
+    let error = InputData::from_json_value(&value, "event", "payload").unwrap_err();
+    assert_eq!(error.to_string(), expected_error);
+}
+
+#[test]
+fn test_input_data_from_json_value_with_tag_type() {
+    let value = serde_json::json!({ "type": 42, "content": "{}" });
+
+    let input_data =
+        InputData::from_json_value_with_tag_type(&value, "type", TagType::Number, "content")
+            .unwrap();
+    assert_eq!(input_data.r#type, "42");
+}
+
+#[test]
+fn test_input_data_from_json_list() {
+    let value = serde_json::json!({ "type": "batch", "items": [{ "id": 1 }, "a"] });
+
+    let records =
+        InputData::from_json_list_with_tag_type(&value, "type", TagType::String, "items").unwrap();
+    assert_eq!(
+        records
+            .iter()
+            .map(|record| (record.r#type.as_str(), record.content.as_str()))
+            .collect::<Vec<_>>(),
+        [("batch", "{\"id\":1}"), ("batch", "\"a\"")]
+    );
+
+    let value = serde_json::json!({ "type": "batch", "items": "[]" });
+    let error = InputData::from_json_list_with_tag_type(&value, "type", TagType::String, "items")
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Missing or invalid items field in type batch"
+    );
+}
+
+#[test]
+fn test_input_data_from_json_pointers() {
+    let value = serde_json::json!({
+        "meta": { "kind": "login" },
+        "events": [{ "a/b": "{}" }],
+    });
+
+    let input_data =
+        InputData::from_json_pointers(&value, "/meta/kind", TagType::String, "/events/0/a~1b")
+            .unwrap();
+    assert_eq!(input_data.r#type, "login");
+    assert_eq!(input_data.content, "{}");
+
+    let error = InputData::from_json_pointers(&value, "/meta/kind", TagType::String, "/events/1")
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Missing or invalid /events/1 field in type login"
+    );
+}
+
+#[test]
+fn test_input_data_from_json_paths() {
+    let value = serde_json::json!({
+        "meta": { "kind": "login" },
+        "payload": { "events": [{ "body": "{}" }, { "body": "[]" }] },
+    });
+    let tag_path = JsonPath::parse("$.meta.kind").unwrap();
+
+    let input_data = InputData::from_json_paths(
+        &value,
+        &tag_path,
+        TagType::String,
+        &JsonPath::parse("$.payload.events[*].body").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(input_data.r#type, "login");
+    assert_eq!(input_data.content, "{}");
+
+    let error = InputData::from_json_paths(
+        &value,
+        &tag_path,
+        TagType::String,
+        &JsonPath::parse("$.payload.events[2].body").unwrap(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Missing or invalid $.payload.events[2].body field in type login"
+    );
+}
+
+#[test]
+fn test_parse_numeric_strings() {
+    let options = InferenceOptions {
+        parse_numeric_strings: true,
+        ..Default::default()
+    };
+    let value = serde_json::json!({
+        "userId": "123",
+        "amount": "29.99",
+        "name": "alice",
+        "score": "NaN",
+        "pair": ["1", "a"]
+    });
+
+    let inferred = infer_type_from_value_with_options(value.clone(), &options);
+    assert_eq!(
+        inferred.to_string(),
+        "{ amount: number, name: string, pair: [number, string], score: string, userId: number }"
+    );
+    assert_eq!(
+        infer_type_from_value(value).to_string(),
+        "{ amount: string, name: string, pair: [string, string], score: string, userId: string }"
+    );
+}
+
+#[test]
+fn test_emit_test_fixtures_with_numeric_strings() {
+    let input_data = vec![InputData {
+        r#type: "purchase".to_string(),
+        content: "{\"amount\":\"29.99\",\"count\":\"3\",\"pair\":[\"1\",\"a\"]}".to_string(),
+    }];
+    let options = GenerationOptions {
+        test_fixtures_import: Some("./output".to_string()),
+        inference: InferenceOptions {
+            parse_numeric_strings: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        generated.test_fixtures.unwrap(),
+        r#"import type { PurchaseContent } from "./output";
+
+export const examplePurchase: PurchaseContent = {
+  amount: 29.99,
+  count: 3,
+  pair: [1,"a"]
+};
+"#
+    );
+}
+
+#[test]
+fn test_null_as_optional() {
+    let options = InferenceOptions {
+        null_as_optional: true,
+        ..Default::default()
+    };
+    let merge = |options: &InferenceOptions| {
+        [
+            serde_json::json!({ "note": "a", "count": 1, "flag": true }),
+            serde_json::json!({ "note": null, "count": "2", "flag": null }),
+            serde_json::json!({ "note": "b", "count": null, "flag": false }),
+        ]
+        .into_iter()
+        .map(|value| infer_type_from_value_with_options(value, options))
+        .reduce(|type1, type2| merge_types_with_options(type1, type2, options))
+        .unwrap()
+    };
+
+    assert_eq!(
+        merge(&options).to_string(),
+        "{ count: string | number | null, flag?: boolean, note?: string }"
+    );
+    assert_eq!(
+        merge(&InferenceOptions::default()).to_string(),
+        "{ count: string | number | null, flag: boolean | null, note: string | null }"
+    );
+}
+
+#[test]
+fn test_emit_test_fixtures_with_null_as_optional() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"n\":null}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"n\":\"a\"}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        test_fixtures_import: Some("./output".to_string()),
+        inference: InferenceOptions {
+            null_as_optional: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        generated.test_fixtures.unwrap(),
+        r#"import type { LoginContent } from "./output";
+
+export const exampleLogin: LoginContent = {
+  n: "a"
+};
+
+export const exampleLoginMinimal: LoginContent = {};
+"#
+    );
+}
+
+#[test]
+fn test_prune_rare_properties() {
+    let mut input_data = (0..9)
+        .map(|i| InputData {
+            r#type: "login".to_string(),
+            content: format!("{{\"userId\":{i}}}"),
+        })
+        .collect::<Vec<_>>();
+    input_data.push(InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":9,\"debugId\":\"x\",\"meta\":{\"rare\":1}}".to_string(),
+    });
+    let options = GenerationOptions {
+        rare_property_threshold: Some(0.2),
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"// Pruned rare properties: debugId (10.0%), meta (10.0%)
+export type LoginContent = {
+  userId: number
+};
+
+export type Events = { type: "login", content: LoginContent };
+"#
+    );
+}
+
+#[test]
+fn test_strict_tuples() {
+    let input_data = vec![
+        InputData {
+            r#type: "move".to_string(),
+            content: "{\"position\":[1,2]}".to_string(),
+        },
+        InputData {
+            r#type: "move".to_string(),
+            content: "{\"position\":[1,2,3]}".to_string(),
+        },
+        InputData {
+            r#type: "move".to_string(),
+            content: "{\"position\":[4,5]}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        inference: InferenceOptions {
+            strict_tuples: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type MoveContent = {
+  position: [number, number] | [number, number, number]
+};
+
+export type Events = { type: "move", content: MoveContent };
+"#
+    );
+}
+
+#[test]
+fn test_merge_tuple_union_with_array() {
+    let options = InferenceOptions {
+        strict_tuples: true,
+        ..Default::default()
+    };
+    let tuple_union = merge_types_with_options(
+        InferredType::PrimitiveTuple(vec![PrimitiveType::Number]),
+        InferredType::PrimitiveTuple(vec![PrimitiveType::Number, PrimitiveType::String]),
+        &options,
+    );
+    assert_eq!(tuple_union.to_string(), "[number] | [number, string]");
+
+    let merged = merge_types_with_options(
+        tuple_union,
+        InferredType::Array(Box::new(InferredType::Primitive(PrimitiveType::Number))),
+        &options,
+    );
+    assert_eq!(
+        merged,
+        InferredType::Array(Box::new(InferredType::PrimitiveUnion(vec![
+            PrimitiveType::String,
+            PrimitiveType::Number,
+        ])))
+    );
+}
+
+#[test]
+fn test_output_format_info() {
+    assert_eq!(
+        OutputFormat::TypeScript.info(),
+        FormatInfo {
+            name: "typescript".to_string(),
+            description: "TypeScript type aliases for each event type",
+            extension: "ts",
+            feature: None,
+        }
+    );
+    assert_eq!(
+        OutputFormat::all_info().len(),
+        <OutputFormat as clap::ValueEnum>::value_variants().len()
+    );
+}
+
+#[test]
+fn test_emit_test_fixtures() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"tags\":[]}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":2,\"profile\":null,\"tags\":[\"x\"],\"note\":\"hi\"}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "1".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        emit_branded_ids: true,
+        test_fixtures_import: Some("./output".to_string()),
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        generated.test_fixtures.unwrap(),
+        r#"import type { LoginContent, UserId } from "./output";
+
+export const exampleLogin: LoginContent = {
+  note: "hi",
+  profile: {
+    name: "a"
+  },
+  tags: [],
+  userId: 1 as UserId
+};
+
+export const exampleLoginMinimal: LoginContent = {
+  profile: {
+    name: "a"
+  },
+  tags: [],
+  userId: 1 as UserId
+};
+"#
+    );
+}
+
+#[test]
+fn test_emit_pick_types() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1,\"timestamp\":2,\"note\":\"x\",\"device_name\":\"y\"}".to_string(),
+    }];
+    let options = GenerationOptions {
+        pick_types: vec![
+            "Login:userId,timestamp".parse().unwrap(),
+            PickSpec {
+                alias: Some("LoginSummary".to_string()),
+                ..PickSpec::from_str("login:userId").unwrap()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(ts_output.contains(
+        "export type LoginWithoutDeviceNameNote = Pick<LoginContent, \"userId\" | \"timestamp\">;\n\nexport type LoginSummary = Pick<LoginContent, \"userId\">;\n\nexport type Events ="
+    ));
+}
+
+#[rstest]
+#[case::unknown_type("Logout:userId", "Unknown type in pick types: Logout")]
+#[case::unknown_field("Login:userName", "LoginContent has no field userName")]
+fn test_emit_pick_types_errors(#[case] spec: &str, #[case] expected_error: &str) {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        pick_types: vec![spec.parse().unwrap()],
+        ..Default::default()
+    };
+
+    let error =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap_err();
+    assert_eq!(error.to_string(), expected_error);
+}
+
+#[test]
+fn test_merge_optional_types() {
+    let optional_number =
+        InferredType::Optional(Box::new(InferredType::Primitive(PrimitiveType::Number)));
+    assert_eq!(
+        merge_types(
+            optional_number.clone(),
+            InferredType::Primitive(PrimitiveType::Number)
+        ),
+        optional_number
+    );
+
+    let object = InferredType::Object(PropertyMap::new());
+    let merged = merge_types(
+        InferredType::Optional(Box::new(object.clone())),
+        InferredType::NullableObj(Box::new(object.clone())),
+    );
+    assert_eq!(
+        merged,
+        InferredType::Optional(Box::new(InferredType::NullableObj(Box::new(object))))
+    );
+    assert_eq!(merged.to_string(), "{} | null | undefined");
+    assert_eq!(
+        format_type_to_ts_string(InferredType::Array(Box::new(optional_number))),
+        "Array<number | undefined>"
+    );
+}
+
+#[rstest]
+#[case::primitive_inner(
+    InferredType::NullableObj(Box::new(InferredType::Primitive(PrimitiveType::String))),
+    InferredType::Primitive(PrimitiveType::Number),
+    InferredType::PrimitiveUnion(vec![PrimitiveType::String, PrimitiveType::Number, PrimitiveType::Null])
+)]
+#[case::same_primitive_inner(
+    InferredType::NullableObj(Box::new(InferredType::Primitive(PrimitiveType::String))),
+    InferredType::Primitive(PrimitiveType::String),
+    InferredType::PrimitiveUnion(vec![PrimitiveType::String, PrimitiveType::Null])
+)]
+#[case::object_and_primitive(
+    InferredType::NullableObj(Box::new(InferredType::Object(PropertyMap::new()))),
+    InferredType::Primitive(PrimitiveType::String),
+    InferredType::Any
+)]
+#[case::object_and_null(
+    InferredType::NullableObj(Box::new(InferredType::Object(PropertyMap::new()))),
+    InferredType::Primitive(PrimitiveType::Null),
+    InferredType::NullableObj(Box::new(InferredType::Object(PropertyMap::new())))
+)]
+#[case::tuple_and_null(
+    InferredType::PrimitiveTuple(vec![PrimitiveType::Number]),
+    InferredType::Primitive(PrimitiveType::Null),
+    InferredType::NullableObj(Box::new(InferredType::PrimitiveTuple(vec![PrimitiveType::Number])))
+)]
+fn test_merge_nullable_obj(
+    #[case] type1: InferredType,
+    #[case] type2: InferredType,
+    #[case] expected: InferredType,
+) {
+    assert_eq!(merge_types(type1.clone(), type2.clone()), expected);
+    assert_eq!(merge_types(type2, type1), expected);
+}
+
+#[test]
+fn test_nullable_object_with_primitive_becomes_any() {
+    let input_data = vec![
+        InputData {
+            r#type: "a".to_string(),
+            content: r#"{"value":null}"#.to_string(),
+        },
+        InputData {
+            r#type: "a".to_string(),
+            content: r#"{"value":{"id":1}}"#.to_string(),
+        },
+        InputData {
+            r#type: "a".to_string(),
+            content: r#"{"value":"text"}"#.to_string(),
+        },
+    ];
+    let output = generate_typescript_definitions(input_data, "Events").unwrap();
+    assert!(output.contains("  value: any\n"), "{output}");
+}
+
+#[test]
+fn test_generate_io_ts_codecs() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"tags\":[\"a\"],\"profile\":{\"name\":\"a\"}}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"tags\":[],\"profile\":null,\"note\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "{\"at\":1}".to_string(),
+        },
+    ];
+
+    let output = generate_io_ts_codecs(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import * as t from "io-ts";
+
+export const LoginContent = t.intersection([
+  t.type({
+    profile: t.union([t.type({
+      name: t.string,
+    }), t.null]),
+    tags: t.array(t.string),
+    userId: t.union([t.string, t.number]),
+  }),
+  t.partial({
+    note: t.string,
+  }),
+]);
+export type LoginContent = t.TypeOf<typeof LoginContent>;
+
+export const LogoutContent = t.type({
+  at: t.number,
+});
+export type LogoutContent = t.TypeOf<typeof LogoutContent>;
+
+export const Events = t.union([
+  t.type({ type: t.literal("login"), content: LoginContent }),
+  t.type({ type: t.literal("logout"), content: LogoutContent }),
+]);
+export type Events = t.TypeOf<typeof Events>;
+"#
+    );
+}
+
+#[test]
+fn test_recursive_types() {
+    let input_data = vec![
+        InputData {
+            r#type: "tree".to_string(),
+            content: "{\"id\":1,\"children\":[{\"id\":2,\"children\":[{\"id\":3,\"children\":[]}]}]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "file".to_string(),
+            content: "{\"path\":\"a\",\"entry\":{\"name\":\"a\",\"parent\":{\"name\":\"b\",\"parent\":null}}}"
+                .to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        recursive_types: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type FileContentEntry = {
+  name: string;
+  parent: FileContentEntry | null
+};
+
+export type FileContent = {
+  entry: FileContentEntry;
+  path: string
+};
+
+export type TreeContent = {
+  children: Array<TreeContent>;
+  id: number
+};
+
+export type Events = { type: "file", content: FileContent } | { type: "tree", content: TreeContent };
+"#
+    );
+}
+
+#[test]
+fn test_emit_test_fixtures_with_truncated_strings() {
+    let input_data = vec![InputData {
+        r#type: "upload".to_string(),
+        content: "{\"data\":\"aGVsbG8gd29ybGQ=\",\"tags\":[\"short\",\"ラベルが長い\"]}"
+            .to_string(),
+    }];
+    let options = GenerationOptions {
+        test_fixtures_import: Some("./output".to_string()),
+        inference: InferenceOptions {
+            example_string_limit: Some(5),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        generated.test_fixtures.unwrap(),
+        r#"import type { UploadContent } from "./output";
+
+export const exampleUpload: UploadContent = {
+  data: "aGVsb…",
+  tags: ["short","ラベルが長…"]
+};
+"#
+    );
+}
+
+#[test]
+fn test_emit_test_fixtures_with_satisfies() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        test_fixtures_import: Some("./output".to_string()),
+        fixtures_satisfies: true,
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        generated.test_fixtures.unwrap(),
+        r#"import type { LoginContent } from "./output";
+
+export const exampleLogin = {
+  userId: 1
+} satisfies LoginContent;
+"#
+    );
+}
+
+#[test]
+fn test_emit_typescript_satisfies_check() {
+    let input_data = || {
+        vec![
+            InputData {
+                r#type: "login".to_string(),
+                content: "{\"userId\":1}".to_string(),
+            },
+            InputData {
+                r#type: "login".to_string(),
+                content: "{\"userId\":2,\"note\":\"hi\"}".to_string(),
+            },
+        ]
+    };
+    let options = GenerationOptions {
+        satisfies_check_import: Some("./output".to_string()),
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data(), "Events", &options).unwrap();
+    assert!(generated.test_fixtures.is_none());
+    assert_eq!(
+        generated.satisfies_check.unwrap(),
+        r#"// Compile-time checks of the generated types. Do not import this module.
+import type { LoginContent } from "./output";
+
+export const exampleLogin = {
+  note: "hi",
+  userId: 1
+} satisfies LoginContent;
+
+export const exampleLoginMinimal = {
+  userId: 1
+} satisfies LoginContent;
+"#
+    );
+
+    let options = GenerationOptions {
+        test_fixtures_import: Some("./output".to_string()),
+        ..options
+    };
+    let generated = generate_typescript_files(input_data(), "Events", &options).unwrap();
+    assert_eq!(
+        generated.satisfies_check.unwrap(),
+        r#"// Compile-time checks of the generated types. Do not import this module.
+import type { LoginContent } from "./output";
+import { exampleLogin, exampleLoginMinimal } from "./Events.fixtures";
+
+exampleLogin satisfies LoginContent;
+exampleLoginMinimal satisfies LoginContent;
+"#
+    );
+}
+
+#[test]
+fn test_emit_test_fixtures_with_const_assertions() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"user\":{\"id\":1},\"tags\":[\"a\",\"b\"]}".to_string(),
+    }];
+    let options = GenerationOptions {
+        test_fixtures_import: Some("./output".to_string()),
+        fixtures_const_assertions: true,
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        generated.test_fixtures.unwrap(),
+        r#"import type { LoginContent } from "./output";
+
+export const exampleLogin: LoginContent = {
+  tags: ["a","b"] as const,
+  user: {
+    id: 1
+  } as const
+} as const;
+"#
+    );
+}
+
+#[test]
+fn test_emit_test_fixtures_with_type_assertions() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "user/deleted".to_string(),
+            content: "{}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        test_fixtures_import: Some("./output".to_string()),
+        fixtures_type_check: true,
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        generated.test_fixtures.unwrap(),
+        r#"import type { Events, LoginContent, UserDeletedContent } from "./output";
+
+export const exampleLogin: LoginContent = {
+  userId: 1
+};
+
+export const exampleUserDeleted: UserDeletedContent = {};
+
+const _typeCheck = {
+  login: exampleLogin,
+  "user/deleted": exampleUserDeleted,
+} satisfies Record<Events["type"], unknown>;
+"#
+    );
+}
+
+#[test]
+fn test_typedoc_module_and_tsdoc_tags() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        module_declaration: Some("events".to_string()),
+        typedoc_module: Some("events".to_string()),
+        tsdoc_tags: vec![TsdocTag::Public, TsdocTag::Sealed],
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"/** @module events */
+
+declare module "events" {
+  /** @public @sealed */
+  export type LoginContent = {
+    userId: number
+  };
+
+  /** @public @sealed */
+  export type Events = { type: "login", content: LoginContent };
+}
+"#
+    );
+}
+
+#[test]
+fn test_emit_match_helper() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "user/deleted".to_string(),
+            content: "{}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        emit_match_helper: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(
+        ts_output.ends_with(
+            r#"export type Events = { type: "login", content: LoginContent } | { type: "user/deleted", content: UserDeletedContent };
+
+export function matchEvent<T>(event: Events, handlers: {
+  login: (content: LoginContent) => T;
+  "user/deleted": (content: UserDeletedContent) => T;
+}): T {
+  switch (event.type) {
+    case "login":
+      return handlers.login(event.content);
+    case "user/deleted":
+      return handlers["user/deleted"](event.content);
+  }
+}
+"#
+        ),
+        "{ts_output}"
+    );
+}
+
+#[test]
+fn test_emit_fastify_schemas() {
+    let input_data = vec![InputData {
+        r#type: "user/deleted".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        emit_fastify_schemas: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    let (types, route_options) = ts_output
+        .split_once("\nexport const userDeletedRouteOptions: RouteShorthandOptions = ")
+        .unwrap();
+    assert_eq!(
+        types,
+        r#"import type { RouteShorthandOptions } from "fastify";
+
+export type UserDeletedContent = {
+  userId: number
+};
+
+export type Events = { type: "user/deleted", content: UserDeletedContent };
+"#
+    );
+    let route_options: serde_json::Value =
+        serde_json::from_str(route_options.strip_suffix(";\n").unwrap()).unwrap();
+    assert_eq!(
+        route_options,
+        serde_json::json!({
+            "schema": {
+                "body": {
+                    "type": "object",
+                    "properties": { "userId": { "type": "number" } },
+                    "required": ["userId"],
+                },
+                "response": {
+                    "200": {
+                        "type": "object",
+                        "properties": { "status": { "const": "ok" } },
+                        "required": ["status"],
+                    },
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_emit_fetch_types() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "{}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        fetch_helpers: Some(FetchHelpers {
+            endpoint: "https://example.com/events".to_string(),
+            method: HttpMethod::Put,
+        }),
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(
+        ts_output.ends_with(
+            r#"export type Events = { type: "login", content: LoginContent } | { type: "logout", content: LogoutContent };
+
+export async function sendLogin(content: LoginContent): Promise<Response> {
+  return fetch("https://example.com/events", {
+    method: "PUT",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ type: "login", content }),
+  });
+}
+
+export async function sendLogout(content: LogoutContent): Promise<Response> {
+  return fetch("https://example.com/events", {
+    method: "PUT",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ type: "logout", content }),
+  });
+}
+
+export async function dispatchEvent(event: Events): Promise<Response> {
+  switch (event.type) {
+    case "login":
+      return sendLogin(event.content);
+    case "logout":
+      return sendLogout(event.content);
+  }
+}
+"#
+        ),
+        "{ts_output}"
+    );
+}
+
+#[test]
+fn test_emit_class_declarations() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"note\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":2,\"at\":3}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "{\"user-id\":1}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        emit_class_declarations: true,
+        class_to_json: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export class LoginContent {
+  constructor(
+    public userId: number,
+    public at?: number,
+    public note?: string,
+  ) {}
+
+  toJSON() {
+    return { userId: this.userId, at: this.at, note: this.note };
+  }
+}
+
+export type LogoutContent = {
+  "user-id": number
+};
+
+export type Events = { type: "login", content: LoginContent } | { type: "logout", content: LogoutContent };
+"#
+    );
+}
+
+#[test]
+fn test_emit_mapped_types() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "ping".to_string(),
+            content: "1".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        emit_mapped_types: true,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        r#"export type LoginContent = {
+  userId: number
+};
+
+export type LoginContentUpdate = {
+  [K in keyof LoginContent]?: LoginContent[K]
+};
+
+export type PingContent = number;
+
+export type Events = { type: "login", content: LoginContent } | { type: "ping", content: PingContent };
+"#
+    );
+}
+
+#[rstest]
+#[case::prefix(
+    TypeNameCollisionStrategy::Prefix,
+    "UserCreatedContent1",
+    "UserCreatedContent2"
+)]
+#[case::suffix(
+    TypeNameCollisionStrategy::Suffix,
+    "UserCreatedContent_user$created",
+    "UserCreatedContent_user_created"
+)]
+fn test_type_name_collision_strategy(
+    #[case] strategy: TypeNameCollisionStrategy,
+    #[case] dashed_name: &str,
+    #[case] underscored_name: &str,
+) {
+    let input_data = vec![
+        InputData {
+            r#type: "user-created".to_string(),
+            content: "1".to_string(),
+        },
+        InputData {
+            r#type: "user_created".to_string(),
+            content: "true".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        type_name_collision_strategy: strategy,
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        ts_output,
+        format!(
+            r#"export type {dashed_name} = number;
+
+export type {underscored_name} = boolean;
+
+export type Events = {{ type: "user-created", content: {dashed_name} }} | {{ type: "user_created", content: {underscored_name} }};
+"#
+        )
+    );
+}
+
+#[test]
+fn test_helper_names_follow_type_name_collisions() {
+    let input_data = vec![
+        InputData {
+            r#type: "user-created".to_string(),
+            content: "{\"id\":1}".to_string(),
+        },
+        InputData {
+            r#type: "user_created".to_string(),
+            content: "{\"id\":2}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        emit_union_helpers: true,
+        emit_runtime_types: true,
+        fetch_helpers: Some(FetchHelpers {
+            endpoint: "/events".to_string(),
+            method: HttpMethod::Post,
+        }),
+        test_fixtures_import: Some("./output".to_string()),
+        emit_fastify_schemas: true,
+        type_name_collision_strategy: TypeNameCollisionStrategy::Prefix,
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    for route_name in ["userCreated1", "userCreated2"] {
+        assert!(
+            generated
+                .definitions
+                .contains(&format!("export const {route_name}RouteOptions: "))
+        );
+    }
+    for name in ["UserCreated1", "UserCreated2"] {
+        assert!(
+            generated
+                .definitions
+                .contains(&format!("export function is{name}("))
+        );
+        assert!(
+            generated
+                .definitions
+                .contains(&format!("export async function send{name}("))
+        );
+        assert!(
+            generated
+                .definitions
+                .contains(&format!("export const validate{name} ="))
+        );
+        assert!(
+            generated
+                .test_fixtures
+                .as_ref()
+                .unwrap()
+                .contains(&format!("export const example{name}: UserCreatedContent"))
+        );
+    }
+}
+
+#[test]
+fn test_type_name_collision_error() {
+    let input_data = vec![
+        InputData {
+            r#type: "user-created".to_string(),
+            content: "1".to_string(),
+        },
+        InputData {
+            r#type: "user_created".to_string(),
+            content: "1".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "1".to_string(),
+        },
+    ];
+
+    let error = generate_typescript_definitions_with_options(
+        input_data,
+        "Events",
+        &GenerationOptions::default(),
+    )
+    .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .ends_with("\n  UserCreatedContent: user-created, user_created"),
+        "{error}"
+    );
+}
+
+#[test]
+fn test_kotlin_type_name_collisions() {
+    let input_data = || {
+        vec![
+            InputData {
+                r#type: "c-d".to_string(),
+                content: "1".to_string(),
+            },
+            InputData {
+                r#type: "c_d".to_string(),
+                content: "true".to_string(),
+            },
+        ]
+    };
+
+    let error = generate_kotlin(input_data(), "Events").unwrap_err();
+    assert!(
+        error.to_string().ends_with("\n  CDContent: c-d, c_d"),
+        "{error}"
+    );
+
+    let options = GenerationOptions {
+        type_name_collision_strategy: TypeNameCollisionStrategy::Prefix,
+        ..Default::default()
+    };
+    let kotlin_output = generate_kotlin_with_options(input_data(), "Events", &options).unwrap();
+    assert_eq!(
+        kotlin_output,
+        r#"typealias CDContent1 = Double
+
+typealias CDContent2 = Boolean
+
+sealed class Events {
+    abstract val type: String
+
+    data class CDEvent1(val content: CDContent1) : Events() {
+        override val type get() = "c-d"
+    }
+
+    data class CDEvent2(val content: CDContent2) : Events() {
+        override val type get() = "c_d"
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn test_emit_json_schema_document() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1}".to_string(),
+        },
+        InputData {
+            r#type: "ping".to_string(),
+            content: "[1]".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        json_schema_id: Some("https://example.com/events.schema.json".to_string()),
+        ..Default::default()
+    };
+
+    let generated = generate_typescript_files(input_data, "Events", &options).unwrap();
+    let document: serde_json::Value =
+        serde_json::from_str(&generated.json_schema.unwrap()).unwrap();
+    assert_eq!(
+        document,
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "https://example.com/events.schema.json",
+            "$ref": "#/$defs/Events",
+            "$defs": {
+                "Events": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "type": { "const": "login" },
+                                "content": { "$ref": "#/$defs/LoginContent" },
+                            },
+                            "required": ["type", "content"],
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "type": { "const": "ping" },
+                                "content": { "$ref": "#/$defs/PingContent" },
+                            },
+                            "required": ["type", "content"],
+                        },
+                    ],
+                },
+                "LoginContent": {
+                    "type": "object",
+                    "properties": { "userId": { "type": "number" } },
+                    "required": ["userId"],
+                },
+                "PingContent": {
+                    "type": "array",
+                    "prefixItems": [{ "type": "number" }],
+                    "items": false,
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_property_docs_from_key() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1,\"isActive\":true}".to_string(),
+    }];
+    let options = GenerationOptions {
+        format: FormatOptions {
+            property_docs_from_key: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let ts_output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert!(ts_output.starts_with(
+        r#"export type LoginContent = {
+  /** Is active. */
+  isActive: boolean;
+  /** User id. */
+  userId: number
+};
+"#
+    ));
+}
+
+#[test]
+fn test_error_on_any() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"meta\":{\"a\":1},\"tags\":[1,{\"b\":2}]}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"meta\":\"x\",\"tags\":[]}".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        error_on_any: true,
+        ..Default::default()
+    };
+
+    let error =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap_err();
+    let error = error.downcast::<AnyTypeError>().unwrap();
+    assert_eq!(
+        error.locations,
+        vec![
+            ("login".to_string(), "$.meta".to_string()),
+            ("login".to_string(), "$.tags[]".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_generate_openrpc() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1,\"note\":null}".to_string(),
+    }];
+
+    let output = generate_openrpc(input_data, "Events").unwrap();
+    let document: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(
+        document,
+        serde_json::json!({
+            "openrpc": "1.3.2",
+            "info": { "title": "Events", "version": "1.0.0" },
+            "methods": [{
+                "name": "login",
+                "paramStructure": "by-name",
+                "params": [{
+                    "name": "content",
+                    "required": true,
+                    "schema": { "$ref": "#/components/schemas/LoginContent" },
+                }],
+            }],
+            "components": {
+                "schemas": {
+                    "LoginContent": {
+                        "type": "object",
+                        "properties": {
+                            "note": { "type": "null" },
+                            "userId": { "type": "number" },
+                        },
+                        "required": ["note", "userId"],
+                    },
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_generate_superstruct() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"tags\":[\"a\"],\"profile\":{\"name\":\"a\"}}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"tags\":[],\"profile\":null,\"note\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "{\"at\":1}".to_string(),
+        },
+    ];
+
+    let output = generate_superstruct(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import { type Infer, array, literal, nullable, number, object, optional, string, union } from "superstruct";
+
+export const LoginContent = object({
+  note: optional(string()),
+  profile: nullable(object({
+    name: string(),
+  })),
+  tags: array(string()),
+  userId: union([string(), number()]),
+});
+export type LoginContent = Infer<typeof LoginContent>;
+
+export const LogoutContent = object({
+  at: number(),
+});
+export type LogoutContent = Infer<typeof LogoutContent>;
+
+export const Events = union([
+  object({ type: literal("login"), content: LoginContent }),
+  object({ type: literal("logout"), content: LogoutContent }),
+]);
+export type Events = Infer<typeof Events>;
+"#
+    );
+}
+
+#[test]
+fn test_generate_runtypes() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"tags\":[\"a\"],\"profile\":{\"name\":\"a\"}}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"tags\":[],\"profile\":null,\"note\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "{\"at\":1}".to_string(),
+        },
+    ];
+
+    let output = generate_runtypes(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import { type Static, Array, Literal, Null, Number, Optional, Record, String, Union } from "runtypes";
+
+export const LoginContent = Record({
+  note: Optional(String),
+  profile: Union(Record({
+    name: String,
+  }), Null),
+  tags: Array(String),
+  userId: Union(String, Number),
+});
+export type LoginContent = Static<typeof LoginContent>;
+
+export const LogoutContent = Record({
+  at: Number,
+});
+export type LogoutContent = Static<typeof LogoutContent>;
+
+export const Events = Union(
+  Record({ type: Literal("login"), content: LoginContent }),
+  Record({ type: Literal("logout"), content: LogoutContent }),
+);
+export type Events = Static<typeof Events>;
+"#
+    );
+}
+
+#[test]
+fn test_generate_valibot() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"flags\":[true,null]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"profile\":null,\"flags\":[false,null],\"note\":\"x\"}"
+                .to_string(),
+        },
+    ];
+
+    let output = generate_valibot(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import * as v from "valibot";
+
+export const LoginContent = v.object({
+  flags: v.tuple([v.boolean(), v.null_()]),
+  note: v.optional(v.string()),
+  profile: v.nullable(v.object({
+    name: v.string(),
+  })),
+  userId: v.union([v.string(), v.number()]),
+});
+export type LoginContent = v.InferOutput<typeof LoginContent>;
+
+export const Events = v.object({ type: v.literal("login"), content: LoginContent });
+export type Events = v.InferOutput<typeof Events>;
+"#
+    );
+}
+
+#[test]
+fn test_generate_pydantic() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"pair\":[\"a\",1],\"class\":\"x\"}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"profile\":null,\"pair\":[\"b\",2],\"class\":\"y\",\"user-agent\":\"z\"}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1]".to_string(),
+        },
+    ];
+
+    let output = generate_pydantic(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"from typing import Annotated, Literal, Union
+from pydantic import BaseModel, Field
+
+
+class LoginContentProfile(BaseModel):
+    name: str
+
+
+class LoginContent(BaseModel):
+    class_: str = Field(alias="class")
+    pair: tuple[str, float]
+    profile: LoginContentProfile | None
+    user_agent: str | None = Field(default=None, alias="user-agent")
+    userId: str | float
+
+
+class LoginEvent(BaseModel):
+    type: Literal["login"]
+    content: LoginContent
+
+
+LogoutContent = tuple[float]
+
+
+class LogoutEvent(BaseModel):
+    type: Literal["logout"]
+    content: LogoutContent
+
+
+Events = Annotated[Union[LoginEvent, LogoutEvent], Field(discriminator="type")]
+"#
+    );
+}
+
+#[test]
+fn test_generate_kotlin() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"pair\":[\"a\",1],\"class\":\"x\"}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"profile\":null,\"pair\":[\"b\",2],\"class\":\"y\",\"note\":\"z\"}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1,2,3]".to_string(),
+        },
+    ];
+
+    let output = generate_kotlin(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"data class LoginContentProfile(
+    val name: String,
+)
+
+sealed class LoginContentUserId {
+    data class StringValue(val value: String) : LoginContentUserId()
+    data class DoubleValue(val value: Double) : LoginContentUserId()
+}
+
+data class LoginContent(
+    val `class`: String,
+    val note: String? = null,
+    val pair: Pair<String, Double>,
+    val profile: LoginContentProfile?,
+    val userId: LoginContentUserId,
+)
+
+typealias LogoutContent = Triple<Double, Double, Double>
+
+sealed class Events {
+    abstract val type: String
+
+    data class LoginEvent(val content: LoginContent) : Events() {
+        override val type get() = "login"
+    }
+
+    data class LogoutEvent(val content: LogoutContent) : Events() {
+        override val type get() = "logout"
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn test_generate_kotlin_rejects_non_numeric_number_tags() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "1".to_string(),
+    }];
+    let options = GenerationOptions {
+        tag_type: TagType::Number,
+        ..Default::default()
+    };
+
+    let error = generate_kotlin_with_options(input_data, "Events", &options).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Event type \"login\" is not a number tag"
+    );
+}
+
+#[test]
+fn test_generate_swift() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content:
+                "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"class\":\"x\",\"user-agent\":null}"
+                    .to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"profile\":null,\"class\":\"y\",\"user-agent\":null}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "user/deleted".to_string(),
+            content: "[1]".to_string(),
+        },
+    ];
+
+    let output = generate_swift(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import Foundation
+
+struct LoginContentProfile: Codable {
+    var name: String
+}
+
+enum LoginContentUserId: Codable {
+    case string(String)
+    case double(Double)
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if let value = try? container.decode(String.self) {
+            self = .string(value)
+        } else if let value = try? container.decode(Double.self) {
+            self = .double(value)
+        } else {
+            throw DecodingError.typeMismatch(LoginContentUserId.self, DecodingError.Context(codingPath: decoder.codingPath, debugDescription: "No member of LoginContentUserId matched"))
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .string(let value): try container.encode(value)
+        case .double(let value): try container.encode(value)
+        }
+    }
+}
+
+struct LoginContent: Codable {
+    var `class`: String
+    var profile: LoginContentProfile?
+    var user_agent: Never? // Always null in the observed data.
+    var userId: LoginContentUserId
+
+    enum CodingKeys: String, CodingKey {
+        case `class`
+        case profile
+        case user_agent = "user-agent"
+        case userId
+    }
+}
+
+typealias UserDeletedContent = [Double]
+
+enum Events: Codable {
+    case login(LoginContent)
+    case userDeleted(UserDeletedContent)
+
+    private enum CodingKeys: String, CodingKey {
+        case type
+        case content
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        switch try container.decode(String.self, forKey: .type) {
+        case "login":
+            self = .login(try container.decode(LoginContent.self, forKey: .content))
+        case "user/deleted":
+            self = .userDeleted(try container.decode(UserDeletedContent.self, forKey: .content))
+        case let type:
+            throw DecodingError.dataCorruptedError(forKey: .type, in: container, debugDescription: "Unknown event type \(type)")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: CodingKeys.self)
+        switch self {
+        case .login(let content):
+            try container.encode("login", forKey: .type)
+            try container.encode(content, forKey: .content)
+        case .userDeleted(let content):
+            try container.encode("user/deleted", forKey: .type)
+            try container.encode(content, forKey: .content)
+        }
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn test_generate_swift_rejects_non_numeric_number_tags() {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "1".to_string(),
+    }];
+    let options = GenerationOptions {
+        tag_type: TagType::Number,
+        ..Default::default()
+    };
+
+    let error = generate_swift_with_options(input_data, "Events", &options).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Event type \"login\" is not a number tag"
+    );
+}
+
+#[test]
+fn test_generate_csharp() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"class\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"profile\":null,\"class\":\"y\",\"user-agent\":\"z\"}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[\"a\",\"b\"]".to_string(),
+        },
+    ];
+
+    let output = generate_csharp(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"#nullable enable
+
+using System.Collections.Generic;
+using System.Text.Json.Serialization;
+
+public record LoginContentProfile(
+    [property: JsonPropertyName("name")] string Name);
+
+public record LoginContent(
+    [property: JsonPropertyName("class")] string Class,
+    [property: JsonPropertyName("profile")] LoginContentProfile? Profile,
+    [property: JsonPropertyName("userId")] object /* string | number */ UserId,
+    [property: JsonPropertyName("user-agent")] string? UserAgent = null);
+
+[JsonPolymorphic(TypeDiscriminatorPropertyName = "type")]
+[JsonDerivedType(typeof(LoginEvent), "login")]
+[JsonDerivedType(typeof(LogoutEvent), "logout")]
+public abstract record Events;
+
+public sealed record LoginEvent([property: JsonPropertyName("content")] LoginContent Content) : Events;
+
+public sealed record LogoutEvent([property: JsonPropertyName("content")] IReadOnlyList<string> Content) : Events;
+"#
+    );
+}
+
+#[test]
+fn test_generate_cerberus() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"type\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"type\":\"y\",\"tags\":[\"z\",\"w\"],\"options\":{}}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1,\"a\"]".to_string(),
+        },
+    ];
+
+    let output = generate_cerberus(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"LOGIN_CONTENT_SCHEMA = {
+    "options": {"type": "dict", "schema": {}, "required": False},
+    "profile": {
+        "type": "dict",
+        "schema": {"name": {"type": "string", "required": True}},
+        "nullable": True,
+        "required": True,
+    },
+    "tags": {
+        "type": "list",
+        "items": [{"type": "string"}, {"type": "string"}],
+        "required": False,
+    },
+    "type": {"type": "string", "required": True},
+    "user_id": {"anyof": [{"type": "string"}, {"type": "number"}], "required": True},
+}
+
+
+# Maps each event type to the schema of its events.
+EVENTS_SCHEMAS = {
+    "login": {
+        "type": {"type": "string", "allowed": ["login"], "required": True},
+        "content": {"type": "dict", "schema": LOGIN_CONTENT_SCHEMA, "required": True},
+    },
+    "logout": {
+        "type": {"type": "string", "allowed": ["logout"], "required": True},
+        "content": {
+            "type": "list",
+            "items": [{"type": "number"}, {"type": "string"}],
+            "required": True,
+        },
+    },
+}
+"#
+    );
+}
+
+#[test]
+fn test_generate_mongoose() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"type\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"type\":\"y\",\"tags\":[\"z\"],\"items\":[{\"id\":1}]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "\"bye\"".to_string(),
+        },
+    ];
+
+    let output = generate_mongoose(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import { type Document, Schema } from "mongoose";
+
+export type LoginContent = {
+  items?: Array<{
+  id: number
+}>;
+  profile: {
+  name: string
+} | null;
+  tags?: [string];
+  type: string;
+  user_id: string | number
+};
+export const loginContentSchema = new Schema(
+  {
+    items: {
+      type: [
+        {
+          type: new Schema(
+            {
+              id: { type: Number, required: true },
+            },
+            { _id: false },
+          ),
+        },
+      ],
+      required: false,
+    },
+    profile: {
+      type: new Schema(
+        {
+          name: { type: String, required: true },
+        },
+        { _id: false },
+      ),
+      required: false,
+    },
+    tags: { type: [{ type: String }], required: false },
+    type: { type: String, required: true },
+    user_id: { type: Schema.Types.Mixed, required: true },
+  },
+  { _id: false },
+);
+export type LoginContentDocument = LoginContent & Document;
+
+// The 'content' field contained invalid JSON: "bye"
+export type LogoutContent = string;
+
+export type Events = { type: "login", content: LoginContent } | { type: "logout", content: LogoutContent };
+export const eventsSchema = new Schema({}, { discriminatorKey: "type" });
+eventsSchema.discriminator(
+  "login",
+  new Schema({
+    content: { type: loginContentSchema, required: true },
+  }),
+);
+eventsSchema.discriminator(
+  "logout",
+  new Schema({
+    content: { type: String, required: true },
+  }),
+);
+export type EventsDocument = Events & Document;
+"#
+    );
+}
+
+#[test]
+fn test_generate_clojure_spec() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"first name\":\"x\"}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"tags\":[\"z\",\"w\"]}".to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1,\"a\"]".to_string(),
+        },
+    ];
+
+    let output = generate_clojure_spec(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"(ns events.spec
+  (:require [clojure.spec.alpha :as s]))
+
+(s/def :events.spec.login-content-profile/name string?)
+(s/def ::login-content-profile (s/keys :req-un [:events.spec.login-content-profile/name]))
+
+;; The key "first name" cannot be written as a keyword.
+(s/def :events.spec.login-content/profile (s/nilable ::login-content-profile))
+(s/def :events.spec.login-content/tags (s/tuple string? string?))
+(s/def :events.spec.login-content/user_id (s/or :string string? :number number?))
+(s/def ::login-content (s/keys :req-un [:events.spec.login-content/profile :events.spec.login-content/user_id] :opt-un [:events.spec.login-content/tags]))
+
+(s/def ::logout-content (s/tuple number? string?))
+
+(defmulti events-type :type)
+
+(s/def :events.spec.login-event/content ::login-content)
+(defmethod events-type "login" [_]
+  (s/keys :req-un [:events.spec.login-event/content]))
+
+(s/def :events.spec.logout-event/content ::logout-content)
+(defmethod events-type "logout" [_]
+  (s/keys :req-un [:events.spec.logout-event/content]))
+
+(s/def ::events (s/multi-spec events-type :type))
+"#
+    );
+}
+
+#[test]
+fn test_generate_elm() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"type\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"type\":\"y\",\"tags\":[\"z\",\"w\"]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1,\"a\"]".to_string(),
+        },
+    ];
+
+    let output = generate_elm(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"module Events exposing (..)
+
+import Json.Decode as Decode exposing (Decoder)
+
+
+type alias LoginContentProfile =
+    { name : String
+    }
+
+
+loginContentProfileDecoder : Decoder LoginContentProfile
+loginContentProfileDecoder =
+    Decode.succeed LoginContentProfile
+        |> andMap (Decode.field "name" Decode.string)
+
+
+type LoginContentUserId
+    = LoginContentUserIdString String
+    | LoginContentUserIdFloat Float
+
+
+loginContentUserIdDecoder : Decoder LoginContentUserId
+loginContentUserIdDecoder =
+    Decode.oneOf
+        [ Decode.map LoginContentUserIdString Decode.string
+        , Decode.map LoginContentUserIdFloat Decode.float
+        ]
+
+
+type alias LoginContent =
+    { profile : Maybe LoginContentProfile
+    , tags : Maybe ( String, String )
+    , type_ : String
+    , userId : LoginContentUserId
+    }
+
+
+loginContentDecoder : Decoder LoginContent
+loginContentDecoder =
+    Decode.succeed LoginContent
+        |> andMap (Decode.field "profile" (Decode.nullable loginContentProfileDecoder))
+        |> andMap (Decode.oneOf [ Decode.field "tags" (Decode.nullable (Decode.map2 Tuple.pair (Decode.index 0 Decode.string) (Decode.index 1 Decode.string))), Decode.succeed Nothing ])
+        |> andMap (Decode.field "type" Decode.string)
+        |> andMap (Decode.field "user_id" loginContentUserIdDecoder)
+
+
+type alias LogoutContent =
+    ( Float, String )
+
+
+logoutContentDecoder : Decoder LogoutContent
+logoutContentDecoder =
+    Decode.map2 Tuple.pair (Decode.index 0 Decode.float) (Decode.index 1 Decode.string)
+
+
+type Events
+    = LoginEvent LoginContent
+    | LogoutEvent LogoutContent
+
+
+eventsDecoder : Decoder Events
+eventsDecoder =
+    Decode.field "type" Decode.string
+        |> Decode.andThen
+            (\tag ->
+                case tag of
+                    "login" ->
+                        Decode.map LoginEvent (Decode.field "content" loginContentDecoder)
+
+                    "logout" ->
+                        Decode.map LogoutEvent (Decode.field "content" logoutContentDecoder)
+
+                    _ ->
+                        Decode.fail ("Unknown type: " ++ tag)
+            )
+
+
+andMap : Decoder a -> Decoder (a -> b) -> Decoder b
+andMap =
+    Decode.map2 (|>)
+"#
+    );
+}
+
+#[test]
+fn test_generate_haskell() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"type\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"type\":\"y\",\"tags\":[\"z\",\"w\"]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1,\"a\"]".to_string(),
+        },
+    ];
+
+    let output = generate_haskell(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"{-# LANGUAGE DeriveGeneric #-}
+
+module Events where
+
+import Data.Aeson
+import Data.Maybe (fromMaybe)
+import Data.Text (Text)
+import GHC.Generics (Generic)
+
+data LoginContentProfile = LoginContentProfile
+  { loginContentProfileName :: Text
+  }
+  deriving (Show, Eq, Generic)
+
+loginContentProfileJsonOptions :: Options
+loginContentProfileJsonOptions = recordOptions [("loginContentProfileName", "name")]
+
+instance FromJSON LoginContentProfile where
+  parseJSON = genericParseJSON loginContentProfileJsonOptions
+
+instance ToJSON LoginContentProfile where
+  toJSON = genericToJSON loginContentProfileJsonOptions
+
+data LoginContentUserId
+  = LoginContentUserIdString Text
+  | LoginContentUserIdNumber Double
+  deriving (Show, Eq, Generic)
+
+instance FromJSON LoginContentUserId where
+  parseJSON = genericParseJSON untaggedOptions
+
+instance ToJSON LoginContentUserId where
+  toJSON = genericToJSON untaggedOptions
+
+data LoginContent = LoginContent
+  { loginContentProfile :: Maybe LoginContentProfile
+  , loginContentTags :: Maybe (Text, Text)
+  , loginContentType :: Text
+  , loginContentUserId :: LoginContentUserId
+  }
+  deriving (Show, Eq, Generic)
+
+loginContentJsonOptions :: Options
+loginContentJsonOptions = recordOptions [("loginContentProfile", "profile"), ("loginContentTags", "tags"), ("loginContentType", "type"), ("loginContentUserId", "user_id")]
+
+instance FromJSON LoginContent where
+  parseJSON = genericParseJSON loginContentJsonOptions
+
+instance ToJSON LoginContent where
+  toJSON = genericToJSON loginContentJsonOptions
+
+type LogoutContent = (Double, Text)
+
+data Events
+  = LoginEvent LoginContent
+  | LogoutEvent LogoutContent
+  deriving (Show, Eq, Generic)
+
+eventsJsonOptions :: Options
+eventsJsonOptions =
+  defaultOptions
+    { constructorTagModifier = \constructor -> fromMaybe constructor (lookup constructor [("LoginEvent", "login"), ("LogoutEvent", "logout")])
+    , sumEncoding = TaggedObject {tagFieldName = "type", contentsFieldName = "content"}
+    }
+
+instance FromJSON Events where
+  parseJSON = genericParseJSON eventsJsonOptions
+
+instance ToJSON Events where
+  toJSON = genericToJSON eventsJsonOptions
+
+-- | Options for a record whose fields are mapped to the given JSON keys.
+recordOptions :: [(String, String)] -> Options
+recordOptions keys =
+  defaultOptions
+    { fieldLabelModifier = \field -> fromMaybe field (lookup field keys)
+    , omitNothingFields = True
+    }
+
+untaggedOptions :: Options
+untaggedOptions = defaultOptions {sumEncoding = UntaggedValue}
+"#
+    );
+}
+
+#[test]
+fn test_generate_java() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"class\":\"x\"}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"class\":\"y\",\"tags\":[\"z\"]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "true".to_string(),
+        },
+    ];
+
+    let output = generate_java(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import com.fasterxml.jackson.annotation.JsonInclude;
+import com.fasterxml.jackson.annotation.JsonProperty;
+import com.fasterxml.jackson.annotation.JsonSubTypes;
+import com.fasterxml.jackson.annotation.JsonTypeInfo;
+import java.util.List;
+
+@JsonTypeInfo(use = JsonTypeInfo.Id.NAME, include = JsonTypeInfo.As.PROPERTY, property = "type")
+@JsonSubTypes({
+    @JsonSubTypes.Type(value = Events.LoginEvent.class, name = "login"),
+    @JsonSubTypes.Type(value = Events.LogoutEvent.class, name = "logout")
+})
+public abstract class Events {
+    public static class LoginContentProfile {
+        @JsonProperty("name")
+        public String name;
+    }
+
+    public static class LoginContent {
+        @JsonProperty("class")
+        public String class_;
+
+        @JsonProperty("profile")
+        public LoginContentProfile profile;
+
+        @JsonProperty("tags")
+        @JsonInclude(JsonInclude.Include.NON_NULL)
+        public List<String> tags;
+
+        @JsonProperty("user_id")
+        public Object userId; // string | number
+    }
+
+    public static final class LoginEvent extends Events {
+        @JsonProperty("content")
+        public LoginContent content;
+    }
+
+    public static final class LogoutEvent extends Events {
+        @JsonProperty("content")
+        public Boolean content;
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn test_generate_jtd() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"pair\":[1,2]}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"pair\":[3,4],\"tags\":[\"z\",\"w\"]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "true".to_string(),
+        },
+    ];
+
+    let output = generate_jtd(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"{
+  "definitions": {
+    "LoginContent": {
+      "optionalProperties": {
+        "tags": {
+          "elements": {
+            "type": "string"
+          }
+        }
+      },
+      "properties": {
+        "pair": {
+          "elements": {
+            "type": "float64"
+          }
+        },
+        "profile": {
+          "nullable": true,
+          "properties": {
+            "name": {
+              "type": "string"
+            }
+          }
+        },
+        "user_id": {
+          "metadata": {
+            "description": "string | float64"
+          }
+        }
+      }
+    },
+    "LogoutContent": {
+      "type": "boolean"
+    }
+  },
+  "discriminator": "type",
+  "mapping": {
+    "login": {
+      "properties": {
+        "content": {
+          "ref": "LoginContent"
+        }
+      }
+    },
+    "logout": {
+      "properties": {
+        "content": {
+          "ref": "LogoutContent"
+        }
+      }
+    }
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn test_generate_typebox() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"tags\":[]}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"profile\":null,\"tags\":[\"x\"],\"note\":\"x\"}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "{\"at\":1}".to_string(),
+        },
+    ];
+
+    let output = generate_typebox(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import { type Static, Type } from "@sinclair/typebox";
+
+export const LoginContent = Type.Object({
+  note: Type.Optional(Type.String()),
+  profile: Type.Union([Type.Object({
+    name: Type.String(),
+  }), Type.Null()]),
+  tags: Type.Array(Type.String()),
+  userId: Type.Union([Type.String(), Type.Number()]),
+});
+export type LoginContent = Static<typeof LoginContent>;
+
+export const LogoutContent = Type.Object({
+  at: Type.Number(),
+});
+export type LogoutContent = Static<typeof LogoutContent>;
+
+export const Events = Type.Union([
+  Type.Object({ type: Type.Literal("login"), content: LoginContent }),
+  Type.Object({ type: Type.Literal("logout"), content: LogoutContent }),
+]);
+export type Events = Static<typeof Events>;
+"#
+    );
+}
+
+#[test]
+fn test_generate_typeorm() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"id\":\"x\",\"score\":1.5}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"id\":\"y\",\"score\":2,\"tags\":[\"z\"],\"admin\":false}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1,\"a\"]".to_string(),
+        },
+    ];
+
+    let output = generate_typeorm(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import { Column, Entity, PrimaryGeneratedColumn } from "typeorm";
+
+@Entity()
+export class LoginContent {
+  @PrimaryGeneratedColumn()
+  _id!: number;
+
+  @Column("boolean", { nullable: true })
+  admin?: boolean;
+
+  @Column()
+  id!: string;
+
+  @Column("json", { nullable: true })
+  profile!: {
+  name: string
+} | null;
+
+  @Column("float")
+  score!: number;
+
+  @Column("json", { nullable: true })
+  tags?: [string];
+
+  @Column("json")
+  user_id!: string | number;
+}
+
+@Entity()
+export class LogoutContent {
+  @PrimaryGeneratedColumn()
+  id!: number;
+
+  @Column("json")
+  content!: [number, string];
+}
+
+export type Events = { type: "login", content: LoginContent } | { type: "logout", content: LogoutContent };
+
+export const eventsEntities = [LoginContent, LogoutContent];
+"#
+    );
+}
+
+#[test]
+fn test_generate_django() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":1,\"profile\":{\"name\":\"a\"},\"id\":\"x\",\"is-admin\":true}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"user_id\":\"2\",\"profile\":null,\"id\":\"y\",\"is-admin\":false,\"score\":1.5,\"tags\":[\"z\"]}"
+                .to_string(),
+        },
+        InputData {
+            r#type: "logout".to_string(),
+            content: "[1,\"a\"]".to_string(),
+        },
+    ];
+
+    let output = generate_django(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"from django.db import models
+
+
+class LoginContent(models.Model):
+    field_id = models.CharField(max_length=255)
+    is_admin = models.BooleanField(db_column="is-admin")
+    profile = models.JSONField(null=True, blank=True)
+    score = models.FloatField(null=True, blank=True)
+    tags = models.JSONField(null=True, blank=True)
+    user_id = models.JSONField()
+
+
+class LogoutContent(models.Model):
+    content = models.JSONField()
+
+
+# Maps each event type to the model of its content.
+EVENTS_MODELS = {
+    "login": LoginContent,
+    "logout": LogoutContent,
+}
+"#
+    );
+}
+
+#[test]
+fn test_generate_effect_schema() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"profile\":{\"name\":\"a\"},\"pair\":[1,\"a\"]}".to_string(),
+        },
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":\"2\",\"profile\":null,\"pair\":[2,\"b\"],\"note\":\"x\"}"
+                .to_string(),
+        },
+    ];
+
+    let output = generate_effect_schema(input_data, "Events").unwrap();
+    assert_eq!(
+        output,
+        r#"import { Schema } from "effect";
+
+export const LoginContent = Schema.Struct({
+  note: Schema.optional(Schema.String),
+  pair: Schema.Tuple(Schema.Number, Schema.String),
+  profile: Schema.NullOr(Schema.Struct({
+    name: Schema.String,
+  })),
+  userId: Schema.Union(Schema.String, Schema.Number),
+});
+export type LoginContent = typeof LoginContent.Type;
+
+export const Events = Schema.Struct({ type: Schema.Literal("login"), content: LoginContent });
+export type Events = typeof Events.Type;
+"#
+    );
+}
+
+#[test]
+fn test_compress_output() {
+    let input_data = vec![
+        InputData {
+            r#type: "login".to_string(),
+            content: "{\"userId\":1,\"timestamp\":2}".to_string(),
+        },
+        InputData {
+            r#type: "log out".to_string(),
+            content: "not json".to_string(),
+        },
+    ];
+    let options = GenerationOptions {
+        compress_output: true,
+        ..Default::default()
+    };
+
+    let output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(
+        output,
+        "export type LogOutContent=string;export type LoginContent={timestamp:number;userId:number};export type Events={type:\"log out\",content:LogOutContent}|{type:\"login\",content:LoginContent};"
+    );
+}
+
+#[rstest]
+#[case::module(
+    None,
+    r#"import typia from "typia";
+
+export type LoginContent = {
+  userId: number
+};
+
+export type Events = { type: "login", content: LoginContent };
+
+export const validateLogin = typia.createValidate<LoginContent>();
+"#
+)]
+#[case::ambient(
+    Some("events".to_string()),
+    r#"declare module "events" {
+  export type LoginContent = {
+    userId: number
+  };
+
+  export type Events = { type: "login", content: LoginContent };
+
+  export const validateLogin: (input: unknown) => import("typia").IValidation<LoginContent>;
+}
+"#
+)]
+fn test_emit_runtime_types(#[case] module_declaration: Option<String>, #[case] expected: &str) {
+    let input_data = vec![InputData {
+        r#type: "login".to_string(),
+        content: "{\"userId\":1}".to_string(),
+    }];
+    let options = GenerationOptions {
+        emit_runtime_types: true,
+        module_declaration,
+        ..Default::default()
+    };
+
+    let output =
+        generate_typescript_definitions_with_options(input_data, "Events", &options).unwrap();
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_generate_split_typescript_files() {
+    let input_data = vec![
+        InputData {
+            r#type: "user/created".to_string(),
+            content: "{\"id\":1}".to_string(),
+        },
+        InputData {
+            r#type: "user/deleted".to_string(),
+            content: "{\"id\":2}".to_string(),
+        },
+        InputData {
+            r#type: "product/created".to_string(),
+            content: "{\"sku\":\"a\"}".to_string(),
+        },
+    ];
+
+    let generated = generate_split_typescript_files(
+        input_data,
+        "Events",
+        "/",
+        1,
+        &GenerationOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        generated.definitions,
+        r#"import type { ProductEvents } from "./product";
+import type { UserEvents } from "./user";
+
+export type { ProductEvents, UserEvents };
+export type Events = ProductEvents | UserEvents;
+"#
+    );
+    assert_eq!(
+        generated.modules.keys().collect::<Vec<_>>(),
+        ["product", "user"]
+    );
+    assert!(
+        generated.modules["user"].ends_with(
+            "export type UserEvents = { type: \"user/created\", content: UserCreatedContent } | { type: \"user/deleted\", content: UserDeletedContent };\n"
+        ),
+        "{}",
+        generated.modules["user"]
+    );
+}
+
+#[test]
+fn test_generate_split_typescript_files_with_separator() {
+    let input_data = [
+        "com.example.users.created",
+        "com.example.users.deleted",
+        "com.other",
+    ]
+    .into_iter()
+    .map(|r#type| InputData {
+        r#type: r#type.to_string(),
+        content: "{}".to_string(),
+    })
+    .collect();
+
+    let generated = generate_split_typescript_files(
+        input_data,
+        "Events",
+        ".",
+        3,
+        &GenerationOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        generated.modules.keys().collect::<Vec<_>>(),
+        ["com-example-users", "com-other"]
+    );
+    assert!(
+        generated
+            .definitions
+            .ends_with("export type Events = ComExampleUsersEvents | ComOtherEvents;\n"),
+        "{}",
+        generated.definitions
+    );
+}
+
+#[test]
+fn test_merge_array_with_object() {
+    let array = InferredType::Array(Box::new(InferredType::Primitive(PrimitiveType::Number)));
+    let object = infer_type_from_value(serde_json::json!({ "id": 1 }));
+
+    let merged = merge_types(object.clone(), array.clone());
+    assert_eq!(
+        merged,
+        InferredType::ComplexUnion(vec![array.clone(), object.clone()])
+    );
+    assert_eq!(
+        format_type_to_ts_string(merged.clone()),
+        "Array<number> | {\n  id: number\n}"
+    );
+
+    // Later values are merged into the member of the same kind.
+    let merged = merge_types(
+        merged,
+        infer_type_from_value(serde_json::json!({ "name": "a" })),
+    );
+    assert_eq!(
+        merged.to_string(),
+        "number[] | { id?: number, name?: string }"
+    );
+    assert_eq!(
+        merge_types(merged, InferredType::Primitive(PrimitiveType::String)),
+        InferredType::Any
+    );
+}
+
+#[test]
+fn test_merge_tuple_with_object() {
+    let merge_both_ways = |a: serde_json::Value, b: serde_json::Value| {
+        let (a, b) = (infer_type_from_value(a), infer_type_from_value(b));
+        let merged = merge_types(a.clone(), b.clone());
+        assert_eq!(merged, merge_types(b, a));
+        merged.to_string()
+    };
+
+    assert_eq!(
+        merge_both_ways(
+            serde_json::json!({ "w": {} }),
+            serde_json::json!({ "w": [] })
+        ),
+        "{ w: [] | {} }"
+    );
+    assert_eq!(
+        merge_both_ways(serde_json::json!([1, "s"]), serde_json::json!({ "id": 1 })),
+        "(string | number)[] | { id: number }"
+    );
+    // The empty tuple becomes an array once an array is merged into the union.
+    assert_eq!(
+        merge_types(
+            merge_types(
+                infer_type_from_value(serde_json::json!([])),
+                infer_type_from_value(serde_json::json!({ "id": 1 }))
+            ),
+            infer_type_from_value(serde_json::json!([1, 2]))
+        )
+        .to_string(),
+        "number[] | { id: number }"
+    );
+
+    // A tuple merged into a union with an array gives the same type as merging it into the
+    // array first.
+    let tuple = infer_type_from_value(serde_json::json!([1, "s"]));
+    let object = infer_type_from_value(serde_json::json!({ "id": 1 }));
+    let array = InferredType::Array(Box::new(InferredType::Primitive(PrimitiveType::Number)));
+    assert_eq!(
+        merge_types(merge_types(array.clone(), object.clone()), tuple.clone()),
+        merge_types(merge_types(array, tuple), object)
+    );
+}
+
+#[test]
+fn test_display_type_tree() {
+    let mut inferred_type = merge_types(
+        infer_type_from_value(serde_json::json!({
+            "userId": 1,
+            "name": "a",
+            "tags": [{ "id": 1 }],
+            "meta": {},
+        })),
+        infer_type_from_value(serde_json::json!({ "userId": 2, "tags": [], "meta": null })),
+    );
+    assert_eq!(
+        display_type_tree(&inferred_type, 0),
+        "Object {
+  meta: NullableObj(
+    Object {}
+  )
+  name?: Primitive(String)
+  tags: Array(
+    Object {
+      id: Primitive(Number)
+    }
+  )
+  userId: Primitive(Number)
+}"
+    );
+
+    inferred_type = merge_types(
+        InferredType::Array(Box::new(InferredType::PrimitiveUnion(vec![
+            PrimitiveType::String,
+            PrimitiveType::Number,
+        ]))),
+        inferred_type,
+    );
+    assert!(
+        display_type_tree(&inferred_type, 1).starts_with(
+            "ComplexUnion(
+    Array(
+      PrimitiveUnion([String, Number])
+    )
+    Object {
+      meta: NullableObj("
+        ),
+        "{}",
+        display_type_tree(&inferred_type, 1)
+    );
+}
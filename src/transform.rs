@@ -0,0 +1,234 @@
+use crate::{
+    inference::{InferenceOptions, merge_types_with_options},
+    types::{InferredType, PrimitiveType},
+};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use stringcase::pascal_case;
+
+/// Replaces `number`/`string` properties whose names end in `Id` or `_id` with branded types.
+///
+/// Each brand name is recorded in `brands` together with the primitive types it wraps.
+pub fn brand_id_fields(
+    inferred_type: &mut InferredType,
+    brands: &mut BTreeMap<String, BTreeSet<PrimitiveType>>,
+) {
+    match inferred_type {
+        InferredType::Object(properties) => {
+            for (key, prop_def) in properties.iter_mut() {
+                if let InferredType::Primitive(p @ (PrimitiveType::Number | PrimitiveType::String)) =
+                    prop_def.r#type
+                    && (key.ends_with("Id") || key.ends_with("_id"))
+                {
+                    let name = pascal_case(key);
+                    brands.entry(name.clone()).or_default().insert(p);
+                    prop_def.r#type = InferredType::Branded {
+                        name,
+                        inner: Box::new(InferredType::Primitive(p)),
+                    };
+                } else {
+                    brand_id_fields(&mut prop_def.r#type, brands);
+                }
+            }
+        }
+        InferredType::Array(item_type)
+        | InferredType::NullableObj(item_type)
+        | InferredType::Optional(item_type) => brand_id_fields(item_type, brands),
+        InferredType::ComplexUnion(members) => {
+            for member in members {
+                brand_id_fields(member, brands);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Removes every object property whose key matches one of `filters`, at any depth.
+pub fn filter_properties(inferred_type: &mut InferredType, filters: &[Regex]) {
+    match inferred_type {
+        InferredType::Object(properties) => {
+            properties.retain(|key, _| !filters.iter().any(|filter| filter.is_match(key)));
+            for prop_def in properties.values_mut() {
+                filter_properties(&mut prop_def.r#type, filters);
+            }
+        }
+        InferredType::Array(item_type)
+        | InferredType::NullableObj(item_type)
+        | InferredType::Optional(item_type) => filter_properties(item_type, filters),
+        InferredType::ComplexUnion(members) => {
+            for member in members {
+                filter_properties(member, filters);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Removes the top-level object properties present in fewer than `threshold` of `total` records,
+/// given how many records each property was present in. Returns the removed properties with the
+/// fraction of records they were present in, ordered by key.
+pub fn prune_rare_properties(
+    inferred_type: &mut InferredType,
+    counts: &HashMap<String, usize>,
+    total: usize,
+    threshold: f64,
+) -> Vec<(String, f64)> {
+    let InferredType::Object(properties) = inferred_type else {
+        return Vec::new();
+    };
+    let mut pruned = Vec::new();
+    properties.retain(|key, _| {
+        let ratio = counts.get(key).copied().unwrap_or_default() as f64 / total as f64;
+        let is_rare = ratio < threshold;
+        if is_rare {
+            pruned.push((key.clone(), ratio));
+        }
+        !is_rare
+    });
+    pruned.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+    pruned
+}
+
+/// Folds objects nested inside an object with the same property names back into it, replacing the
+/// nested copies with a reference to the enclosing type.
+///
+/// `name` is the name of the root type. Nested objects that turn out to be recursive are named
+/// after the path of properties leading to them, e.g. `FileContentTree`, and moved into `named`.
+pub fn fold_recursive_types(
+    inferred_type: &mut InferredType,
+    name: &str,
+    named: &mut BTreeMap<String, InferredType>,
+    options: &InferenceOptions,
+) {
+    fold_into(inferred_type, name, options);
+    fold_children(inferred_type, name, named, options);
+}
+
+fn fold_children(
+    inferred_type: &mut InferredType,
+    name: &str,
+    named: &mut BTreeMap<String, InferredType>,
+    options: &InferenceOptions,
+) {
+    match inferred_type {
+        InferredType::Object(properties) => {
+            for (key, prop_def) in properties.iter_mut() {
+                fold_nested(
+                    &mut prop_def.r#type,
+                    &format!("{name}{}", pascal_case(key)),
+                    named,
+                    options,
+                );
+            }
+        }
+        InferredType::Array(item_type)
+        | InferredType::NullableObj(item_type)
+        | InferredType::Optional(item_type) => fold_children(item_type, name, named, options),
+        InferredType::ComplexUnion(members) => {
+            for member in members {
+                fold_children(member, name, named, options);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn fold_nested(
+    inferred_type: &mut InferredType,
+    name: &str,
+    named: &mut BTreeMap<String, InferredType>,
+    options: &InferenceOptions,
+) {
+    match inferred_type {
+        InferredType::Object(_) => {
+            if fold_into(inferred_type, name, options) {
+                let mut folded =
+                    std::mem::replace(inferred_type, InferredType::Reference(name.to_string()));
+                fold_children(&mut folded, name, named, options);
+                named.insert(name.to_string(), folded);
+            } else {
+                fold_children(inferred_type, name, named, options);
+            }
+        }
+        InferredType::Array(item_type)
+        | InferredType::NullableObj(item_type)
+        | InferredType::Optional(item_type) => fold_nested(item_type, name, named, options),
+        InferredType::ComplexUnion(members) => {
+            for member in members {
+                fold_nested(member, name, named, options);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Merges every descendant of `anchor` with the same property names into it, until none are
+/// left. Returns whether any were found.
+fn fold_into(anchor: &mut InferredType, name: &str, options: &InferenceOptions) -> bool {
+    let keys = match anchor {
+        InferredType::Object(properties) if !properties.is_empty() => {
+            properties.keys().cloned().collect::<BTreeSet<_>>()
+        }
+        _ => return false,
+    };
+
+    let mut found = false;
+    loop {
+        let mut matches = Vec::new();
+        if let InferredType::Object(properties) = anchor {
+            for prop_def in properties.values_mut() {
+                take_matching(&mut prop_def.r#type, &keys, name, &mut matches);
+            }
+        }
+        if matches.is_empty() {
+            return found;
+        }
+        found = true;
+        // Merging can make previously distinct objects match, hence the loop.
+        let merged = matches.into_iter().fold(
+            std::mem::replace(anchor, InferredType::Never),
+            |type1, type2| merge_types_with_options(type1, type2, options),
+        );
+        *anchor = merged;
+    }
+}
+
+/// Replaces every object with exactly `keys` as its property names by a reference to `name`,
+/// collecting the replaced objects into `matches`.
+fn take_matching(
+    inferred_type: &mut InferredType,
+    keys: &BTreeSet<String>,
+    name: &str,
+    matches: &mut Vec<InferredType>,
+) {
+    match inferred_type {
+        InferredType::Object(properties) => {
+            let is_match =
+                properties.len() == keys.len() && properties.keys().all(|key| keys.contains(key));
+            if is_match {
+                let mut taken =
+                    std::mem::replace(inferred_type, InferredType::Reference(name.to_string()));
+                // Deeper levels must be references too, so that they merge with the anchor's.
+                if let InferredType::Object(properties) = &mut taken {
+                    for prop_def in properties.values_mut() {
+                        take_matching(&mut prop_def.r#type, keys, name, matches);
+                    }
+                }
+                matches.push(taken);
+            } else {
+                for prop_def in properties.values_mut() {
+                    take_matching(&mut prop_def.r#type, keys, name, matches);
+                }
+            }
+        }
+        InferredType::Array(item_type)
+        | InferredType::NullableObj(item_type)
+        | InferredType::Optional(item_type) => take_matching(item_type, keys, name, matches),
+        InferredType::ComplexUnion(members) => {
+            for member in members {
+                take_matching(member, keys, name, matches);
+            }
+        }
+        _ => {}
+    }
+}
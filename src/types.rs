@@ -1,5 +1,10 @@
+use anyhow::{Context as _, Result};
 use serde::Deserialize;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+#[cfg(not(feature = "preserve-order"))]
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Deserialize, Debug)]
 pub struct InputData {
@@ -7,6 +12,151 @@ pub struct InputData {
     pub content: String,
 }
 
+impl InputData {
+    /// Extracts a record from a JSON value, reading the string tag from the `tag` field and the
+    /// string payload from the `content` field.
+    pub fn from_json_value(value: &Value, tag: &str, content: &str) -> Result<Self> {
+        Self::from_json_value_with_tag_type(value, tag, TagType::String, content)
+    }
+
+    pub fn from_json_value_with_tag_type(
+        value: &Value,
+        tag: &str,
+        tag_type: TagType,
+        content: &str,
+    ) -> Result<Self> {
+        Self::from_fields(
+            value,
+            (tag, value.get(tag)),
+            tag_type,
+            (content, value.get(content)),
+        )
+    }
+
+    /// Extracts a record, locating the tag and content with RFC 6901 JSON Pointers such as
+    /// `/meta/kind` or `/events/0/payload`.
+    pub fn from_json_pointers(
+        value: &Value,
+        tag_pointer: &str,
+        tag_type: TagType,
+        content_pointer: &str,
+    ) -> Result<Self> {
+        Self::from_fields(
+            value,
+            (tag_pointer, value.pointer(tag_pointer)),
+            tag_type,
+            (content_pointer, value.pointer(content_pointer)),
+        )
+    }
+
+    /// Extracts a record, locating the tag and content with RFC 9535 JSONPath expressions such as
+    /// `$.meta.kind` or `$.payload.events[0]`. When an expression matches several values, the
+    /// first one is used.
+    pub fn from_json_paths(
+        value: &Value,
+        tag_path: &JsonPath,
+        tag_type: TagType,
+        content_path: &JsonPath,
+    ) -> Result<Self> {
+        Self::from_fields(
+            value,
+            (&tag_path.to_string(), tag_path.query(value).first()),
+            tag_type,
+            (&content_path.to_string(), content_path.query(value).first()),
+        )
+    }
+
+    /// Extracts a record for each element of the array in the `list` field, for events that batch
+    /// several payloads such as `{"type": "batch", "content": [{…}, {…}]}`. Each element is the
+    /// payload itself rather than a JSON string.
+    pub fn from_json_list_with_tag_type(
+        value: &Value,
+        tag: &str,
+        tag_type: TagType,
+        list: &str,
+    ) -> Result<Vec<Self>> {
+        let r#type = Self::extract_tag(value, tag, value.get(tag), tag_type)?;
+        let items = value
+            .get(list)
+            .and_then(Value::as_array)
+            .with_context(|| format!("Missing or invalid {list} field in type {type}"))?;
+        Ok(items
+            .iter()
+            .map(|item| InputData {
+                r#type: r#type.clone(),
+                content: item.to_string(),
+            })
+            .collect())
+    }
+
+    fn from_fields(
+        value: &Value,
+        (tag, tag_value): (&str, Option<&Value>),
+        tag_type: TagType,
+        (content, content_value): (&str, Option<&Value>),
+    ) -> Result<Self> {
+        let r#type = Self::extract_tag(value, tag, tag_value, tag_type)?;
+        let content = content_value
+            .and_then(Value::as_str)
+            .with_context(|| format!("Missing or invalid {content} field in type {type}"))?
+            .to_string();
+        Ok(InputData { r#type, content })
+    }
+
+    fn extract_tag(
+        value: &Value,
+        tag: &str,
+        tag_value: Option<&Value>,
+        tag_type: TagType,
+    ) -> Result<String> {
+        tag_value
+            .and_then(|tag_value| tag_type.extract(tag_value))
+            .with_context(|| format!("Missing or invalid {tag} field in value: {value}"))
+    }
+}
+
+/// The JSON type of the discriminant tag field.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TagType {
+    #[default]
+    String,
+    Number,
+    Bool,
+    /// Accepts any JSON value, keyed by its JSON serialization.
+    Any,
+}
+
+impl TagType {
+    /// Extracts the tag value as the string key used to group events.
+    pub fn extract(&self, tag_value: &Value) -> Option<String> {
+        match self {
+            TagType::String => tag_value.as_str().map(str::to_string),
+            TagType::Number => tag_value.as_f64().map(|n| n.to_string()),
+            TagType::Bool => tag_value.as_bool().map(|b| b.to_string()),
+            TagType::Any => Some(tag_value.to_string()),
+        }
+    }
+
+    /// Formats a tag key as a TypeScript literal type.
+    pub fn format_literal(&self, tag_key: &str) -> String {
+        match self {
+            TagType::String => format!("\"{tag_key}\""),
+            // Numbers, booleans and JSON serializations are already valid literals.
+            TagType::Number | TagType::Bool | TagType::Any => tag_key.to_string(),
+        }
+    }
+
+    /// Converts a tag key back into the JSON value it was extracted from.
+    pub fn json_value(&self, tag_key: &str) -> Value {
+        match self {
+            TagType::String => Value::String(tag_key.to_string()),
+            TagType::Number | TagType::Bool | TagType::Any => {
+                serde_json::from_str(tag_key).unwrap_or_else(|_| Value::String(tag_key.to_string()))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PrimitiveType {
     String,
@@ -15,24 +165,48 @@ pub enum PrimitiveType {
     Null,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum InferredType {
     Primitive(PrimitiveType),
     Any,
     Array(Box<InferredType>),
-    Object(HashMap<String, PropertyDefinition>),
+    Object(PropertyMap),
     PrimitiveUnion(Vec<PrimitiveType>),
     PrimitiveTuple(Vec<PrimitiveType>),
+    /// A union of differently shaped tuples, produced when tuples are merged strictly.
+    TupleUnion(Vec<Vec<PrimitiveType>>),
     /// Represents an object type, which can also be an array.
     NullableObj(Box<InferredType>),
+    /// A union of structurally different types, such as an array and an object. Holds at most one
+    /// member of each kind.
+    ComplexUnion(Vec<InferredType>),
+    /// A value that may be `undefined`, emitted as `T | undefined`. Unlike an optional property,
+    /// the key itself is always present.
+    Optional(Box<InferredType>),
     /// Represents the identity element for type union operations.
     Never,
+    /// A nominal type alias, emitted as `inner & { readonly __brand: "name" }`.
+    Branded {
+        name: String,
+        inner: Box<InferredType>,
+    },
+    /// A reference to a named type, used to express recursive types.
+    Reference(String),
 }
 
-#[derive(Debug, PartialEq)]
+/// Object properties keyed by name. With the `preserve-order` feature, keys keep the order in
+/// which they were first observed.
+#[cfg(not(feature = "preserve-order"))]
+pub type PropertyMap = HashMap<String, PropertyDefinition>;
+#[cfg(feature = "preserve-order")]
+pub type PropertyMap = indexmap::IndexMap<String, PropertyDefinition>;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct PropertyDefinition {
     pub r#type: InferredType,
     pub optional: bool,
+    /// The first distinct observed values, if examples are being collected.
+    pub examples: Vec<Value>,
 }
 
 impl PrimitiveType {
@@ -45,3 +219,177 @@ impl PrimitiveType {
         }
     }
 }
+
+impl InferredType {
+    /// Returns whether this type or any type nested in it is `Any`.
+    pub fn contains_any(&self) -> bool {
+        self.any_node(&|node| matches!(node, InferredType::Any))
+    }
+
+    /// Returns whether this type or any type nested in it is `Never`.
+    pub fn contains_never(&self) -> bool {
+        self.any_node(&|node| matches!(node, InferredType::Never))
+    }
+
+    /// Returns whether this type accepts `null` itself, without looking into nested types.
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            InferredType::NullableObj(_) | InferredType::Primitive(PrimitiveType::Null) => true,
+            InferredType::PrimitiveUnion(types) => types.contains(&PrimitiveType::Null),
+            InferredType::ComplexUnion(members) => members.iter().any(InferredType::is_nullable),
+            InferredType::Optional(inner) | InferredType::Branded { inner, .. } => {
+                inner.is_nullable()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether this type or any type nested in it is `Optional` or has an optional
+    /// property.
+    pub fn is_optional_anywhere(&self) -> bool {
+        self.any_node(&|node| match node {
+            InferredType::Optional(_) => true,
+            InferredType::Object(properties) => {
+                properties.values().any(|prop_def| prop_def.optional)
+            }
+            _ => false,
+        })
+    }
+
+    /// Returns whether `predicate` holds for this type or any type nested in it.
+    fn any_node(&self, predicate: &impl Fn(&InferredType) -> bool) -> bool {
+        if predicate(self) {
+            return true;
+        }
+        match self {
+            InferredType::Array(inner)
+            | InferredType::NullableObj(inner)
+            | InferredType::Optional(inner)
+            | InferredType::Branded { inner, .. } => inner.any_node(predicate),
+            InferredType::Object(properties) => properties
+                .values()
+                .any(|prop_def| prop_def.r#type.any_node(predicate)),
+            InferredType::ComplexUnion(members) => {
+                members.iter().any(|member| member.any_node(predicate))
+            }
+            InferredType::Primitive(_)
+            | InferredType::Any
+            | InferredType::PrimitiveUnion(_)
+            | InferredType::PrimitiveTuple(_)
+            | InferredType::TupleUnion(_)
+            | InferredType::Never
+            | InferredType::Reference(_) => false,
+        }
+    }
+
+    /// Returns the path of every `Any` within this type, such as `$.user.tags[]`.
+    pub fn any_paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.collect_any_paths(&mut "$".to_string(), &mut paths);
+        paths
+    }
+
+    fn collect_any_paths(&self, path: &mut String, paths: &mut Vec<String>) {
+        match self {
+            InferredType::Any => paths.push(path.clone()),
+            InferredType::Array(item_type) => {
+                path.push_str("[]");
+                item_type.collect_any_paths(path, paths);
+                path.truncate(path.len() - "[]".len());
+            }
+            InferredType::Object(properties) => {
+                let mut sorted = properties.iter().collect::<Vec<_>>();
+                sorted.sort_by_key(|(key, _)| *key);
+                for (key, prop_def) in sorted {
+                    let len = path.len();
+                    path.push('.');
+                    path.push_str(key);
+                    prop_def.r#type.collect_any_paths(path, paths);
+                    path.truncate(len);
+                }
+            }
+            InferredType::NullableObj(inner)
+            | InferredType::Optional(inner)
+            | InferredType::Branded { inner, .. } => inner.collect_any_paths(path, paths),
+            InferredType::ComplexUnion(members) => {
+                for member in members {
+                    member.collect_any_paths(path, paths);
+                }
+            }
+            InferredType::Primitive(_)
+            | InferredType::PrimitiveUnion(_)
+            | InferredType::PrimitiveTuple(_)
+            | InferredType::TupleUnion(_)
+            | InferredType::Never
+            | InferredType::Reference(_) => {}
+        }
+    }
+}
+
+/// Compact, human-readable rendering used for debug output and error messages.
+/// Unlike the TypeScript formatter, this never emits newlines.
+impl fmt::Display for InferredType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InferredType::Primitive(prim_type) => f.write_str(prim_type.as_str()),
+            InferredType::Any => f.write_str("any"),
+            InferredType::Never => f.write_str("never"),
+            InferredType::Array(item_type) => match **item_type {
+                InferredType::PrimitiveUnion(_)
+                | InferredType::TupleUnion(_)
+                | InferredType::NullableObj(_)
+                | InferredType::Optional(_)
+                | InferredType::ComplexUnion(_) => {
+                    write!(f, "({item_type})[]")
+                }
+                _ => write!(f, "{item_type}[]"),
+            },
+            InferredType::Object(properties) => {
+                if properties.is_empty() {
+                    return f.write_str("{}");
+                }
+                let mut sorted = properties.iter().collect::<Vec<_>>();
+                sorted.sort_by_key(|(key, _)| *key);
+                f.write_str("{ ")?;
+                for (i, (key, prop_def)) in sorted.into_iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    let optional_marker = if prop_def.optional { "?" } else { "" };
+                    write!(f, "{key}{optional_marker}: {}", prop_def.r#type)?;
+                }
+                f.write_str(" }")
+            }
+            InferredType::PrimitiveUnion(types) => {
+                let type_strings: Vec<&str> = types.iter().map(PrimitiveType::as_str).collect();
+                f.write_str(&type_strings.join(" | "))
+            }
+            InferredType::PrimitiveTuple(types) => {
+                let type_strings: Vec<&str> = types.iter().map(PrimitiveType::as_str).collect();
+                write!(f, "[{}]", type_strings.join(", "))
+            }
+            InferredType::TupleUnion(tuples) => {
+                for (i, types) in tuples.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" | ")?;
+                    }
+                    let type_strings: Vec<&str> = types.iter().map(PrimitiveType::as_str).collect();
+                    write!(f, "[{}]", type_strings.join(", "))?;
+                }
+                Ok(())
+            }
+            InferredType::ComplexUnion(members) => {
+                for (i, member) in members.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" | ")?;
+                    }
+                    write!(f, "{member}")?;
+                }
+                Ok(())
+            }
+            InferredType::NullableObj(obj) => write!(f, "{obj} | null"),
+            InferredType::Optional(inner) => write!(f, "{inner} | undefined"),
+            InferredType::Branded { name, .. } | InferredType::Reference(name) => f.write_str(name),
+        }
+    }
+}